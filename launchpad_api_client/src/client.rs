@@ -1,10 +1,83 @@
 use crate::{
-    HTTPClient, LaunchpadError,
-    fake::{fake_bug, fake_bug_tasks_page_1, fake_bug_tasks_page_2, fake_project},
+    HTTPClient, LAUNCHPAD_API_BASE_URL, LaunchpadError,
+    fake::{
+        fake_bug, fake_bug_activity_page_1, fake_bug_activity_page_2, fake_bug_attachments,
+        fake_bug_messages, fake_bug_tasks, fake_bug_tasks_page_1, fake_bug_tasks_page_2,
+        fake_person, fake_project,
+    },
 };
-use reqwest::Client;
+use reqwest::{Client, StatusCode};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use tracing::warn;
 #[derive(Debug)]
-pub struct ReqwestClient(Client);
+pub struct ReqwestClient {
+    client: Client,
+    oauth: Option<OAuthCredentials>,
+    base_url: String,
+}
+
+/// OAuth1 access token of a Launchpad account, requests are signed with the
+/// PLAINTEXT method that Launchpad expects.
+#[derive(Debug, Clone)]
+pub struct OAuthCredentials {
+    consumer_key: String,
+    token: String,
+    token_secret: String,
+}
+
+impl OAuthCredentials {
+    pub fn new(consumer_key: &str, token: &str, token_secret: &str) -> Self {
+        Self {
+            consumer_key: consumer_key.to_string(),
+            token: token.to_string(),
+            token_secret: token_secret.to_string(),
+        }
+    }
+
+    /// Builds the `Authorization` header value for a given realm, nonce and
+    /// timestamp.
+    fn authorization(&self, realm: &str, nonce: &str, timestamp: u64) -> String {
+        // PLAINTEXT signature is "consumer_secret&token_secret", Launchpad
+        // consumers have an empty secret.
+        let signature = format!("&{}", self.token_secret);
+        format!(
+            "OAuth realm=\"{realm}\", \
+             oauth_consumer_key=\"{}\", \
+             oauth_token=\"{}\", \
+             oauth_signature_method=\"PLAINTEXT\", \
+             oauth_signature=\"{}\", \
+             oauth_timestamp=\"{timestamp}\", \
+             oauth_nonce=\"{}\", \
+             oauth_version=\"1.0\"",
+            percent_encode(&self.consumer_key),
+            percent_encode(&self.token),
+            percent_encode(&signature),
+            percent_encode(nonce),
+        )
+    }
+}
+
+/// Returns the OAuth realm of a Launchpad instance, the root of its API host,
+/// e.g. "https://api.launchpad.net/" for "https://api.launchpad.net/1.0".
+fn oauth_realm(base_url: &str) -> String {
+    match reqwest::Url::parse(base_url) {
+        Ok(url) => format!("{}/", url.origin().ascii_serialization()),
+        Err(_) => base_url.to_string(),
+    }
+}
+
+/// Percent encodes everything but the unreserved characters, as OAuth1 requires.
+fn percent_encode(value: &str) -> String {
+    value
+        .bytes()
+        .map(|b| match b {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'.' | b'_' | b'~' => {
+                (b as char).to_string()
+            }
+            _ => format!("%{b:02X}"),
+        })
+        .collect()
+}
 
 #[derive(Debug)]
 pub(crate) struct FakeClient;
@@ -12,7 +85,49 @@ pub(crate) struct FakeClient;
 #[allow(dead_code)]
 impl ReqwestClient {
     pub fn new() -> Self {
-        Self(reqwest::Client::new())
+        Self {
+            client: reqwest::Client::new(),
+            oauth: None,
+            base_url: LAUNCHPAD_API_BASE_URL.to_string(),
+        }
+    }
+
+    /// Creates a client signing its requests with the given OAuth1 access token.
+    pub fn with_oauth(consumer_key: &str, token: &str, token_secret: &str) -> Self {
+        Self {
+            client: reqwest::Client::new(),
+            oauth: Some(OAuthCredentials::new(consumer_key, token, token_secret)),
+            base_url: LAUNCHPAD_API_BASE_URL.to_string(),
+        }
+    }
+
+    /// Targets another Launchpad instance, like
+    /// "https://api.qastaging.launchpad.net/1.0", or a test server.
+    pub fn with_base_url(mut self, base_url: &str) -> Self {
+        self.base_url = base_url.trim_end_matches('/').to_string();
+        self
+    }
+
+    /// Returns true when the requests are signed, as changing anything on
+    /// Launchpad requires.
+    pub fn is_authenticated(&self) -> bool {
+        self.oauth.is_some()
+    }
+
+    /// Adds the OAuth1 header when credentials are set, requests stay
+    /// anonymous otherwise.
+    fn sign(&self, request: reqwest::RequestBuilder) -> reqwest::RequestBuilder {
+        let Some(oauth) = &self.oauth else {
+            return request;
+        };
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default();
+        let nonce = format!("{}{}", now.as_secs(), now.subsec_nanos());
+        request.header(
+            reqwest::header::AUTHORIZATION,
+            oauth.authorization(&oauth_realm(&self.base_url), &nonce, now.as_secs()),
+        )
     }
 }
 
@@ -24,8 +139,184 @@ impl Default for ReqwestClient {
 
 impl HTTPClient for ReqwestClient {
     async fn get(&self, url: &str) -> Result<String, LaunchpadError> {
-        let res = &self.0.get(url).send().await?.text().await?;
-        Ok(res.to_string())
+        let response = self.sign(self.client.get(url)).send().await?;
+        let status = response.status();
+        if !status.is_success() {
+            return Err(LaunchpadError::HttpStatus {
+                status: status.as_u16(),
+                url: url.to_string(),
+            });
+        }
+        Ok(response.text().await?)
+    }
+
+    async fn post(&self, url: &str, form: &[(&str, &str)]) -> Result<String, LaunchpadError> {
+        let response = self.sign(self.client.post(url)).form(form).send().await?;
+        let status = response.status();
+        if !status.is_success() {
+            return Err(LaunchpadError::HttpStatus {
+                status: status.as_u16(),
+                url: url.to_string(),
+            });
+        }
+        Ok(response.text().await?)
+    }
+
+    fn base_url(&self) -> &str {
+        &self.base_url
+    }
+
+    async fn patch(&self, url: &str, body: &str) -> Result<String, LaunchpadError> {
+        let response = self
+            .sign(self.client.patch(url))
+            .header(reqwest::header::CONTENT_TYPE, "application/json")
+            .body(body.to_string())
+            .send()
+            .await?;
+        let status = response.status();
+        if !status.is_success() {
+            return Err(LaunchpadError::HttpStatus {
+                status: status.as_u16(),
+                url: url.to_string(),
+            });
+        }
+        Ok(response.text().await?)
+    }
+}
+
+/// Retries the requests of another client on transient failures, waiting
+/// twice as long before each new attempt.
+#[derive(Debug)]
+pub struct RetryClient<C> {
+    inner: C,
+    max_attempts: u32,
+    base_delay: Duration,
+}
+
+impl<C: HTTPClient> RetryClient<C> {
+    pub fn new(inner: C) -> Self {
+        Self {
+            inner,
+            max_attempts: 3,
+            base_delay: Duration::from_millis(500),
+        }
+    }
+
+    /// Sets how many times a request is sent at most, including the first one.
+    pub fn max_attempts(mut self, max_attempts: u32) -> Self {
+        self.max_attempts = max_attempts.max(1);
+        self
+    }
+
+    /// Sets the delay before the first retry.
+    pub fn base_delay(mut self, base_delay: Duration) -> Self {
+        self.base_delay = base_delay;
+        self
+    }
+}
+
+/// Returns true for the failures worth retrying: network errors, timeouts,
+/// server errors and rate limiting. Other client errors like 404 are final.
+fn is_transient(error: &LaunchpadError) -> bool {
+    match error {
+        LaunchpadError::HttpRequest(e) => e.is_connect() || e.is_timeout() || e.is_request(),
+        LaunchpadError::Timeout { .. } => true,
+        LaunchpadError::HttpStatus { status, .. } => {
+            *status >= 500 || *status == StatusCode::TOO_MANY_REQUESTS.as_u16()
+        }
+        _ => false,
+    }
+}
+
+impl<C: HTTPClient + Sync> HTTPClient for RetryClient<C> {
+    async fn get(&self, url: &str) -> Result<String, LaunchpadError> {
+        let mut delay = self.base_delay;
+        let mut attempt = 1;
+        loop {
+            match self.inner.get(url).await {
+                Err(e) if attempt < self.max_attempts && is_transient(&e) => {
+                    warn!("Attempt {attempt} to get \"{url}\" failed, retrying: {e}");
+                    tokio::time::sleep(delay).await;
+                    delay *= 2;
+                    attempt += 1;
+                }
+                result => return result,
+            }
+        }
+    }
+
+    /// Posts are not idempotent, a failed one could have been applied so it
+    /// is never sent again.
+    async fn post(&self, url: &str, form: &[(&str, &str)]) -> Result<String, LaunchpadError> {
+        self.inner.post(url, form).await
+    }
+
+    /// Not sent again either, a status changed meanwhile by someone else
+    /// would be overwritten.
+    async fn patch(&self, url: &str, body: &str) -> Result<String, LaunchpadError> {
+        self.inner.patch(url, body).await
+    }
+
+    fn base_url(&self) -> &str {
+        self.inner.base_url()
+    }
+}
+
+/// Time given to Launchpad to answer a request.
+pub const DEFAULT_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Gives up on the requests of another client that take too long, so a
+/// stalled connection does not hang the caller.
+#[derive(Debug)]
+pub struct TimeoutClient<C> {
+    inner: C,
+    timeout: Duration,
+}
+
+impl<C: HTTPClient> TimeoutClient<C> {
+    pub fn new(inner: C) -> Self {
+        Self {
+            inner,
+            timeout: DEFAULT_TIMEOUT,
+        }
+    }
+
+    /// Sets how long a request may take.
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = timeout;
+        self
+    }
+
+    fn timeout_error(&self, url: &str) -> LaunchpadError {
+        warn!("Request to \"{url}\" timed out");
+        LaunchpadError::Timeout {
+            url: url.to_string(),
+            timeout: self.timeout,
+        }
+    }
+}
+
+impl<C: HTTPClient + Sync> HTTPClient for TimeoutClient<C> {
+    async fn get(&self, url: &str) -> Result<String, LaunchpadError> {
+        tokio::time::timeout(self.timeout, self.inner.get(url))
+            .await
+            .unwrap_or_else(|_| Err(self.timeout_error(url)))
+    }
+
+    async fn post(&self, url: &str, form: &[(&str, &str)]) -> Result<String, LaunchpadError> {
+        tokio::time::timeout(self.timeout, self.inner.post(url, form))
+            .await
+            .unwrap_or_else(|_| Err(self.timeout_error(url)))
+    }
+
+    async fn patch(&self, url: &str, body: &str) -> Result<String, LaunchpadError> {
+        tokio::time::timeout(self.timeout, self.inner.patch(url, body))
+            .await
+            .unwrap_or_else(|_| Err(self.timeout_error(url)))
+    }
+
+    fn base_url(&self) -> &str {
+        self.inner.base_url()
     }
 }
 
@@ -48,6 +339,13 @@ impl HTTPClient for FakeClient {
             "https://api.launchpad.net/1.0/bugs/5000" => {
                 Ok(fake_bug(url).replace("self_link", "dself_link"))
             }
+            "https://api.launchpad.net/1.0/bugs/6000" => Ok(fake_project()),
+            "https://api.launchpad.net/1.0/bugs/7000" => Ok("Object: <lp.bugs.model.bug.BugSet object at 0x7f92e7ae4730>, name: '7000'".to_string()),
+            "https://api.launchpad.net/1.0/bugs/404" => Err(LaunchpadError::HttpStatus {
+                status: 404,
+                url: url.to_string(),
+            }),
+            "https://api.launchpad.net/1.0/bugs/2093869/bug_tasks" => Ok(fake_bug_tasks()),
             "https://api.launchpad.net/1.0/nova" => Ok(fake_project()),
             "https://api.launchpad.net/1.0/nova?ws.op=searchTasks&status=New" => Ok(fake_bug_tasks_page_1()),
             "https://api.launchpad.net/1.0/nova?status=New&ws.op=searchTasks&ws.size=2&memo=2&ws.start=2" => Ok(fake_bug_tasks_page_2()),
@@ -56,15 +354,106 @@ impl HTTPClient for FakeClient {
             // "https://api.launchpad.net/1.0/notaproject" => Err(LaunchpadError::Deserialization(
             //     serde_json::from_str::<serde_json::Value>("invalid json").unwrap_err(),
             // )),
+            url if url.starts_with("https://api.launchpad.net/1.0/~") => Ok(fake_person(url)),
+            url if url.ends_with("/messages") => Ok(fake_bug_messages()),
+            url if url.ends_with("/attachments") => Ok(fake_bug_attachments()),
+            url if url.ends_with("/activity") => Ok(fake_bug_activity_page_1()),
+            url if url.ends_with("/activity?ws.size=2&memo=2&ws.start=2") => {
+                Ok(fake_bug_activity_page_2())
+            }
             _ => Ok(fake_bug(url)),
         }
     }
+
+    async fn post(&self, url: &str, _form: &[(&str, &str)]) -> Result<String, LaunchpadError> {
+        match url {
+            "https://api.launchpad.net/1.0/bugs/401" => Err(LaunchpadError::HttpStatus {
+                status: 401,
+                url: url.to_string(),
+            }),
+            _ => Ok(String::new()),
+        }
+    }
+
+    async fn patch(&self, url: &str, _body: &str) -> Result<String, LaunchpadError> {
+        match url {
+            "https://api.launchpad.net/1.0/nova/+bug/401" => Err(LaunchpadError::HttpStatus {
+                status: 401,
+                url: url.to_string(),
+            }),
+            _ => Ok(String::new()),
+        }
+    }
 }
 
 // --- Test Module ---
 #[cfg(test)]
 mod tests {
     use super::*;
+    use std::sync::atomic::{AtomicU32, Ordering};
+
+    #[test]
+    fn test_oauth_authorization_header() {
+        let oauth = OAuthCredentials::new("ratatai", "token", "s3cr3t/+");
+
+        assert_eq!(
+            oauth.authorization("https://api.launchpad.net/", "4242", 1700000000),
+            "OAuth realm=\"https://api.launchpad.net/\", \
+             oauth_consumer_key=\"ratatai\", \
+             oauth_token=\"token\", \
+             oauth_signature_method=\"PLAINTEXT\", \
+             oauth_signature=\"%26s3cr3t%2F%2B\", \
+             oauth_timestamp=\"1700000000\", \
+             oauth_nonce=\"4242\", \
+             oauth_version=\"1.0\""
+        );
+    }
+
+    #[test]
+    fn test_anonymous_client_is_not_signed() {
+        let client = ReqwestClient::new();
+        let request = client
+            .sign(client.client.get("https://api.launchpad.net/1.0/bugs/1"))
+            .build()
+            .unwrap();
+        assert!(request.headers().get("authorization").is_none());
+
+        let client = ReqwestClient::with_oauth("ratatai", "token", "secret");
+        let request = client
+            .sign(client.client.get("https://api.launchpad.net/1.0/bugs/1"))
+            .build()
+            .unwrap();
+        let header = request.headers()["authorization"].to_str().unwrap();
+        assert!(header.starts_with("OAuth realm="));
+        assert!(header.contains("oauth_signature=\"%26secret\""));
+        assert!(client.is_authenticated());
+        assert!(!ReqwestClient::new().is_authenticated());
+    }
+
+    #[test]
+    fn test_oauth_realm_follows_base_url() {
+        assert_eq!(
+            oauth_realm(LAUNCHPAD_API_BASE_URL),
+            "https://api.launchpad.net/"
+        );
+        assert_eq!(
+            oauth_realm("http://127.0.0.1:8080/1.0"),
+            "http://127.0.0.1:8080/"
+        );
+
+        let client = ReqwestClient::with_oauth("ratatai", "token", "secret")
+            .with_base_url("https://api.qastaging.launchpad.net/1.0/");
+        let request = client
+            .sign(
+                client
+                    .client
+                    .get("https://api.qastaging.launchpad.net/1.0/bugs/1"),
+            )
+            .build()
+            .unwrap();
+        let header = request.headers()["authorization"].to_str().unwrap();
+        assert!(header.starts_with("OAuth realm=\"https://api.qastaging.launchpad.net/\""));
+    }
 
     #[tokio::test]
     async fn test_get_invalid_url_error() {
@@ -99,6 +488,201 @@ mod tests {
             &error
         );
     }
+    /// Serves a single request with the given status line, returning the url
+    /// of the server.
+    async fn serve_status(status_line: &'static str) -> String {
+        use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}/bugs/1", listener.local_addr().unwrap());
+        tokio::spawn(async move {
+            let (mut stream, _) = listener.accept().await.unwrap();
+            let mut request = [0; 1024];
+            let _ = stream.read(&mut request).await;
+            let response = format!("HTTP/1.1 {status_line}\r\nContent-Length: 0\r\n\r\n");
+            stream.write_all(response.as_bytes()).await.unwrap();
+        });
+        url
+    }
+
+    /// Answers a single request with a json document, returning the url of
+    /// the server and a handle giving the request line received.
+    async fn serve_json(body: String) -> (String, tokio::task::JoinHandle<String>) {
+        use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}", listener.local_addr().unwrap());
+        let request = tokio::spawn(async move {
+            let (mut stream, _) = listener.accept().await.unwrap();
+            let mut request = [0; 1024];
+            let n = stream.read(&mut request).await.unwrap();
+            let response = format!(
+                "HTTP/1.1 200 OK\r\nContent-Type: application/json\r\nContent-Length: {}\r\n\r\n{body}",
+                body.len()
+            );
+            stream.write_all(response.as_bytes()).await.unwrap();
+            let request = String::from_utf8_lossy(&request[..n]);
+            request.lines().next().unwrap_or_default().to_string()
+        });
+        (url, request)
+    }
+
+    #[tokio::test]
+    async fn test_base_url() {
+        assert_eq!(ReqwestClient::new().base_url(), LAUNCHPAD_API_BASE_URL);
+
+        let (url, request) = serve_json(fake_bug("https://api.launchpad.net/1.0/bugs/42")).await;
+        let client = ReqwestClient::new().with_base_url(&format!("{url}/"));
+        assert_eq!(client.base_url(), url);
+        let client = RetryClient::new(TimeoutClient::new(client));
+        assert_eq!(client.base_url(), url);
+
+        let bug = crate::get_bug(&client, 42).await.unwrap();
+        assert_eq!(bug.id, 42);
+        assert_eq!(request.await.unwrap(), "GET /bugs/42 HTTP/1.1");
+    }
+
+    #[tokio::test]
+    async fn test_get_maps_http_status() {
+        for (status_line, status, transient) in [
+            ("404 Not Found", 404, false),
+            ("500 Internal Server Error", 500, true),
+        ] {
+            let url = serve_status(status_line).await;
+
+            let error = ReqwestClient::new().get(&url).await.unwrap_err();
+
+            assert!(
+                matches!(&error, LaunchpadError::HttpStatus { status: s, url: u } if *s == status && *u == url),
+                "unexpected error {error:?}"
+            );
+            assert_eq!(is_transient(&error), transient);
+        }
+    }
+
+    /// Fails with the given status a number of times before succeeding.
+    struct FlakyClient {
+        status: u16,
+        failures: AtomicU32,
+        calls: AtomicU32,
+    }
+
+    impl FlakyClient {
+        fn new(status: u16, failures: u32) -> Self {
+            Self {
+                status,
+                failures: AtomicU32::new(failures),
+                calls: AtomicU32::new(0),
+            }
+        }
+    }
+
+    impl HTTPClient for FlakyClient {
+        async fn get(&self, url: &str) -> Result<String, LaunchpadError> {
+            self.calls.fetch_add(1, Ordering::SeqCst);
+            if self.failures.load(Ordering::SeqCst) > 0 {
+                self.failures.fetch_sub(1, Ordering::SeqCst);
+                return Err(LaunchpadError::HttpStatus {
+                    status: self.status,
+                    url: url.to_string(),
+                });
+            }
+            Ok("ok".to_string())
+        }
+
+        async fn post(&self, url: &str, _form: &[(&str, &str)]) -> Result<String, LaunchpadError> {
+            self.get(url).await
+        }
+
+        async fn patch(&self, url: &str, _body: &str) -> Result<String, LaunchpadError> {
+            self.get(url).await
+        }
+    }
+
+    #[tokio::test]
+    async fn test_retry_client_retries_transient_errors() {
+        let client = RetryClient::new(FlakyClient::new(503, 2)).base_delay(Duration::ZERO);
+
+        let result = client.get("http://truc/bidule").await;
+
+        assert_eq!(result.unwrap(), "ok");
+        assert_eq!(client.inner.calls.load(Ordering::SeqCst), 3);
+    }
+
+    #[tokio::test]
+    async fn test_retry_client_gives_up() {
+        let client = RetryClient::new(FlakyClient::new(429, 5))
+            .max_attempts(2)
+            .base_delay(Duration::ZERO);
+
+        let result = client.get("http://truc/bidule").await;
+
+        assert!(matches!(
+            result,
+            Err(LaunchpadError::HttpStatus { status: 429, .. })
+        ));
+        assert_eq!(client.inner.calls.load(Ordering::SeqCst), 2);
+    }
+
+    /// Answers after a delay.
+    struct SlowClient(Duration);
+
+    impl HTTPClient for SlowClient {
+        async fn get(&self, _url: &str) -> Result<String, LaunchpadError> {
+            tokio::time::sleep(self.0).await;
+            Ok("ok".to_string())
+        }
+
+        async fn post(&self, url: &str, _form: &[(&str, &str)]) -> Result<String, LaunchpadError> {
+            self.get(url).await
+        }
+
+        async fn patch(&self, url: &str, _body: &str) -> Result<String, LaunchpadError> {
+            self.get(url).await
+        }
+    }
+
+    #[tokio::test]
+    async fn test_timeout_client() {
+        let timeout = Duration::from_millis(10);
+        let client = TimeoutClient::new(SlowClient(Duration::from_secs(60))).timeout(timeout);
+
+        let result = client.get("http://truc/bidule").await;
+
+        assert!(matches!(
+            &result,
+            Err(LaunchpadError::Timeout { timeout: t, .. }) if *t == timeout
+        ));
+        assert!(is_transient(&result.unwrap_err()));
+
+        let client = TimeoutClient::new(SlowClient(Duration::ZERO));
+        assert_eq!(client.timeout, DEFAULT_TIMEOUT);
+        assert_eq!(client.post("http://truc/bidule", &[]).await.unwrap(), "ok");
+    }
+
+    #[tokio::test]
+    async fn test_retry_client_does_not_retry_not_found() {
+        let client = RetryClient::new(FlakyClient::new(404, 1)).base_delay(Duration::ZERO);
+
+        let result = client.get("http://truc/bidule").await;
+
+        assert!(matches!(
+            result,
+            Err(LaunchpadError::HttpStatus { status: 404, .. })
+        ));
+        assert_eq!(client.inner.calls.load(Ordering::SeqCst), 1);
+    }
+
+    #[tokio::test]
+    async fn test_retry_client_does_not_retry_posts() {
+        let client = RetryClient::new(FlakyClient::new(503, 1)).base_delay(Duration::ZERO);
+
+        let result = client.post("http://truc/bidule", &[]).await;
+
+        assert!(result.is_err());
+        assert_eq!(client.inner.calls.load(Ordering::SeqCst), 1);
+    }
+
     #[tokio::test]
     async fn test_fake_client() {
         let client = FakeClient::new();
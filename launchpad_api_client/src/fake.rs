@@ -191,10 +191,94 @@ pub(crate) fn fake_bug(url: &str) -> String {
             }"#}.to_string().replace("https://api.launchpad.net/1.0/bugs/2066153", url).replace("2066153", url.split('/').next_back().unwrap())
 }
 
+pub(crate) fn fake_person(url: &str) -> String {
+    indoc! {r#"{
+            "self_link": "https://api.launchpad.net/1.0/~cruzfjf",
+            "web_link": "https://launchpad.net/~cruzfjf",
+            "resource_type_link": "https://api.launchpad.net/1.0/#person",
+            "name": "cruzfjf",
+            "display_name": "Cruz",
+            "is_team": false,
+            "is_valid": true,
+            "karma": 0,
+            "hide_email_addresses": true,
+            "private": false,
+            "time_zone": "UTC",
+            "date_created": "2024-05-20T08:46:14.187358+00:00",
+            "http_etag": "\"3b1bb8bb6b6d1e0c15b4aa9bd6b9a1b0b4ad4c6c-2a4c1e8e3df5ab1f01b25cd26c9b1e1dd6a3f0e4\""
+            }"#}.to_string().replace("https://api.launchpad.net/1.0/~cruzfjf", url)
+}
+
 // pub(crate) fn fake_bug_tasks_page(url: &str) -> String {
 //     indoc! {r#""#}.to_string()
 // }
 
+pub(crate) fn fake_bug_messages() -> String {
+    indoc! {r#"{
+            "start": 0,
+            "total_size": 2,
+            "entries": [
+                {
+                "self_link": "https://api.launchpad.net/1.0/nova/+bug/2066153/comments/0",
+                "web_link": "https://bugs.launchpad.net/nova/+bug/2066153/comments/0",
+                "resource_type_link": "https://api.launchpad.net/1.0/#message",
+                "subject": "Install and configure controller node for Red Hat Enterprise Linux and CentOS in nova",
+                "content": "I have followed all the installation steps, but I can't start either the Nova Scheduler or the Nova Conductor.",
+                "date_created": "2024-05-20T08:50:45.545421+00:00",
+                "date_deleted": null,
+                "date_last_edited": null,
+                "owner_link": "https://api.launchpad.net/1.0/~cruzfjf",
+                "parent_link": null,
+                "http_etag": "\"1f7c0e0b4e2fb27b5e5f5e6c9b8a7d6c5b4a3f2e-6b1ab1b0a35b0c7f3e2d1c0b9a8f7e6d5c4b3a2f\""
+                },
+                {
+                "self_link": "https://api.launchpad.net/1.0/nova/+bug/2066153/comments/1",
+                "web_link": "https://bugs.launchpad.net/nova/+bug/2066153/comments/1",
+                "resource_type_link": "https://api.launchpad.net/1.0/#message",
+                "subject": "Re: Install and configure controller node for Red Hat Enterprise Linux and CentOS in nova",
+                "content": "which release are you installing and what errors are in the scheduler and conductor logs?",
+                "date_created": "2024-05-21T10:12:03.114532+00:00",
+                "date_deleted": null,
+                "date_last_edited": null,
+                "owner_link": "https://api.launchpad.net/1.0/~sean-k-mooney",
+                "parent_link": null,
+                "http_etag": "\"5c2d9e8f7a6b5c4d3e2f1a0b9c8d7e6f5a4b3c2d-7e6f5a4b3c2d1e0f9a8b7c6d5e4f3a2b1c0d9e8f\""
+                }
+            ]
+            }"#}.to_string()
+}
+
+pub(crate) fn fake_bug_tasks() -> String {
+    indoc! {r#"{
+            "start": 0,
+            "total_size": 1,
+            "entries": [
+                {
+                "self_link": "https://api.launchpad.net/1.0/nova/+bug/2093869",
+                "web_link": "https://bugs.launchpad.net/nova/+bug/2093869",
+                "resource_type_link": "https://api.launchpad.net/1.0/#bug_task",
+                "bug_link": "https://api.launchpad.net/1.0/bugs/2093869",
+                "milestone_link": null,
+                "status": "Triaged",
+                "importance": "High",
+                "assignee_link": null,
+                "bug_target_display_name": "OpenStack Compute (nova)",
+                "bug_target_name": "nova",
+                "bug_watch_link": null,
+                "date_assigned": null,
+                "date_created": "2025-01-13T08:46:25.105013+00:00",
+                "date_triaged": "2025-01-14T10:02:11.324518+00:00",
+                "owner_link": "https://api.launchpad.net/1.0/~auniyal",
+                "target_link": "https://api.launchpad.net/1.0/nova",
+                "title": "Bug #2093869 in OpenStack Compute (nova): \"update conflict: Inventory for 'DISK_GB' on resource provider 'r_p-id' in use\"",
+                "related_tasks_collection_link": "https://api.launchpad.net/1.0/nova/+bug/2093869/related_tasks",
+                "is_complete": false,
+                "http_etag": "\"0c3fa1f0e5d1c2b4a7e8f9d0c1b2a3f4e5d6c7b8-95de74425077a72215679530b17ac6df67ae008d\""
+                }
+            ]
+            }"#}.to_string()
+}
+
 pub(crate) fn fake_bug_tasks_page_1() -> String {
     indoc! {r#"{
             "start": 0,
@@ -336,3 +420,73 @@ pub(crate) fn fake_bug_tasks_page_2() -> String {
         }  ]
     }"#}.to_string()
 }
+
+pub(crate) fn fake_bug_attachments() -> String {
+    indoc! {r#"{
+            "start": 0,
+            "total_size": 1,
+            "entries": [
+                {
+                "self_link": "https://api.launchpad.net/1.0/bugs/2066153/+attachment/5781234",
+                "web_link": "https://bugs.launchpad.net/nova/+bug/2066153/+attachment/5781234",
+                "resource_type_link": "https://api.launchpad.net/1.0/#bug_attachment",
+                "title": "nova-scheduler.log",
+                "type": "Unspecified",
+                "data_link": "https://api.launchpad.net/1.0/bugs/2066153/+attachment/5781234/data",
+                "message_link": "https://api.launchpad.net/1.0/nova/+bug/2066153/comments/0",
+                "bug_link": "https://api.launchpad.net/1.0/bugs/2066153",
+                "http_etag": "\"8d3c2b1a0f9e8d7c6b5a4f3e2d1c0b9a8f7e6d5c-4b3a2f1e0d9c8b7a6f5e4d3c2b1a0f9e8d7c6b5a\""
+                }
+            ]
+            }"#}.to_string()
+}
+
+pub(crate) fn fake_bug_activity_page_1() -> String {
+    indoc! {r#"{
+            "start": 0,
+            "total_size": 3,
+            "next_collection_link": "https://api.launchpad.net/1.0/bugs/2066153/activity?ws.size=2&memo=2&ws.start=2",
+            "entries": [
+                {
+                "resource_type_link": "https://api.launchpad.net/1.0/#bug_activity",
+                "bug_link": "https://api.launchpad.net/1.0/bugs/2066153",
+                "datechanged": "2024-05-24T15:20:20.721018+00:00",
+                "person_link": "https://api.launchpad.net/1.0/~stuts",
+                "whatchanged": "bug",
+                "oldvalue": null,
+                "newvalue": null,
+                "message": "added bug"
+                },
+                {
+                "resource_type_link": "https://api.launchpad.net/1.0/#bug_activity",
+                "bug_link": "https://api.launchpad.net/1.0/bugs/2066153",
+                "datechanged": "2024-05-27T08:02:11.154871+00:00",
+                "person_link": "https://api.launchpad.net/1.0/~sean-k-mooney",
+                "whatchanged": "nova: status",
+                "oldvalue": "New",
+                "newvalue": "Incomplete",
+                "message": null
+                }
+            ]
+            }"#}.to_string()
+}
+
+pub(crate) fn fake_bug_activity_page_2() -> String {
+    indoc! {r#"{
+            "start": 2,
+            "total_size": 3,
+            "prev_collection_link": "https://api.launchpad.net/1.0/bugs/2066153/activity?ws.size=2&direction=backwards&memo=2",
+            "entries": [
+                {
+                "resource_type_link": "https://api.launchpad.net/1.0/#bug_activity",
+                "bug_link": "https://api.launchpad.net/1.0/bugs/2066153",
+                "datechanged": "2024-05-27T08:02:11.154871+00:00",
+                "person_link": "https://api.launchpad.net/1.0/~sean-k-mooney",
+                "whatchanged": "tags",
+                "oldvalue": "",
+                "newvalue": "libvirt",
+                "message": null
+                }
+            ]
+            }"#}.to_string()
+}
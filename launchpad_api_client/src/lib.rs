@@ -1,20 +1,37 @@
 pub mod client;
 mod fake;
 
-use chrono::{DateTime, Utc};
-use serde::Deserialize;
+use chrono::{DateTime, NaiveDate, NaiveDateTime, Utc};
+use futures::{Stream, TryStreamExt, stream};
+use serde::{Deserialize, de::DeserializeOwned};
 use serde_json::{Value, error::Category};
+use std::pin::pin;
 use thiserror::Error;
-use tracing::debug;
+use tracing::{debug, warn};
 
 #[derive(Error, Debug)]
 pub enum LaunchpadError {
     #[error("HTTP request failed: {0}")]
     HttpRequest(#[from] reqwest::Error),
+    #[error("HTTP status {status} for {url}")]
+    HttpStatus { status: u16, url: String },
     #[error("Deserialization failed: {0}")]
     Deserialization(#[from] serde_json::Error),
     #[error("Invalid project: {0}")]
     InvalidProject(String),
+    #[error("Bug #{0} does not exist")]
+    BugNotFound(u32),
+    #[error("Invalid status: {0}")]
+    InvalidStatus(String),
+    #[error("Unexpected resource: expected {expected}, got {found}")]
+    UnexpectedResource { expected: String, found: String },
+    #[error("Launchpad credentials are required to {0}")]
+    Unauthorized(String),
+    #[error("No answer from {url} after {}s", timeout.as_secs())]
+    Timeout {
+        url: String,
+        timeout: std::time::Duration,
+    },
 }
 
 pub trait HTTPClient {
@@ -22,12 +39,34 @@ pub trait HTTPClient {
         &self,
         url: &str,
     ) -> impl std::future::Future<Output = Result<String, LaunchpadError>> + Send;
+
+    /// Sends a form to a url, used to call the named operations (`ws.op`).
+    fn post(
+        &self,
+        url: &str,
+        form: &[(&str, &str)],
+    ) -> impl std::future::Future<Output = Result<String, LaunchpadError>> + Send;
+
+    /// Sends a json document holding the fields of a resource to change.
+    fn patch(
+        &self,
+        url: &str,
+        body: &str,
+    ) -> impl std::future::Future<Output = Result<String, LaunchpadError>> + Send;
+
+    /// Root of the API the urls are built from, the production one unless
+    /// the client targets another instance like qastaging.
+    fn base_url(&self) -> &str {
+        LAUNCHPAD_API_BASE_URL
+    }
 }
 
-const LAUNCHPAD_API_BASE_URL: &str = "https://api.launchpad.net/1.0";
-const LAUNCHPAD_API_BUG_BASE_URL: &str = "https://api.launchpad.net/1.0/bugs";
+/// Root of the production API.
+pub const LAUNCHPAD_API_BASE_URL: &str = "https://api.launchpad.net/1.0";
+/// Largest page of entries Launchpad returns for a collection.
+pub const MAX_PAGE_SIZE: u32 = 300;
 
-#[derive(Debug)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum StatusFilter {
     New,
     Incomplete,
@@ -42,6 +81,41 @@ pub enum StatusFilter {
     FixReleased,
 }
 
+impl StatusFilter {
+    pub const ALL: [StatusFilter; 11] = [
+        StatusFilter::New,
+        StatusFilter::Incomplete,
+        StatusFilter::Opinion,
+        StatusFilter::Invalid,
+        StatusFilter::WontFix,
+        StatusFilter::Confirmed,
+        StatusFilter::Triaged,
+        StatusFilter::InProgress,
+        StatusFilter::Deferred,
+        StatusFilter::FixCommitted,
+        StatusFilter::FixReleased,
+    ];
+}
+
+impl std::fmt::Display for StatusFilter {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", String::from(*self).replace('+', " "))
+    }
+}
+
+impl std::str::FromStr for StatusFilter {
+    type Err = LaunchpadError;
+
+    /// Parses a status as displayed ("Won't Fix") or as sent in urls ("Won't+Fix").
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let wanted = s.trim().replace('+', " ");
+        StatusFilter::ALL
+            .into_iter()
+            .find(|status| status.to_string().eq_ignore_ascii_case(&wanted))
+            .ok_or_else(|| LaunchpadError::InvalidStatus(s.to_string()))
+    }
+}
+
 impl From<StatusFilter> for String {
     fn from(value: StatusFilter) -> Self {
         match value {
@@ -60,6 +134,107 @@ impl From<StatusFilter> for String {
     }
 }
 
+/// Importance of a bug task, from the least to the most important.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Importance {
+    Undecided,
+    Wishlist,
+    Low,
+    Medium,
+    High,
+    Critical,
+}
+
+impl Importance {
+    pub const ALL: [Importance; 6] = [
+        Importance::Undecided,
+        Importance::Wishlist,
+        Importance::Low,
+        Importance::Medium,
+        Importance::High,
+        Importance::Critical,
+    ];
+}
+
+impl std::fmt::Display for Importance {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", String::from(*self))
+    }
+}
+
+impl From<Importance> for String {
+    fn from(value: Importance) -> Self {
+        match value {
+            Importance::Undecided => String::from("Undecided"),
+            Importance::Wishlist => String::from("Wishlist"),
+            Importance::Low => String::from("Low"),
+            Importance::Medium => String::from("Medium"),
+            Importance::High => String::from("High"),
+            Importance::Critical => String::from("Critical"),
+        }
+    }
+}
+
+/// Confidentiality level of a bug.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(from = "String")]
+pub enum InformationType {
+    Public,
+    PublicSecurity,
+    PrivateSecurity,
+    Private,
+    Proprietary,
+    Embargoed,
+    /// A value unknown to this client, kept as sent by Launchpad
+    Other(String),
+}
+
+impl InformationType {
+    /// Returns true if the bug is not visible to everyone.
+    pub fn is_private(&self) -> bool {
+        !matches!(
+            self,
+            InformationType::Public | InformationType::PublicSecurity
+        )
+    }
+
+    pub fn is_security(&self) -> bool {
+        matches!(
+            self,
+            InformationType::PublicSecurity | InformationType::PrivateSecurity
+        )
+    }
+}
+
+impl From<String> for InformationType {
+    fn from(value: String) -> Self {
+        match value.as_str() {
+            "Public" => InformationType::Public,
+            "Public Security" => InformationType::PublicSecurity,
+            "Private Security" => InformationType::PrivateSecurity,
+            "Private" => InformationType::Private,
+            "Proprietary" => InformationType::Proprietary,
+            "Embargoed" => InformationType::Embargoed,
+            _ => InformationType::Other(value),
+        }
+    }
+}
+
+impl std::fmt::Display for InformationType {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let name = match self {
+            InformationType::Public => "Public",
+            InformationType::PublicSecurity => "Public Security",
+            InformationType::PrivateSecurity => "Private Security",
+            InformationType::Private => "Private",
+            InformationType::Proprietary => "Proprietary",
+            InformationType::Embargoed => "Embargoed",
+            InformationType::Other(name) => name,
+        };
+        write!(f, "{name}")
+    }
+}
+
 #[derive(Debug, Deserialize)]
 pub struct LaunchpadBugTasksResponse {
     pub start: u32,
@@ -122,16 +297,48 @@ impl BugTaskEntry {
             .parse()
             .unwrap()
     }
+
+    /// Returns the account name of the assignee, none if unassigned.
+    pub fn assignee_name(&self) -> Option<&str> {
+        self.assignee_link.as_deref().map(person_name_from_link)
+    }
+
+    /// Returns the id of the bug, the last segment of `bug_link`.
+    pub fn bug_id(&self) -> Option<u32> {
+        self.bug_link
+            .trim_end_matches('/')
+            .rsplit('/')
+            .next()?
+            .parse()
+            .ok()
+    }
+
+    /// Returns the bug title, without the `Bug #id in target: "..."` wrapping
+    /// Launchpad adds to the task title.
+    pub fn bug_title(&self) -> Option<&str> {
+        let prefix = format!(
+            "Bug #{} in {}: ",
+            self.bug_id()?,
+            self.bug_target_display_name
+        );
+        let title = self.title.strip_prefix(&prefix)?;
+        Some(
+            title
+                .strip_prefix('"')
+                .and_then(|t| t.strip_suffix('"'))
+                .unwrap_or(title),
+        )
+    }
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, Clone)]
 pub struct LaunchpadBug {
     pub self_link: String,
     pub web_link: String,
     pub resource_type_link: String,
     pub id: u32,
     pub private: bool,
-    pub information_type: String,
+    pub information_type: InformationType,
     pub name: Option<String>,
     pub title: String,
     pub description: String,
@@ -173,6 +380,80 @@ pub struct LaunchpadBug {
     pub http_etag: String,
 }
 
+impl LaunchpadBug {
+    /// Returns the id of the bug this one is a duplicate of.
+    pub fn duplicate_of(&self) -> Option<u32> {
+        self.duplicate_of_link
+            .as_deref()?
+            .trim_end_matches('/')
+            .rsplit('/')
+            .next()?
+            .parse()
+            .ok()
+    }
+}
+
+/// A message posted on a bug, the first one being the bug description.
+#[derive(Debug, Deserialize, Clone)]
+pub struct BugMessage {
+    pub self_link: String,
+    pub subject: Option<String>,
+    pub content: String,
+    pub owner_link: Option<String>,
+    #[serde(default, deserialize_with = "deserialize_optional_datetime")]
+    pub date_created: Option<DateTime<Utc>>,
+}
+
+/// A file attached to a bug, like a log or a patch.
+#[derive(Debug, Deserialize, Clone)]
+pub struct BugAttachment {
+    pub self_link: String,
+    pub title: String,
+    /// "Patch" or "Unspecified"
+    #[serde(rename = "type")]
+    pub attachment_type: String,
+    pub data_link: String,
+    pub message_link: Option<String>,
+}
+
+/// A change made to a bug, like a new status or tag.
+#[derive(Debug, Deserialize, Clone)]
+pub struct BugActivity {
+    #[serde(default, deserialize_with = "deserialize_optional_datetime")]
+    pub datechanged: Option<DateTime<Utc>>,
+    pub person_link: Option<String>,
+    /// The changed field, like "nova: status" or "tags"
+    pub whatchanged: String,
+    pub oldvalue: Option<String>,
+    pub newvalue: Option<String>,
+    pub message: Option<String>,
+}
+
+/// A bug with its collections, which may fail to load without preventing
+/// the bug to be displayed.
+#[derive(Debug)]
+pub struct BugDetails {
+    pub bug: LaunchpadBug,
+    pub messages: Result<Vec<BugMessage>, LaunchpadError>,
+    pub attachments: Result<Vec<BugAttachment>, LaunchpadError>,
+}
+
+#[derive(Debug, Deserialize)]
+struct LaunchpadCollection<T> {
+    next_collection_link: Option<String>,
+    entries: Vec<T>,
+}
+
+/// A Launchpad person or team, only the fields used to display it are kept.
+#[derive(Debug, Deserialize)]
+pub struct LaunchpadPerson {
+    pub self_link: String,
+    pub web_link: String,
+    pub resource_type_link: String,
+    pub name: String,
+    pub display_name: String,
+}
+
 // Helper function to deserialize optional date-time strings.
 // Launchpad API dates are in ISO 8601 format, e.g., "2025-01-13T08:46:25.105013+00:00"
 // Some date fields can be null or completely absent.
@@ -186,48 +467,71 @@ where
                 // Handle cases where it might be ""
                 None
             } else {
-                s.parse::<DateTime<Utc>>().ok()
+                parse_datetime(&s)
             }
         })
     })
 }
 
+/// Parses an ISO 8601 date, falling back to the variants seen on Launchpad:
+/// a space instead of the 'T', no time zone meaning UTC, or no time at all.
+fn parse_datetime(s: &str) -> Option<DateTime<Utc>> {
+    let date = s
+        .parse::<DateTime<Utc>>()
+        .ok()
+        .or_else(|| {
+            DateTime::parse_from_str(s, "%Y-%m-%d %H:%M:%S%.f%:z")
+                .ok()
+                .map(|date| date.to_utc())
+        })
+        .or_else(|| {
+            ["%Y-%m-%dT%H:%M:%S%.f", "%Y-%m-%d %H:%M:%S%.f"]
+                .iter()
+                .find_map(|format| NaiveDateTime::parse_from_str(s, format).ok())
+                .or_else(|| {
+                    NaiveDate::parse_from_str(s, "%Y-%m-%d")
+                        .ok()
+                        .and_then(|date| date.and_hms_opt(0, 0, 0))
+                })
+                .map(|date| date.and_utc())
+        });
+    if date.is_none() {
+        warn!("Ignoring the unknown date format \"{s}\"");
+    }
+    date
+}
+
 pub async fn get_bug(
     client: &impl HTTPClient,
     bug_id: u32,
 ) -> Result<LaunchpadBug, LaunchpadError> {
-    let url = format!("{LAUNCHPAD_API_BUG_BASE_URL}/{bug_id}");
+    let url = format!("{}/bugs/{bug_id}", client.base_url());
     debug!("Connecting to \"{url}\"");
-    let response = client.get(&url).await?;
+    let response = match client.get(&url).await {
+        Err(LaunchpadError::HttpStatus { status: 404, .. }) => {
+            return Err(LaunchpadError::BugNotFound(bug_id));
+        }
+        response => response?,
+    };
 
-    let bug: LaunchpadBug = serde_json::from_str(&response)?;
+    // Like for the projects, a missing bug may be answered with a text page
+    let resource: Value = serde_json::from_str(&response).map_err(|e| match e.classify() {
+        Category::Syntax => LaunchpadError::BugNotFound(bug_id),
+        _ => LaunchpadError::Deserialization(e),
+    })?;
+    check_resource_type(&resource, "bug")?;
+    let bug: LaunchpadBug = serde_json::from_value(resource)?;
     Ok(bug)
 }
 
-pub async fn get_project_bug_tasks(
+/// Returns the tasks of a bug, one for each project or series it affects.
+pub async fn get_bug_tasks(
     client: &impl HTTPClient,
-    project_name: &str,
-    filter: Option<StatusFilter>,
+    bug_id: u32,
 ) -> Result<Vec<BugTaskEntry>, LaunchpadError> {
-    let url = format!("{LAUNCHPAD_API_BASE_URL}/{project_name}");
-    debug!("Connecting to \"{url}\"");
-    let response = client.get(&url).await?;
-
-    check_project(project_name, &url, &response)?;
-
-    // At this point we have a valid project
-    let url = match filter {
-        None => format!("{LAUNCHPAD_API_BASE_URL}/{project_name}?ws.op=searchTasks"),
-        Some(f) => format!(
-            "{LAUNCHPAD_API_BASE_URL}/{project_name}?ws.op=searchTasks&status={}",
-            String::from(f)
-        ),
-    };
-
+    let url = format!("{}/bugs/{bug_id}/bug_tasks", client.base_url());
     let mut bug_tasks_page = get_bug_tasks_page(client, &url).await?;
-
     let mut bugtasks: Vec<BugTaskEntry> = Vec::with_capacity(bug_tasks_page.total_size as usize);
-
     copy_bug_tasks_page(&bug_tasks_page, &mut bugtasks);
 
     while bug_tasks_page.next_collection_link.is_some() {
@@ -239,6 +543,382 @@ pub async fn get_project_bug_tasks(
     Ok(bugtasks)
 }
 
+/// Posts a comment on a bug, this requires an authenticated client.
+pub async fn post_bug_comment(
+    client: &impl HTTPClient,
+    bug_id: u32,
+    content: &str,
+) -> Result<(), LaunchpadError> {
+    let url = format!("{}/bugs/{bug_id}", client.base_url());
+    debug!("Posting a comment to \"{url}\"");
+    match client
+        .post(&url, &[("ws.op", "newMessage"), ("content", content)])
+        .await
+    {
+        Err(LaunchpadError::HttpStatus { status: 401, .. }) => {
+            Err(LaunchpadError::Unauthorized("post comments".to_string()))
+        }
+        result => result.map(|_| ()),
+    }
+}
+
+/// Changes the status of a bug task, this requires an authenticated client.
+pub async fn set_bug_task_status(
+    client: &impl HTTPClient,
+    bug_task_self_link: &str,
+    status: StatusFilter,
+) -> Result<(), LaunchpadError> {
+    debug!("Setting the status of \"{bug_task_self_link}\" to {status}");
+    let body = serde_json::json!({ "status": status.to_string() }).to_string();
+    match client.patch(bug_task_self_link, &body).await {
+        Err(LaunchpadError::HttpStatus { status: 401, .. }) => Err(LaunchpadError::Unauthorized(
+            "change the bug status".to_string(),
+        )),
+        result => result.map(|_| ()),
+    }
+}
+
+/// Returns the messages of a bug in the order they were posted.
+pub async fn get_bug_messages(
+    client: &impl HTTPClient,
+    bug: &LaunchpadBug,
+) -> Result<Vec<BugMessage>, LaunchpadError> {
+    get_collection(client, &bug.messages_collection_link, "message").await
+}
+
+pub async fn get_bug_attachments(
+    client: &impl HTTPClient,
+    bug: &LaunchpadBug,
+) -> Result<Vec<BugAttachment>, LaunchpadError> {
+    get_collection(client, &bug.attachments_collection_link, "bug_attachment").await
+}
+
+/// Returns the changes made to a bug, the oldest first.
+pub async fn get_bug_activity(
+    client: &impl HTTPClient,
+    bug: &LaunchpadBug,
+) -> Result<Vec<BugActivity>, LaunchpadError> {
+    get_collection(client, &bug.activity_collection_link, "bug_activity").await
+}
+
+/// Returns a bug with its messages and attachments, both fetched at the same
+/// time once the bug is known.
+pub async fn get_bug_full(
+    client: &impl HTTPClient,
+    bug_id: u32,
+) -> Result<BugDetails, LaunchpadError> {
+    let bug = get_bug(client, bug_id).await?;
+    let (messages, attachments) = futures::join!(
+        get_bug_messages(client, &bug),
+        get_bug_attachments(client, &bug)
+    );
+    Ok(BugDetails {
+        bug,
+        messages,
+        attachments,
+    })
+}
+
+// Fetches every page of a collection whose entries are of the `resource` type.
+async fn get_collection<T: DeserializeOwned>(
+    client: &impl HTTPClient,
+    url: &str,
+    resource: &str,
+) -> Result<Vec<T>, LaunchpadError> {
+    let mut entries = Vec::new();
+    let mut next_link = Some(url.to_string());
+    while let Some(url) = next_link {
+        debug!("Connecting to \"{url}\"");
+        let response = client.get(&url).await?;
+        let page: Value = serde_json::from_str(&response)?;
+        match page["entries"].as_array() {
+            Some(page_entries) => page_entries
+                .iter()
+                .try_for_each(|entry| check_resource_type(entry, resource))?,
+            None => {
+                return Err(LaunchpadError::UnexpectedResource {
+                    expected: format!("{resource} collection"),
+                    found: resource_type(&page).to_string(),
+                });
+            }
+        }
+        let page: LaunchpadCollection<T> = serde_json::from_value(page)?;
+        entries.extend(page.entries);
+        next_link = page.next_collection_link;
+    }
+    Ok(entries)
+}
+
+pub async fn get_person(
+    client: &impl HTTPClient,
+    person_link: &str,
+) -> Result<LaunchpadPerson, LaunchpadError> {
+    debug!("Connecting to \"{person_link}\"");
+    let response = client.get(person_link).await?;
+
+    let resource: Value = serde_json::from_str(&response)?;
+    // Bugs can be filed on behalf of a team as well
+    if resource_type(&resource) != "team" {
+        check_resource_type(&resource, "person")?;
+    }
+    let person: LaunchpadPerson = serde_json::from_value(resource)?;
+    Ok(person)
+}
+
+/// Returns the account name from a person link, e.g. "cruzfjf" for
+/// "https://api.launchpad.net/1.0/~cruzfjf".
+pub fn person_name_from_link(person_link: &str) -> &str {
+    let segment = person_link
+        .trim_end_matches('/')
+        .rsplit('/')
+        .next()
+        .unwrap_or(person_link);
+    segment.trim_start_matches('~')
+}
+
+/// Whether the bugs must carry any or all of the searched tags.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TagsCombinator {
+    Any,
+    All,
+}
+
+impl std::fmt::Display for TagsCombinator {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            TagsCombinator::Any => write!(f, "Any"),
+            TagsCombinator::All => write!(f, "All"),
+        }
+    }
+}
+
+/// Criteria of a bug task search, unset ones are left to Launchpad defaults.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SearchOptions {
+    statuses: Vec<StatusFilter>,
+    importances: Vec<Importance>,
+    assignee: Option<String>,
+    tags: Vec<String>,
+    tags_combinator: Option<TagsCombinator>,
+    text: Option<String>,
+    omit_duplicates: bool,
+    size: Option<u32>,
+}
+
+impl SearchOptions {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds a status to look for, any status matches if none is given.
+    pub fn status(mut self, status: StatusFilter) -> Self {
+        self.statuses.push(status);
+        self
+    }
+
+    pub fn statuses(mut self, statuses: &[StatusFilter]) -> Self {
+        self.statuses.extend_from_slice(statuses);
+        self
+    }
+
+    /// Adds an importance to look for, any importance matches if none is given.
+    pub fn importance(mut self, importance: Importance) -> Self {
+        self.importances.push(importance);
+        self
+    }
+
+    /// Only looks for the tasks assigned to this person, by name ("uggla").
+    pub fn assignee(mut self, name: &str) -> Self {
+        self.assignee = Some(name.trim_start_matches('~').to_string());
+        self
+    }
+
+    /// Adds a tag the bugs must have.
+    pub fn tag(mut self, tag: &str) -> Self {
+        self.tags.push(tag.to_string());
+        self
+    }
+
+    /// Sets whether the bugs must carry any (Launchpad default) or all the tags.
+    pub fn tags_combinator(mut self, combinator: TagsCombinator) -> Self {
+        self.tags_combinator = Some(combinator);
+        self
+    }
+
+    /// Only looks for the bugs containing this text.
+    pub fn text(mut self, text: &str) -> Self {
+        self.text = Some(text.to_string());
+        self
+    }
+
+    pub fn omit_duplicates(mut self, omit_duplicates: bool) -> Self {
+        self.omit_duplicates = omit_duplicates;
+        self
+    }
+
+    /// Sets the number of tasks per page, at most `MAX_PAGE_SIZE`.
+    pub fn size(mut self, size: u32) -> Self {
+        self.size = Some(size.clamp(1, MAX_PAGE_SIZE));
+        self
+    }
+}
+
+pub async fn get_project_bug_tasks(
+    client: &impl HTTPClient,
+    project_name: &str,
+    filters: &[StatusFilter],
+    importance: Option<Importance>,
+    omit_duplicates: bool,
+    page_size: Option<u32>,
+) -> Result<Vec<BugTaskEntry>, LaunchpadError> {
+    let mut options = SearchOptions::new()
+        .statuses(filters)
+        .omit_duplicates(omit_duplicates);
+    if let Some(importance) = importance {
+        options = options.importance(importance);
+    }
+    if let Some(size) = page_size {
+        options = options.size(size);
+    }
+    search_bug_tasks(client, project_name, &options).await
+}
+
+/// Returns all the bug tasks of a project matching the options, fetching
+/// every page.
+pub async fn search_bug_tasks(
+    client: &impl HTTPClient,
+    project_name: &str,
+    options: &SearchOptions,
+) -> Result<Vec<BugTaskEntry>, LaunchpadError> {
+    search_bug_tasks_with_progress(client, project_name, options, |_, _| {}).await
+}
+
+/// Same as `search_bug_tasks`, calling `progress` after each page with the
+/// number of tasks fetched so far and the total number of matching tasks.
+pub async fn search_bug_tasks_with_progress(
+    client: &impl HTTPClient,
+    project_name: &str,
+    options: &SearchOptions,
+    mut progress: impl FnMut(usize, u32),
+) -> Result<Vec<BugTaskEntry>, LaunchpadError> {
+    let mut pages = pin!(bug_tasks_pages(client, project_name, options));
+    let mut bugtasks: Vec<BugTaskEntry> = Vec::new();
+    while let Some(page) = pages.try_next().await? {
+        bugtasks.reserve((page.total_size as usize).saturating_sub(bugtasks.len()));
+        bugtasks.extend(page.entries);
+        progress(bugtasks.len(), page.total_size);
+    }
+    Ok(bugtasks)
+}
+
+/// Same as `search_bug_tasks`, yielding the tasks as their pages arrive
+/// rather than once all of them were fetched.
+pub fn stream_project_bug_tasks<'a>(
+    client: &'a impl HTTPClient,
+    project_name: &'a str,
+    options: &'a SearchOptions,
+) -> impl Stream<Item = Result<BugTaskEntry, LaunchpadError>> + 'a {
+    bug_tasks_pages(client, project_name, options)
+        .map_ok(|page| stream::iter(page.entries.into_iter().map(Ok)))
+        .try_flatten()
+}
+
+/// Where the paging of the bug tasks of a project is at.
+enum PagingState {
+    Start,
+    Next(String),
+    Done,
+}
+
+// Fetches the pages of the bug tasks one after the other, once the project
+// is known to exist.
+fn bug_tasks_pages<'a>(
+    client: &'a impl HTTPClient,
+    project_name: &'a str,
+    options: &'a SearchOptions,
+) -> impl Stream<Item = Result<LaunchpadBugTasksResponse, LaunchpadError>> + 'a {
+    stream::try_unfold(PagingState::Start, move |state| async move {
+        let url = match state {
+            PagingState::Start => {
+                get_project(client, project_name).await?;
+                search_tasks_url(client.base_url(), project_name, options)
+            }
+            PagingState::Next(url) => url,
+            PagingState::Done => return Ok(None),
+        };
+        let page = get_bug_tasks_page(client, &url).await?;
+        let next = match &page.next_collection_link {
+            Some(link) => PagingState::Next(link.clone()),
+            None => PagingState::Done,
+        };
+        Ok(Some((page, next)))
+    })
+}
+
+// Checks a project exists, a missing one is reported as invalid.
+async fn get_project(
+    client: &impl HTTPClient,
+    project_name: &str,
+) -> Result<Value, LaunchpadError> {
+    let url = format!("{}/{project_name}", client.base_url());
+    debug!("Connecting to \"{url}\"");
+    let response = match client.get(&url).await {
+        Err(LaunchpadError::HttpStatus { status: 404, .. }) => {
+            return Err(LaunchpadError::InvalidProject(project_name.to_string()));
+        }
+        response => response?,
+    };
+    check_project(project_name, &url, &response)
+}
+
+fn search_tasks_url(base_url: &str, project_name: &str, options: &SearchOptions) -> String {
+    let mut url = format!("{base_url}/{project_name}?ws.op=searchTasks");
+    let mut push = |name: &str, value: &str| {
+        url.push_str(&format!("&{name}={}", query_encode(value)));
+    };
+    // Launchpad expects one parameter per value
+    for status in &options.statuses {
+        push("status", &status.to_string());
+    }
+    for importance in &options.importances {
+        push("importance", &importance.to_string());
+    }
+    if let Some(assignee) = &options.assignee {
+        push("assignee", &format!("{base_url}/~{assignee}"));
+    }
+    for tag in &options.tags {
+        push("tags", tag);
+    }
+    if let Some(combinator) = options.tags_combinator {
+        push("tags_combinator", &combinator.to_string());
+    }
+    if let Some(text) = &options.text {
+        push("search_text", text);
+    }
+    if options.omit_duplicates {
+        push("omit_duplicates", "true");
+    }
+    // The next collection links keep the size, so only the first page needs it
+    if let Some(size) = options.size {
+        push("ws.size", &size.to_string());
+    }
+    url
+}
+
+/// Encodes a query string value, spaces becoming `+` as Launchpad does.
+fn query_encode(value: &str) -> String {
+    value
+        .bytes()
+        .map(|b| match b {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'.' | b'_' | b'~' => {
+                (b as char).to_string()
+            }
+            b' ' => "+".to_string(),
+            b => format!("%{b:02X}"),
+        })
+        .collect()
+}
+
 fn check_project(project_name: &str, url: &str, response: &str) -> Result<Value, LaunchpadError> {
     let project: Result<Value, serde_json::Error> = serde_json::from_str(response);
 
@@ -261,13 +941,46 @@ fn check_project(project_name: &str, url: &str, response: &str) -> Result<Value,
     Ok(project)
 }
 
+// Check the resource type before deserializing, so a valid json document of
+// another kind is reported as such instead of as a missing field.
+fn check_resource_type(resource: &Value, expected: &str) -> Result<(), LaunchpadError> {
+    let found = resource_type(resource);
+    if found != expected {
+        return Err(LaunchpadError::UnexpectedResource {
+            expected: expected.to_string(),
+            found: found.to_string(),
+        });
+    }
+    Ok(())
+}
+
+fn resource_type(resource: &Value) -> &str {
+    resource["resource_type_link"]
+        .as_str()
+        .and_then(|link| link.rsplit('#').next())
+        .unwrap_or("nothing")
+}
+
+fn check_bug_tasks_page(page: &Value) -> Result<(), LaunchpadError> {
+    match page["entries"].as_array() {
+        Some(entries) => entries
+            .iter()
+            .try_for_each(|entry| check_resource_type(entry, "bug_task")),
+        None => Err(LaunchpadError::UnexpectedResource {
+            expected: "bug_task collection".to_string(),
+            found: resource_type(page).to_string(),
+        }),
+    }
+}
+
 async fn get_bug_tasks_page(
     client: &impl HTTPClient,
     url: &str,
 ) -> Result<LaunchpadBugTasksResponse, LaunchpadError> {
     debug!("Connecting to \"{url}\"");
-    let tasks_response_text = client.get(url).await?;
-    let bug_tasks_response: LaunchpadBugTasksResponse = serde_json::from_str(&tasks_response_text)?;
+    let page: Value = serde_json::from_str(&client.get(url).await?)?;
+    check_bug_tasks_page(&page)?;
+    let bug_tasks_response: LaunchpadBugTasksResponse = serde_json::from_value(page)?;
     Ok(bug_tasks_response)
 }
 
@@ -284,9 +997,67 @@ fn copy_bug_tasks_page(
 #[cfg(test)]
 mod tests {
     use crate::client::FakeClient;
+    use futures::StreamExt;
 
     use super::*;
 
+    #[test]
+    fn test_parse_datetime() {
+        let expected = "2025-01-13T08:46:25.105013Z"
+            .parse::<DateTime<Utc>>()
+            .unwrap();
+        for s in [
+            "2025-01-13T08:46:25.105013+00:00",
+            "2025-01-13T09:46:25.105013+01:00",
+            "2025-01-13 08:46:25.105013+00:00",
+            "2025-01-13T08:46:25.105013",
+            "2025-01-13 08:46:25.105013",
+        ] {
+            assert_eq!(parse_datetime(s), Some(expected), "{s}");
+        }
+        assert_eq!(
+            parse_datetime("2025-01-13").unwrap().to_rfc3339(),
+            "2025-01-13T00:00:00+00:00"
+        );
+        assert_eq!(parse_datetime("13/01/2025"), None);
+        assert_eq!(parse_datetime("yesterday"), None);
+    }
+
+    #[test]
+    fn test_malformed_date_is_none() {
+        let mut task: Value = serde_json::from_str(&fake::fake_bug_tasks()).unwrap();
+        task["entries"][0]["date_created"] = Value::from("13/01/2025 08:46");
+        let page: LaunchpadBugTasksResponse = serde_json::from_value(task).unwrap();
+        assert_eq!(page.entries[0].date_created, None);
+    }
+
+    #[test]
+    fn test_status_filter_round_trip() {
+        for status in StatusFilter::ALL {
+            assert_eq!(status.to_string().parse::<StatusFilter>().unwrap(), status);
+            assert_eq!(
+                String::from(status).parse::<StatusFilter>().unwrap(),
+                status
+            );
+        }
+    }
+
+    #[test]
+    fn test_status_filter_from_str() {
+        assert_eq!(
+            "in progress".parse::<StatusFilter>().unwrap(),
+            StatusFilter::InProgress
+        );
+        assert_eq!(
+            "WON'T+FIX".parse::<StatusFilter>().unwrap(),
+            StatusFilter::WontFix
+        );
+        assert_eq!(StatusFilter::WontFix.to_string(), "Won't Fix");
+
+        let error = "Solved".parse::<StatusFilter>().unwrap_err();
+        assert_eq!(error.to_string(), "Invalid status: Solved");
+    }
+
     #[tokio::test]
     async fn test_get_bug() {
         let client = FakeClient::new();
@@ -294,7 +1065,28 @@ mod tests {
         let json = get_bug(&client, 666).await;
 
         assert!(json.is_ok());
-        assert_eq!(json.unwrap().id, 666);
+        let bug = json.unwrap();
+        assert_eq!(bug.id, 666);
+        assert_eq!(bug.information_type, InformationType::Public);
+        assert_eq!(bug.duplicate_of(), None);
+
+        let mut bug = bug;
+        bug.duplicate_of_link = Some("https://api.launchpad.net/1.0/bugs/2066206".to_string());
+        assert_eq!(bug.duplicate_of(), Some(2066206));
+    }
+
+    #[test]
+    fn test_information_type_from_string() {
+        let private_security = InformationType::from("Private Security".to_string());
+        assert_eq!(private_security, InformationType::PrivateSecurity);
+        assert!(private_security.is_private());
+        assert!(private_security.is_security());
+        assert_eq!(private_security.to_string(), "Private Security");
+
+        let other = InformationType::from("Top Secret".to_string());
+        assert_eq!(other, InformationType::Other("Top Secret".to_string()));
+        assert!(other.is_private());
+        assert_eq!(other.to_string(), "Top Secret");
     }
 
     #[tokio::test]
@@ -306,13 +1098,214 @@ mod tests {
         // assert that the result is an err
         assert!(json.is_err());
 
+        // The checked value is deserialized, it has no position
         let error = format!("{:?}", json.unwrap_err());
         assert_eq!(
-            "Deserialization(Error(\"missing field `self_link`\", line: 45, column: 1))",
+            "Deserialization(Error(\"missing field `self_link`\", line: 0, column: 0))",
             &error
         );
     }
 
+    #[tokio::test]
+    async fn test_get_bug_not_found() {
+        let client = FakeClient::new();
+
+        for bug_id in [404, 7000] {
+            let error = get_bug(&client, bug_id).await.unwrap_err();
+            assert!(matches!(error, LaunchpadError::BugNotFound(id) if id == bug_id));
+        }
+    }
+
+    #[tokio::test]
+    async fn test_get_bug_unexpected_resource_error() {
+        let client = FakeClient::new();
+
+        let json = get_bug(&client, 6000).await;
+
+        assert!(json.is_err());
+
+        let error = format!("{:?}", json.unwrap_err());
+        assert_eq!(
+            "UnexpectedResource { expected: \"bug\", found: \"project\" }",
+            &error
+        );
+    }
+
+    #[tokio::test]
+    async fn test_get_bug_tasks() {
+        let client = FakeClient::new();
+
+        let bug_tasks = get_bug_tasks(&client, 2093869).await;
+
+        assert!(bug_tasks.is_ok());
+        let bug_tasks = bug_tasks.unwrap();
+        assert_eq!(bug_tasks.len(), 1);
+        assert_eq!(bug_tasks[0].get_id(), 2093869);
+        assert_eq!(bug_tasks[0].status, "Triaged");
+        assert_eq!(bug_tasks[0].assignee_name(), None);
+    }
+
+    #[tokio::test]
+    async fn test_get_bug_messages() {
+        let client = FakeClient::new();
+        let bug = get_bug(&client, 666).await.unwrap();
+
+        let messages = get_bug_messages(&client, &bug).await;
+
+        assert!(messages.is_ok());
+        let messages = messages.unwrap();
+        assert_eq!(messages.len(), 2);
+        assert_eq!(
+            messages[1].owner_link.as_deref(),
+            Some("https://api.launchpad.net/1.0/~sean-k-mooney")
+        );
+        assert!(messages[1].content.starts_with("which release"));
+    }
+
+    #[tokio::test]
+    async fn test_get_bug_activity() {
+        let client = FakeClient::new();
+        let bug = get_bug(&client, 666).await.unwrap();
+
+        // The activity spans two pages
+        let activity = get_bug_activity(&client, &bug).await.unwrap();
+
+        let changes: Vec<_> = activity
+            .iter()
+            .map(|a| (a.whatchanged.as_str(), a.newvalue.as_deref()))
+            .collect();
+        assert_eq!(
+            changes,
+            [
+                ("bug", None),
+                ("nova: status", Some("Incomplete")),
+                ("tags", Some("libvirt"))
+            ]
+        );
+        assert_eq!(activity[1].oldvalue.as_deref(), Some("New"));
+        assert!(activity[0].datechanged.is_some());
+    }
+
+    #[tokio::test]
+    async fn test_get_bug_full() {
+        let client = FakeClient::new();
+
+        let details = get_bug_full(&client, 666).await.unwrap();
+
+        assert_eq!(details.bug.id, 666);
+        assert_eq!(details.messages.unwrap().len(), 2);
+        let attachments = details.attachments.unwrap();
+        assert_eq!(attachments.len(), 1);
+        assert_eq!(attachments[0].title, "nova-scheduler.log");
+        assert_eq!(attachments[0].attachment_type, "Unspecified");
+
+        // A missing collection does not prevent to get the bug
+        let mut bug = details.bug;
+        bug.attachments_collection_link = "https://api.launchpad.net/1.0/nova".to_string();
+        assert!(get_bug_attachments(&client, &bug).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_post_bug_comment() {
+        let client = FakeClient::new();
+
+        assert!(post_bug_comment(&client, 666, "Thanks").await.is_ok());
+
+        let error = post_bug_comment(&client, 401, "Thanks").await.unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "Launchpad credentials are required to post comments"
+        );
+    }
+
+    /// Records the documents patched, answering like Launchpad.
+    struct PatchRecorder(std::sync::Mutex<Vec<(String, String)>>);
+
+    impl HTTPClient for PatchRecorder {
+        async fn get(&self, url: &str) -> Result<String, LaunchpadError> {
+            FakeClient::new().get(url).await
+        }
+
+        async fn post(&self, url: &str, form: &[(&str, &str)]) -> Result<String, LaunchpadError> {
+            FakeClient::new().post(url, form).await
+        }
+
+        async fn patch(&self, url: &str, body: &str) -> Result<String, LaunchpadError> {
+            self.0
+                .lock()
+                .unwrap()
+                .push((url.to_string(), body.to_string()));
+            FakeClient::new().patch(url, body).await
+        }
+    }
+
+    #[tokio::test]
+    async fn test_set_bug_task_status() {
+        let client = PatchRecorder(Default::default());
+        let link = "https://api.launchpad.net/1.0/nova/+bug/2093869";
+
+        set_bug_task_status(&client, link, StatusFilter::Incomplete)
+            .await
+            .unwrap();
+        set_bug_task_status(&client, link, StatusFilter::WontFix)
+            .await
+            .unwrap();
+
+        assert_eq!(
+            *client.0.lock().unwrap(),
+            [
+                (link.to_string(), r#"{"status":"Incomplete"}"#.to_string()),
+                (link.to_string(), r#"{"status":"Won't Fix"}"#.to_string()),
+            ]
+        );
+
+        let error = set_bug_task_status(
+            &FakeClient::new(),
+            "https://api.launchpad.net/1.0/nova/+bug/401",
+            StatusFilter::Incomplete,
+        )
+        .await
+        .unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "Launchpad credentials are required to change the bug status"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_get_person() {
+        let client = FakeClient::new();
+
+        let person = get_person(&client, "https://api.launchpad.net/1.0/~cruzfjf").await;
+
+        assert!(person.is_ok());
+        let person = person.unwrap();
+        assert_eq!(person.name, "cruzfjf");
+        assert_eq!(person.display_name, "Cruz");
+    }
+
+    #[tokio::test]
+    async fn test_get_person_unexpected_resource_error() {
+        let client = FakeClient::new();
+
+        let person = get_person(&client, "https://api.launchpad.net/1.0/bugs/42").await;
+
+        let error = format!("{:?}", person.unwrap_err());
+        assert_eq!(
+            "UnexpectedResource { expected: \"person\", found: \"bug\" }",
+            &error
+        );
+    }
+
+    #[test]
+    fn test_person_name_from_link() {
+        assert_eq!(
+            person_name_from_link("https://api.launchpad.net/1.0/~cruzfjf"),
+            "cruzfjf"
+        );
+        assert_eq!(person_name_from_link("~nova-bugs/"), "nova-bugs");
+    }
+
     #[tokio::test]
     async fn test_get_project_bugs() {
         let bug_links_ref = [
@@ -324,7 +1317,8 @@ mod tests {
 
         let client = FakeClient::new();
 
-        let bug_tasks = get_project_bug_tasks(&client, "nova", Some(StatusFilter::New)).await;
+        let bug_tasks =
+            get_project_bug_tasks(&client, "nova", &[StatusFilter::New], None, false, None).await;
 
         assert!(bug_tasks.is_ok());
         let bug_tasks = bug_tasks.unwrap();
@@ -334,11 +1328,186 @@ mod tests {
         assert_eq!(bug_links, bug_links_ref);
     }
 
+    #[tokio::test]
+    async fn test_stream_project_bug_tasks() {
+        let client = FakeClient::new();
+        let options = SearchOptions::new().status(StatusFilter::New);
+
+        let streamed: Vec<BugTaskEntry> = stream_project_bug_tasks(&client, "nova", &options)
+            .try_collect()
+            .await
+            .unwrap();
+
+        let collected = search_bug_tasks(&client, "nova", &options).await.unwrap();
+        let links = |tasks: &[BugTaskEntry]| -> Vec<String> {
+            tasks.iter().map(|t| t.self_link.clone()).collect()
+        };
+        assert_eq!(links(&streamed), links(&collected));
+        assert_eq!(streamed.len(), 4);
+
+        // The project is checked before the first page
+        let options = SearchOptions::new();
+        let mut stream = pin!(stream_project_bug_tasks(&client, "zorglub", &options));
+        assert!(matches!(
+            stream.next().await,
+            Some(Err(LaunchpadError::InvalidProject(_)))
+        ));
+        assert!(stream.next().await.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_search_bug_tasks_progress() {
+        let client = FakeClient::new();
+        let options = SearchOptions::new().status(StatusFilter::New);
+        let mut pages = Vec::new();
+
+        let bug_tasks =
+            search_bug_tasks_with_progress(&client, "nova", &options, |fetched, total| {
+                pages.push((fetched, total))
+            })
+            .await
+            .unwrap();
+
+        assert_eq!(bug_tasks.len(), 4);
+        assert_eq!(pages, [(2, 4), (4, 4)]);
+    }
+
+    #[test]
+    fn test_search_tasks_url() {
+        assert_eq!(
+            search_tasks_url(LAUNCHPAD_API_BASE_URL, "nova", &SearchOptions::new()),
+            "https://api.launchpad.net/1.0/nova?ws.op=searchTasks"
+        );
+        assert_eq!(
+            search_tasks_url(
+                LAUNCHPAD_API_BASE_URL,
+                "nova",
+                &SearchOptions::new()
+                    .status(StatusFilter::New)
+                    .omit_duplicates(true)
+            ),
+            "https://api.launchpad.net/1.0/nova?ws.op=searchTasks&status=New&omit_duplicates=true"
+        );
+        assert_eq!(
+            search_tasks_url(
+                LAUNCHPAD_API_BASE_URL,
+                "nova",
+                &SearchOptions::new().statuses(&[StatusFilter::New, StatusFilter::Incomplete])
+            ),
+            "https://api.launchpad.net/1.0/nova?ws.op=searchTasks&status=New&status=Incomplete"
+        );
+        assert_eq!(
+            search_tasks_url(
+                LAUNCHPAD_API_BASE_URL,
+                "nova",
+                &SearchOptions::new().status(StatusFilter::WontFix)
+            ),
+            "https://api.launchpad.net/1.0/nova?ws.op=searchTasks&status=Won%27t+Fix"
+        );
+    }
+
+    #[test]
+    fn test_search_tasks_url_importance() {
+        assert_eq!(
+            search_tasks_url(
+                LAUNCHPAD_API_BASE_URL,
+                "nova",
+                &SearchOptions::new()
+                    .status(StatusFilter::New)
+                    .importance(Importance::Critical)
+            ),
+            "https://api.launchpad.net/1.0/nova?ws.op=searchTasks&status=New&importance=Critical"
+        );
+    }
+
+    #[test]
+    fn test_search_tasks_url_tags() {
+        let options = SearchOptions::new()
+            .tag("gate-failure")
+            .tag("needs+review")
+            .tag("py3.12 ")
+            .tags_combinator(TagsCombinator::All);
+        assert_eq!(
+            search_tasks_url(LAUNCHPAD_API_BASE_URL, "nova", &options),
+            "https://api.launchpad.net/1.0/nova?ws.op=searchTasks\
+             &tags=gate-failure&tags=needs%2Breview&tags=py3.12+&tags_combinator=All"
+        );
+    }
+
+    #[test]
+    fn test_search_tasks_url_options() {
+        let options = SearchOptions::new()
+            .status(StatusFilter::Triaged)
+            .importance(Importance::High)
+            .importance(Importance::Critical)
+            .assignee("~uggla")
+            .tag("libvirt")
+            .tag("live-migration")
+            .text("numa topology");
+        assert_eq!(
+            search_tasks_url(LAUNCHPAD_API_BASE_URL, "nova", &options),
+            "https://api.launchpad.net/1.0/nova?ws.op=searchTasks&status=Triaged\
+             &importance=High&importance=Critical\
+             &assignee=https%3A%2F%2Fapi.launchpad.net%2F1.0%2F~uggla\
+             &tags=libvirt&tags=live-migration&search_text=numa+topology"
+        );
+    }
+
+    #[test]
+    fn test_search_tasks_url_assignee() {
+        let options = SearchOptions::new().assignee("sean-k-mooney");
+        assert_eq!(
+            search_tasks_url(LAUNCHPAD_API_BASE_URL, "nova", &options),
+            "https://api.launchpad.net/1.0/nova?ws.op=searchTasks\
+             &assignee=https%3A%2F%2Fapi.launchpad.net%2F1.0%2F~sean-k-mooney"
+        );
+        // The same with the leading tilde
+        assert_eq!(
+            search_tasks_url(
+                LAUNCHPAD_API_BASE_URL,
+                "nova",
+                &SearchOptions::new().assignee("~sean-k-mooney")
+            ),
+            search_tasks_url(LAUNCHPAD_API_BASE_URL, "nova", &options)
+        );
+    }
+
+    #[test]
+    fn test_importance_to_string() {
+        let names: Vec<String> = Importance::ALL.into_iter().map(String::from).collect();
+        assert_eq!(
+            names,
+            ["Undecided", "Wishlist", "Low", "Medium", "High", "Critical"]
+        );
+        assert_eq!(Importance::High.to_string(), "High");
+        assert!(Importance::Critical > Importance::High);
+    }
+
+    #[test]
+    fn test_search_tasks_url_page_size() {
+        assert_eq!(
+            search_tasks_url(
+                LAUNCHPAD_API_BASE_URL,
+                "nova",
+                &SearchOptions::new().status(StatusFilter::New).size(100)
+            ),
+            "https://api.launchpad.net/1.0/nova?ws.op=searchTasks&status=New&ws.size=100"
+        );
+        assert!(
+            search_tasks_url(
+                LAUNCHPAD_API_BASE_URL,
+                "nova",
+                &SearchOptions::new().size(1000)
+            )
+            .ends_with("&ws.size=300")
+        );
+    }
+
     #[tokio::test]
     async fn test_get_project_bugs_empty_json_invalid_project_error() {
         let client = FakeClient::new();
 
-        let json = get_project_bug_tasks(&client, "zorglub", None).await;
+        let json = get_project_bug_tasks(&client, "zorglub", &[], None, false, None).await;
 
         // assert that the result is an err
         assert!(json.is_err());
@@ -351,7 +1520,7 @@ mod tests {
     async fn test_get_project_bugs_invalid_json_invalid_project_error() {
         let client = FakeClient::new();
 
-        let json = get_project_bug_tasks(&client, "notaproject", None).await;
+        let json = get_project_bug_tasks(&client, "notaproject", &[], None, false, None).await;
 
         // assert that the result is an err
         assert!(json.is_err());
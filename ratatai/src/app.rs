@@ -1,17 +1,48 @@
 // src/app.rs
 
-use google_ai_rs::Client;
+use crossterm::event::KeyCode;
 use launchpad_api_client::{
-    BugTaskEntry, LaunchpadBug, StatusFilter, get_bug as lp_get_bug, get_project_bug_tasks,
+    BugActivity, BugAttachment, BugMessage, BugTaskEntry, Importance, LaunchpadBug, LaunchpadError,
+    MAX_PAGE_SIZE, SearchOptions, StatusFilter,
+    client::{ReqwestClient, RetryClient, TimeoutClient},
+    get_bug, get_bug_activity, get_bug_full, get_bug_messages, get_bug_tasks, get_person,
+    person_name_from_link, post_bug_comment, search_bug_tasks_with_progress, set_bug_task_status,
+};
+use ratatui::{
+    style::Style,
+    widgets::{Cell, Row, ScrollbarState, TableState},
 };
-use ratatui::widgets::{Cell, Row, ScrollbarState, TableState};
 use regex::Regex;
-use std::sync::{Arc, Mutex};
+use std::{
+    cmp::Ordering,
+    collections::{BTreeSet, HashMap, HashSet},
+    path::{Path, PathBuf},
+    sync::{Arc, Mutex},
+    time::{Duration, Instant},
+};
 use throbber_widgets_tui::ThrobberState;
-use tokio::sync::mpsc::{Receiver, Sender};
-use tracing::{error, info};
+use tokio::{
+    sync::mpsc::{Receiver, Sender},
+    task::AbortHandle,
+};
+use tracing::{error, info, warn};
 
-use crate::{LpMessage, ui::SPINNER_LABELS};
+use crate::{
+    ChatMessage, LpMessage,
+    ai::{GenerationSettings, LazyGeminiClient, TokenUsage, get_initial_prompt},
+    cache::BugCache,
+    clipboard::Clipboard,
+    config::{Config, Theme},
+    export::{bug_to_markdown, export_bugs},
+    filters::BugFilter,
+    input::TextInput,
+    join_monitor::TaskMonitor,
+    keymap::{Action, Key, KeyContext, KeyMap},
+    logging::LogControl,
+    outbox::{OutboxEntry, outbox_dir},
+    session::Session,
+    ui::{SPINNER_LABELS, Symbols, importance_color},
+};
 
 #[derive(Debug, PartialEq, Eq)]
 pub(crate) enum Screen {
@@ -25,73 +56,528 @@ pub(crate) enum ActivePanel {
     Right,
 }
 
+/// Regex extracting the bug id and title from a bug task title, e.g.
+/// `Bug #2093869 in OpenStack Compute (nova): "update conflict"`
+pub(crate) const DEFAULT_TITLE_REGEX: &str = r#"#(\d+) in [^:]+:\s+"([^"]+)""#;
+
+/// Reading speed used to estimate the reading time of a description
+const WORDS_PER_MINUTE: usize = 200;
+
+/// Rows of a page jump until the panel was drawn once
+const DEFAULT_PAGE_ROWS: u16 = 10;
+
+/// Rows of a page jump in a panel showing `viewport` rows.
+pub(crate) fn page_rows(viewport: Option<u16>) -> u16 {
+    viewport
+        .filter(|rows| *rows > 0)
+        .unwrap_or(DEFAULT_PAGE_ROWS)
+}
+
+/// Text of the reply panel before anything was written
+pub(crate) const REPLY_PLACEHOLDER: &str = "No bug replied yet.";
+
+/// Modal popups drawn on top of the current screen, they capture the keys until dismissed.
+#[derive(Debug, PartialEq, Eq)]
+pub(crate) enum Popup {
+    /// A reply draft was saved for the current bug, offer to restore it
+    RestoreDraft(String),
+    /// Lists the key bindings
+    Help,
+    /// An error that does not prevent to go on, any key dismisses it
+    Error(String),
+    /// Ask before sending the reply, listing why it looks broken if it does
+    ConfirmReply {
+        action: Action,
+        warnings: Vec<String>,
+    },
+    /// The reply was edited but not posted, ask before quitting
+    ConfirmQuit,
+    /// Path of the file the listed bugs are exported to
+    ExportBugs(TextInput),
+    /// Path of the markdown file the current bug is exported to
+    ExportMarkdown(TextInput),
+    /// Number of the bug to open, listed or not
+    GoToBug(TextInput),
+    /// Prompt a reply would be crafted with, scrolled by `scroll` lines
+    PromptPreview { prompt: String, scroll: u16 },
+    /// Choose the statuses of the listed bugs, `checked` follows `StatusFilter::ALL`
+    StatusFilter { checked: Vec<bool>, cursor: usize },
+}
+
+/// Author of a chat turn.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum ChatRole {
+    User,
+    Model,
+}
+
+/// A prompt sent to the chat or its answer.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct ChatTurn {
+    pub role: ChatRole,
+    pub text: String,
+}
+
+/// Requests in flight, each one shown in the panel it fills.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct Loading {
+    /// The bug list
+    pub bugs: bool,
+    /// The opened bug
+    pub bug: bool,
+    /// The answer to the 'a' key, shown in the description panel
+    pub ai: bool,
+    /// The chat answer, or the reply being posted
+    pub reply: bool,
+    /// The activity of the opened bug
+    pub activity: bool,
+}
+
+impl Loading {
+    pub(crate) fn any(&self) -> bool {
+        self.bugs || self.bug || self.ai || self.reply || self.activity
+    }
+
+    /// Names what is loading, for the spinner of the bottom bar.
+    pub(crate) fn label(&self) -> Option<String> {
+        let names: Vec<&str> = [
+            (self.bugs, "bug list"),
+            (self.bug, "bug"),
+            (self.ai, "AI"),
+            (self.reply, "reply"),
+            (self.activity, "activity"),
+        ]
+        .into_iter()
+        .filter_map(|(loading, name)| loading.then_some(name))
+        .collect();
+        (!names.is_empty()).then(|| format!("Waiting for {}...", names.join(", ")))
+    }
+}
+
+/// Text edited in the external editor.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum EditTarget {
+    /// The description the AI is asked about
+    Description,
+    Reply,
+}
+
+/// Counts the tasks of each status, the statuses unknown to the workflow
+/// last.
+fn status_counts(tasks: &[BugTaskEntry]) -> Vec<(String, usize)> {
+    let mut counts: Vec<(String, usize)> = Vec::new();
+    for task in tasks {
+        match counts.iter_mut().find(|(status, _)| *status == task.status) {
+            Some((_, count)) => *count += 1,
+            None => counts.push((task.status.clone(), 1)),
+        }
+    }
+    counts.sort_by_key(|(status, _)| {
+        StatusFilter::ALL
+            .iter()
+            .position(|known| known.to_string() == *status)
+            .unwrap_or(StatusFilter::ALL.len())
+    });
+    counts
+}
+
+/// Bugs which changed between two fetches of the bug list, by id.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub(crate) struct BugsDiff {
+    pub added: Vec<u32>,
+    pub changed: Vec<u32>,
+    pub removed: Vec<u32>,
+}
+
+impl BugsDiff {
+    /// Compares the tasks by link, a task changed if its etag did.
+    pub(crate) fn new(old: &[BugTaskEntry], new: &[BugTaskEntry]) -> Self {
+        let old_etags: HashMap<&str, &str> = old
+            .iter()
+            .map(|task| (task.self_link.as_str(), task.http_etag.as_str()))
+            .collect();
+        let new_links: HashSet<&str> = new.iter().map(|task| task.self_link.as_str()).collect();
+        let mut diff = Self::default();
+        for task in new {
+            match old_etags.get(task.self_link.as_str()) {
+                None => diff.added.push(task.get_id()),
+                Some(etag) if *etag != task.http_etag => diff.changed.push(task.get_id()),
+                Some(_) => {}
+            }
+        }
+        diff.removed = old
+            .iter()
+            .filter(|task| !new_links.contains(task.self_link.as_str()))
+            .map(BugTaskEntry::get_id)
+            .collect();
+        diff
+    }
+}
+
+/// Column the bug table is sorted by.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum SortKey {
+    Date,
+    Id,
+    Importance,
+    Status,
+}
+
+impl SortKey {
+    pub(crate) fn next(self) -> Self {
+        match self {
+            SortKey::Date => SortKey::Id,
+            SortKey::Id => SortKey::Importance,
+            SortKey::Importance => SortKey::Status,
+            SortKey::Status => SortKey::Date,
+        }
+    }
+
+    /// Header of the table column showing the key.
+    pub(crate) fn column(self) -> &'static str {
+        match self {
+            SortKey::Date => "Date",
+            SortKey::Id => "Bug ID",
+            SortKey::Importance => "Importance",
+            SortKey::Status => "Status",
+        }
+    }
+}
+
 /// Represents the state of the TUI application.
 #[derive(Debug)]
 pub(crate) struct App {
+    /// Launchpad project whose bugs are listed
+    pub project: String,
+    /// Bug tasks fetched, before the view filters
+    pub all_bug_items: Box<[BugTaskEntry]>,
+    /// Bug tasks displayed in the table
     pub bug_table_items: Box<[BugTaskEntry]>,
+    /// Only display the bugs I have interacted with
+    pub only_my_activity: bool,
+    /// Launchpad account of the user, from the configuration
+    pub assignee: Option<String>,
+    /// Only list the bugs assigned to `assignee`
+    pub only_assigned_to_me: bool,
+    /// Only display the bugs whose title contains this text, ignoring case
+    pub filter_query: String,
+    /// Whether the typed keys go to `filter_query`
+    pub filter_editing: bool,
+    /// First press of a key that must be pressed twice
+    pub pending_key: Option<Key>,
+    pub sort_key: SortKey,
+    pub sort_descending: bool,
+    /// Ask Launchpad to leave out the bugs marked as duplicate
+    pub omit_duplicates: bool,
+    /// Only fetch the bugs in these statuses
+    pub statuses: Vec<StatusFilter>,
+    /// Only fetch the bugs carrying this tag
+    pub tag_filter: Option<String>,
+    /// Tags of the bugs fetched so far, the bug tasks do not carry them
+    pub bug_tags: HashMap<u32, Vec<String>>,
+    /// Distinct tags of the listed bugs, cycled through on the bug list
+    pub listed_tags: Vec<String>,
+    /// Position of the filtered tag in `listed_tags`
+    pub tag_index: Option<usize>,
+    /// Pending fetch of the tags of the listed bugs
+    pub bug_tags_request: Option<AbortHandle>,
+    /// Only fetch the bugs of this importance
+    pub importance: Option<Importance>,
+    pub loading: Loading,
+    /// Interval of the automatic refreshes of the bug list
+    pub refresh_interval: Option<Duration>,
+    /// When the bug list was last requested and last received
+    pub bugs_requested_at: Option<Instant>,
+    pub bugs_fetched_at: Option<Instant>,
+    /// Tasks fetched and total while the bug list is loaded page by page
+    pub bugs_progress: Option<(usize, u32)>,
+    /// Number of listed bugs in each status, in the order of the workflow
+    pub status_counts: Vec<(String, usize)>,
     pub bug_table_rows: Vec<Row<'static>>,
+    /// Rows already built, by task link with the etag of the task they were
+    /// built from, so a refresh only builds the rows of the changed tasks
+    pub bug_row_cache: HashMap<String, (String, Row<'static>)>,
+    /// Bugs which arrived, changed or left with the last fetch
+    pub bugs_diff: BugsDiff,
+    /// Regex used to build the rows from the bug task titles
+    pub title_regex: Regex,
+    /// Transformation applied to the bug tasks after each fetch
+    pub bug_filter: Option<Box<dyn BugFilter>>,
+    /// Level and file of the logs, unset when nothing is logged
+    pub log: Option<LogControl>,
     pub bug_table_state: TableState,
     pub bug_table_scrollbar_state: ScrollbarState,
+    /// Bug rows shown by the table when last drawn
+    pub bug_table_viewport: Option<u16>,
     pub active_panel: ActivePanel,
     pub current_screen: Screen,
+    pub popup: Option<Popup>,
+    /// Transient message shown in the bottom bar until the next key press
+    pub status_message: Option<String>,
+    /// Only show the help key in the bottom bar
+    pub compact_hints: bool,
+    pub keymap: KeyMap,
     pub bug_desc_scroll: u16,
+    /// Lines shown by the description panel when last drawn
+    pub bug_desc_viewport: Option<u16>,
     pub bug_desc_scroll_to_end: bool,
+    pub bug_reply_scroll: u16,
+    pub bug_reply_scroll_to_end: bool,
+    /// Prompts and answers of the chat, the last answer being the draft
+    pub chat_history: Vec<ChatTurn>,
+    pub chat_scroll: u16,
+    pub chat_scroll_to_end: bool,
+    /// The navigation keys scroll the chat history rather than the draft
+    pub chat_focused: bool,
+    /// Prompt being typed to the chat, the keys go to it while set
+    pub chat_input: Option<TextInput>,
     pub current_bug: Option<LaunchpadBug>,
-    /// Whether the spinner in the bottom bar is enabled (toggled by 's')
+    /// Live tasks of the current bug, to spot a status changed since listing
+    pub current_bug_tasks: Vec<BugTaskEntry>,
+    /// Messages posted on the current bug, the first one being its description
+    pub current_bug_messages: Vec<BugMessage>,
+    pub current_bug_attachments: Vec<BugAttachment>,
+    /// Changes made to the current bug, fetched the first time they are shown
+    pub current_bug_activity: Option<Vec<BugActivity>>,
+    /// Whether the description panel shows the activity of the bug
+    pub show_activity: bool,
+    /// Conversation last displayed in the description panel, to know if it
+    /// was replaced by an AI answer or an edit
+    pub displayed_conversation: String,
+    /// Display names of the Launchpad persons, indexed by their link
+    pub person_names: HashMap<String, String>,
+    /// Spins the bottom bar spinner even when nothing loads (toggled by 's')
     pub spinner_enabled: bool,
     /// Stateful state for spinner animation
     pub spinner_state: ThrobberState,
+    /// Time between two steps of the spinner
+    pub spinner_interval: Duration,
+    /// When the spinner last moved, it moves by the elapsed time rather than
+    /// by draw
+    pub spinner_step_at: Instant,
     /// Current index for the spinner label in SPINNER_LABELS
     pub spinner_label_index: usize,
-    pub gemini_client: Arc<Client>,
-    pub launchpad_client: Arc<launchpad_api_client::client::ReqwestClient>,
+    /// Symbols used to draw throbber and scrollbars
+    pub symbols: Symbols,
+    pub gemini_client: Arc<LazyGeminiClient>,
+    /// No Gemini API key or `--offline`, the AI features are disabled
+    pub offline: bool,
+    /// Gemini model used by the chat and the AI requests
+    pub gemini_model: String,
+    pub generation: GenerationSettings,
+    /// File holding the prompt used to craft replies, the embedded one is used if unset
+    pub prompt_file: Option<PathBuf>,
+    /// Where the replies are written with 'w'
+    pub reply_dir: PathBuf,
+    /// Command editing the texts outside of the application
+    pub editor: String,
+    /// Arguments of the editor, `{file}` standing for the edited file
+    pub editor_args: Option<String>,
+    /// Command opening the bugs in a web browser
+    pub browser: Option<String>,
+    /// Ask before quitting with an edited reply
+    pub confirm_quit: bool,
+    /// Ask before posting a reply on Launchpad
+    pub confirm_post: bool,
+    /// Ask before changing the status of a bug
+    pub confirm_status_change: bool,
+    /// Render the description panel as markdown
+    pub markdown: bool,
+    /// Whether the word count and reading time of the description are shown
+    pub reading_time: bool,
+    /// Whether the bug list screen shows the reply as a third panel, on wide
+    /// enough terminals
+    pub split_view: bool,
+    /// Words and minutes to read the current bug description, computed when
+    /// the bug is opened
+    pub description_stats: Option<(usize, usize)>,
+    pub theme: Theme,
+    pub launchpad_client: Arc<RetryClient<TimeoutClient<ReqwestClient>>>,
+    /// The Launchpad requests are signed, as changing a bug status requires
+    pub launchpad_authenticated: bool,
+    /// Bug and link of the task set Incomplete once the reply is posted
+    pub mark_incomplete_after_post: Option<(u32, String)>,
+    /// Time given to Launchpad and Gemini to answer a request
+    pub request_timeout: Duration,
     pub gemini_response: Arc<Mutex<String>>,
+    /// Pending request of the 'a' key
+    pub ai_request: Option<AbortHandle>,
+    /// Failure of the last request of the 'a' key
+    pub ai_error: Arc<Mutex<Option<String>>>,
+    /// Tokens consumed by the AI requests
+    pub token_usage: Arc<Mutex<TokenUsage>>,
     pub lp_sender: Sender<LpMessage>,
     pub app_sender: Sender<String>,
-    pub chat_receiver: Receiver<String>,
+    pub chat_receiver: Receiver<ChatMessage>,
+    pub clipboard: Clipboard,
+    /// Background tasks fetching from Launchpad or Gemini
+    pub tasks: TaskMonitor,
+    /// Recently opened bugs, so reopening them is instant
+    pub bug_cache: BugCache,
     pub bug_reply_text: String,
+    /// The reply was edited since it was started and was not posted
+    pub reply_dirty: bool,
+    /// Text replaced by the last external edit, to undo it
+    pub edit_backup: Option<(EditTarget, String)>,
+    pub session: Session,
+    /// Number of comments posted on the current bug since it was last viewed
+    pub new_comments: Option<u32>,
 }
 
 impl App {
     /// Creates a new instance of the application with the initial state.
     pub(crate) fn new(
-        gemini_client: Client,
-        launchpad_client: launchpad_api_client::client::ReqwestClient,
+        config: &Config,
+        gemini_client: LazyGeminiClient,
+        launchpad_client: ReqwestClient,
         lp_sender: Sender<LpMessage>,
         app_sender: Sender<String>,
-        chat_receiver: Receiver<String>,
+        chat_receiver: Receiver<ChatMessage>,
+        session: Session,
     ) -> App {
-        let items = Box::new([]);
+        let items: Box<[BugTaskEntry]> = Box::new([]);
         let mut table_state = TableState::default();
         table_state.select(None);
         let scrollbar_state = ScrollbarState::new(0);
         let rows = Vec::new();
 
         App {
+            project: config.project().to_string(),
+            all_bug_items: items.clone(),
             bug_table_items: items,
+            only_my_activity: false,
+            assignee: config.assignee.clone(),
+            only_assigned_to_me: false,
+            filter_query: String::new(),
+            filter_editing: false,
+            pending_key: None,
+            sort_key: SortKey::Date,
+            sort_descending: true,
+            omit_duplicates: false,
+            importance: None,
+            loading: Loading::default(),
+            statuses: session_statuses(&session),
+            tag_filter: None,
+            bug_tags: HashMap::new(),
+            listed_tags: Vec::new(),
+            tag_index: None,
+            bug_tags_request: None,
+            refresh_interval: config.refresh_interval(),
+            bugs_requested_at: None,
+            bugs_fetched_at: None,
+            bugs_progress: None,
             bug_table_rows: rows,
+            bug_row_cache: HashMap::new(),
+            status_counts: Vec::new(),
+            bugs_diff: BugsDiff::default(),
+            title_regex: Regex::new(DEFAULT_TITLE_REGEX).unwrap(),
+            bug_filter: None,
+            log: None,
             bug_table_state: table_state,
             bug_table_scrollbar_state: scrollbar_state,
+            bug_table_viewport: None,
             active_panel: ActivePanel::Left,
             current_screen: Screen::BugList,
+            popup: None,
+            status_message: None,
+            compact_hints: false,
+            // Checked when loading the configuration
+            keymap: config.keymap().unwrap_or_else(|e| {
+                warn!("Invalid key bindings, using the default ones: {e:#}");
+                KeyMap::default()
+            }),
             bug_desc_scroll: 0,
+            bug_desc_viewport: None,
             bug_desc_scroll_to_end: false,
+            bug_reply_scroll: 0,
+            bug_reply_scroll_to_end: false,
+            chat_history: Vec::new(),
+            chat_scroll: 0,
+            chat_scroll_to_end: false,
+            chat_focused: false,
+            chat_input: None,
             current_bug: None,
+            current_bug_tasks: Vec::new(),
+            current_bug_messages: Vec::new(),
+            current_bug_attachments: Vec::new(),
+            current_bug_activity: None,
+            show_activity: false,
+            displayed_conversation: String::new(),
+            person_names: HashMap::new(),
             spinner_enabled: false,
             spinner_state: ThrobberState::default(),
             spinner_label_index: 0,
+            spinner_interval: config.spinner_interval(),
+            spinner_step_at: Instant::now(),
+            symbols: Symbols::detect().with_spinner(config.spinner),
             gemini_client: Arc::new(gemini_client),
-            launchpad_client: Arc::new(launchpad_client),
+            offline: false,
+            gemini_model: config.gemini_model().to_string(),
+            generation: config.generation(),
+            prompt_file: config.prompt_file.clone(),
+            reply_dir: config
+                .reply_dir
+                .clone()
+                .unwrap_or_else(|| PathBuf::from(".")),
+            editor: config.editor().to_string(),
+            editor_args: config.editor_args.clone(),
+            browser: config.browser.clone(),
+            confirm_quit: config.confirm_quit(),
+            confirm_post: config.confirm_post(),
+            confirm_status_change: config.confirm_status_change(),
+            markdown: config.markdown(),
+            reading_time: config.reading_time(),
+            split_view: config.split_view(),
+            description_stats: None,
+            theme: config.theme.theme(),
+            launchpad_authenticated: launchpad_client.is_authenticated(),
+            mark_incomplete_after_post: None,
+            launchpad_client: Arc::new(RetryClient::new(
+                TimeoutClient::new(launchpad_client).timeout(config.request_timeout()),
+            )),
+            request_timeout: config.request_timeout(),
             gemini_response: Arc::new(Mutex::new(String::new())),
+            ai_request: None,
+            ai_error: Arc::default(),
+            token_usage: Arc::default(),
             lp_sender,
             app_sender,
             chat_receiver,
+            clipboard: Clipboard::default(),
+            tasks: TaskMonitor::default(),
+            bug_cache: BugCache::new(config.cache_size(), config.cache_ttl()),
             bug_reply_text: String::new(),
+            reply_dirty: false,
+            edit_backup: None,
+            session,
+            new_comments: None,
+        }
+    }
+
+    /// Returns the context used to look up the key bindings of the active panel.
+    pub(crate) fn key_context(&self) -> KeyContext {
+        match (&self.current_screen, &self.active_panel) {
+            (Screen::BugList, ActivePanel::Left) => KeyContext::BugTable,
+            (Screen::BugList, ActivePanel::Right) => KeyContext::BugDescription,
+            (Screen::BugEditing, ActivePanel::Left) => KeyContext::ReplyDescription,
+            (Screen::BugEditing, ActivePanel::Right) => KeyContext::BugReply,
         }
     }
 
+    /// Forgets the panel sizes of the previous terminal size, the scroll
+    /// offsets are clamped to the new ones when drawn.
+    pub(crate) fn handle_resize(&mut self) {
+        self.bug_table_viewport = None;
+        self.bug_desc_viewport = None;
+        self.bug_table_scrollbar_state = ScrollbarState::new(self.bug_table_items.len())
+            .position(self.bug_table_state.selected().unwrap_or(0));
+    }
+
     /// Moves the selection up in the table.
     pub(crate) fn bug_table_previous_item(&mut self) {
+        if self.bug_table_items.is_empty() {
+            return;
+        }
         let i = match self.bug_table_state.selected() {
             Some(i) => {
                 if i == 0 {
@@ -108,6 +594,9 @@ impl App {
 
     /// Moves the selection down in the table.
     pub(crate) fn bug_table_next_item(&mut self) {
+        if self.bug_table_items.is_empty() {
+            return;
+        }
         let i = match self.bug_table_state.selected() {
             Some(i) => {
                 if i >= self.bug_table_items.len() - 1 {
@@ -123,8 +612,11 @@ impl App {
     }
 
     pub(crate) fn bug_table_page_up_item(&mut self) {
+        if self.bug_table_items.is_empty() {
+            return;
+        }
         let i = match self.bug_table_state.selected() {
-            Some(i) => i.saturating_sub(10),
+            Some(i) => i.saturating_sub(page_rows(self.bug_table_viewport).into()),
             None => 0,
         };
         self.bug_table_state.select(Some(i));
@@ -132,8 +624,37 @@ impl App {
     }
 
     pub(crate) fn bug_table_page_down_item(&mut self) {
+        if self.bug_table_items.is_empty() {
+            return;
+        }
+        let i = match self.bug_table_state.selected() {
+            Some(i) => (i + usize::from(page_rows(self.bug_table_viewport)))
+                .min(self.bug_table_items.len() - 1),
+            None => 0,
+        };
+        self.bug_table_state.select(Some(i));
+        self.bug_table_scrollbar_state = self.bug_table_scrollbar_state.position(i);
+    }
+
+    pub(crate) fn bug_table_half_page_up(&mut self) {
+        let rows = (page_rows(self.bug_table_viewport) / 2).max(1);
+        self.bug_table_move_by(-(rows as isize));
+    }
+
+    pub(crate) fn bug_table_half_page_down(&mut self) {
+        let rows = (page_rows(self.bug_table_viewport) / 2).max(1);
+        self.bug_table_move_by(rows as isize);
+    }
+
+    /// Moves the selection by `offset` rows, stopping at the ends of the table.
+    fn bug_table_move_by(&mut self, offset: isize) {
+        if self.bug_table_items.is_empty() {
+            return;
+        }
         let i = match self.bug_table_state.selected() {
-            Some(i) => (i + 10).min(self.bug_table_items.len() - 1),
+            Some(i) => i
+                .saturating_add_signed(offset)
+                .min(self.bug_table_items.len() - 1),
             None => 0,
         };
         self.bug_table_state.select(Some(i));
@@ -141,16 +662,52 @@ impl App {
     }
 
     pub(crate) fn bug_table_go_to_start(&mut self) {
+        if self.bug_table_items.is_empty() {
+            return;
+        }
         self.bug_table_state.select(Some(0));
         self.bug_table_scrollbar_state = self.bug_table_scrollbar_state.position(0);
     }
 
     pub(crate) fn bug_table_go_to_end(&mut self) {
+        if self.bug_table_items.is_empty() {
+            return;
+        }
         let i = self.bug_table_items.len() - 1;
         self.bug_table_state.select(Some(i));
         self.bug_table_scrollbar_state = self.bug_table_scrollbar_state.position(i);
     }
 
+    /// Whether the spinner of the bottom bar is animated, which needs the
+    /// interface to be drawn at each tick.
+    pub(crate) fn spinning(&self) -> bool {
+        self.spinner_enabled || self.loading.any()
+    }
+
+    /// Moves the spinner one step for each interval elapsed since its last
+    /// step, while it spins.
+    pub(crate) fn advance_spinner(&mut self, now: Instant) {
+        if !self.spinning() {
+            self.spinner_step_at = now;
+            return;
+        }
+        let elapsed = now
+            .saturating_duration_since(self.spinner_step_at)
+            .as_millis();
+        let interval = self.spinner_interval.as_millis().max(1);
+        let steps = elapsed / interval;
+        if steps == 0 {
+            return;
+        }
+        // The part of an interval already elapsed counts for the next step
+        self.spinner_step_at = now - Duration::from_millis((elapsed % interval) as u64);
+        // Whole turns change nothing, and a zero step is random
+        let steps = steps % self.symbols.throbber.symbols.len().max(1) as u128;
+        if steps > 0 {
+            self.spinner_state.calc_step(steps as i8);
+        }
+    }
+
     /// Toggles the spinner display in the bottom bar.
     pub(crate) fn toggle_spinner(&mut self) {
         self.spinner_enabled = !self.spinner_enabled;
@@ -158,17 +715,187 @@ impl App {
         self.spinner_label_index = (self.spinner_label_index + 1) % SPINNER_LABELS.len();
     }
 
-    pub(crate) fn get_bugs(&mut self, project: String) {
-        self.spinner_enabled = true;
+    /// Switches to the next importance filter, starting from the most
+    /// important one, and refreshes the list.
+    pub(crate) fn cycle_importance(&mut self) {
+        self.importance = next_importance(self.importance);
+        self.get_bugs();
+    }
+
+    /// Only lists the bugs carrying the next tag of the current bug, all the
+    /// bugs again after its last tag, and refreshes the list.
+    pub(crate) fn cycle_tag_filter(&mut self) {
+        let tags = self
+            .current_bug
+            .as_ref()
+            .map(|bug| bug.tags.as_slice())
+            .unwrap_or_default();
+        if tags.is_empty() && self.tag_filter.is_none() {
+            self.status_message = Some("This bug has no tags".to_string());
+            return;
+        }
+        let current = self
+            .tag_filter
+            .as_ref()
+            .and_then(|tag| tags.iter().position(|t| t == tag));
+        let next = next_tag_position(tags.len(), current, true, false);
+        self.set_tag_filter(next.map(|i| tags[i].clone()));
+    }
+
+    /// Only lists the bugs carrying the next tag found in the list, or the
+    /// previous one, going around the tags, and refreshes the list.
+    pub(crate) fn step_tag_filter(&mut self, forward: bool) {
+        let tags = &self.listed_tags;
+        if tags.is_empty() {
+            let pending = self
+                .bug_tags_request
+                .as_ref()
+                .is_some_and(|request| !request.is_finished());
+            let message = if pending {
+                "The tags of the listed bugs are still being fetched"
+            } else {
+                "The listed bugs have no tags"
+            };
+            self.status_message = Some(message.to_string());
+            return;
+        }
+        let next = next_tag_position(tags.len(), self.tag_index, forward, true);
+        self.set_tag_filter(next.map(|i| tags[i].clone()));
+    }
+
+    /// Lists the bugs whatever their tags again.
+    pub(crate) fn clear_tag_filter(&mut self) {
+        if self.tag_filter.is_none() {
+            self.status_message = Some("The bugs are not filtered by tag".to_string());
+            return;
+        }
+        self.set_tag_filter(None);
+    }
+
+    fn set_tag_filter(&mut self, tag: Option<String>) {
+        self.tag_filter = tag;
+        self.tag_index = self
+            .tag_filter
+            .as_ref()
+            .and_then(|tag| self.listed_tags.iter().position(|t| t == tag));
+        self.get_bugs();
+    }
+
+    /// Fetches the tags of the listed bugs not known yet, one bug after the
+    /// other to spare Launchpad. The bugs opened meanwhile are not fetched
+    /// again.
+    fn get_listed_bug_tags(&mut self) {
+        if let Some(request) = self.bug_tags_request.take() {
+            request.abort();
+        }
+        let bug_ids: Vec<u32> = self
+            .all_bug_items
+            .iter()
+            .map(BugTaskEntry::get_id)
+            .filter(|bug_id| !self.bug_tags.contains_key(bug_id))
+            .collect();
+        if bug_ids.is_empty() {
+            return;
+        }
+        let sender = self.lp_sender.clone();
+        let client = self.launchpad_client.clone();
+        let request = self.tasks.spawn("Bug tags", async move {
+            info!("Task to get the tags of {} bugs started", bug_ids.len());
+            for bug_id in bug_ids {
+                match get_bug(&*client, bug_id).await {
+                    Ok(bug) => {
+                        let tags = bug.tags;
+                        if let Err(e) = sender.send(LpMessage::BugTags { bug_id, tags }).await {
+                            error!("Fail to send message, error {e}");
+                            return;
+                        }
+                    }
+                    // Only the tags of this bug are missing from the cycling
+                    Err(e) => warn!("Fail to get the tags of bug {bug_id}, error {e}"),
+                }
+            }
+            info!("Task to get the bug tags completed");
+        });
+        self.bug_tags_request = Some(request);
+    }
+
+    pub(crate) fn update_bug_tags(&mut self, bug_id: u32, tags: Vec<String>) {
+        self.bug_tags.insert(bug_id, tags);
+        self.update_listed_tags();
+    }
+
+    /// Collects the distinct tags of the listed bugs, keeping the position of
+    /// the filtered tag within the tags when the set shrinks.
+    fn update_listed_tags(&mut self) {
+        let tags: BTreeSet<&String> = self
+            .bug_table_items
+            .iter()
+            .filter_map(|task| self.bug_tags.get(&task.get_id()))
+            .flatten()
+            .collect();
+        self.listed_tags = tags.into_iter().cloned().collect();
+        let last = self.listed_tags.len().checked_sub(1);
+        self.tag_index = self.tag_filter.as_ref().and_then(|tag| {
+            self.listed_tags
+                .iter()
+                .position(|t| t == tag)
+                .or_else(|| Some(self.tag_index?.min(last?)))
+        });
+    }
+
+    /// Opens the popup choosing the statuses of the listed bugs.
+    pub(crate) fn open_status_filter(&mut self) {
+        let checked = StatusFilter::ALL
+            .iter()
+            .map(|status| self.statuses.contains(status))
+            .collect();
+        self.popup = Some(Popup::StatusFilter { checked, cursor: 0 });
+    }
+
+    /// Lists the bugs in the given statuses, remembering them for the next runs.
+    pub(crate) fn set_statuses(&mut self, statuses: Vec<StatusFilter>) {
+        if statuses.is_empty() {
+            self.status_message = Some("Select at least one status".to_string());
+            return;
+        }
+        self.session.statuses = statuses.iter().map(ToString::to_string).collect();
+        if let Err(e) = self.session.save() {
+            warn!("Fail to save session, error {e}");
+        }
+        self.statuses = statuses;
+        self.get_bugs();
+    }
+
+    pub(crate) fn get_bugs(&mut self) {
+        let project = self.project.clone();
+        self.loading.bugs = true;
+        self.bugs_requested_at = Some(Instant::now());
+        self.bugs_progress = None;
         let sender = self.lp_sender.clone();
         let client = self.launchpad_client.clone();
-        tokio::spawn(async move {
+        let mut options = SearchOptions::new()
+            .statuses(&self.statuses)
+            .omit_duplicates(self.omit_duplicates)
+            .size(MAX_PAGE_SIZE);
+        if let Some(importance) = self.importance {
+            options = options.importance(importance);
+        }
+        if let Some(tag) = &self.tag_filter {
+            options = options.tag(tag);
+        }
+        if let Some(assignee) = self.assignee.as_ref().filter(|_| self.only_assigned_to_me) {
+            options = options.assignee(assignee);
+        }
+        self.tasks.spawn("Bug list", async move {
             info!("Task to get bugs started");
 
-            match get_project_bug_tasks(&*client, &project, Some(StatusFilter::New)).await {
-                Ok(mut bug_tasks) => {
-                    bug_tasks.sort_by(|a, b| b.date_created.cmp(&a.date_created));
-
+            // A progress update is not worth waiting for, it is dropped when
+            // the channel is full
+            let progress = |fetched, total| {
+                let _ = sender.try_send(LpMessage::BugsProgress { fetched, total });
+            };
+            match search_bug_tasks_with_progress(&*client, &project, &options, progress).await {
+                Ok(bug_tasks) => {
                     if let Err(e) = sender
                         .send(LpMessage::Bugs(bug_tasks.into_boxed_slice()))
                         .await
@@ -186,51 +913,294 @@ impl App {
         });
     }
 
-    pub(crate) fn update_bugs(&mut self, bugs: Box<[BugTaskEntry]>, re: &Regex) {
-        self.bug_table_items = bugs;
+    pub(crate) fn update_bugs(&mut self, bugs: Box<[BugTaskEntry]>) {
+        let bugs = match &self.bug_filter {
+            Some(filter) => {
+                let mut bugs = bugs.into_vec();
+                filter.apply(&mut bugs);
+                bugs.into_boxed_slice()
+            }
+            None => bugs,
+        };
+        self.bugs_diff = BugsDiff::new(&self.all_bug_items, &bugs);
+        self.all_bug_items = bugs;
+        // The rows of the tasks no longer listed will not be reused
+        let links: HashSet<&str> = self
+            .all_bug_items
+            .iter()
+            .map(|task| task.self_link.as_str())
+            .collect();
+        self.bug_row_cache
+            .retain(|link, _| links.contains(link.as_str()));
+        // The selected bug stays selected if it is still in the list
+        self.apply_view_filters();
+        self.loading.bugs = false;
+        self.bugs_progress = None;
+        self.bugs_fetched_at = Some(Instant::now());
+        self.get_listed_bug_tags();
+    }
+
+    /// Records the progress of the bug list being loaded, a late update
+    /// after the list arrived is ignored.
+    pub(crate) fn update_bugs_progress(&mut self, fetched: usize, total: u32) {
+        if self.loading.bugs {
+            self.bugs_progress = Some((fetched, total));
+        }
+    }
+
+    /// Whether the bug list should be refreshed automatically, which is not
+    /// done while a reply is edited nor more often than the interval when
+    /// the fetch fails.
+    pub(crate) fn refresh_due(&self) -> bool {
+        let Some(interval) = self.refresh_interval else {
+            return false;
+        };
+        !self.loading.bugs
+            && self.current_screen == Screen::BugList
+            && self
+                .bugs_requested_at
+                .is_some_and(|at| at.elapsed() >= interval)
+            // Due until a first fetch succeeds
+            && self
+                .bugs_fetched_at
+                .is_none_or(|at| at.elapsed() >= interval)
+    }
+
+    /// Shows an error in a popup, stopping the Launchpad loading indicators
+    /// as the error may come from any fetch.
+    pub(crate) fn set_error(&mut self, message: String) {
+        error!("{message}");
+        self.popup = Some(Popup::Error(message));
+        self.loading.bugs = false;
+        self.bugs_progress = None;
+        self.loading.bug = false;
+    }
+
+    /// Dismisses the error popup, if it is the one displayed.
+    pub(crate) fn clear_error(&mut self) {
+        if let Some(Popup::Error(_)) = self.popup {
+            self.popup = None;
+        }
+    }
+
+    /// Toggles the display of only the bugs I have interacted with.
+    pub(crate) fn toggle_my_activity(&mut self) {
+        self.only_my_activity = !self.only_my_activity;
+        self.apply_view_filters();
+    }
+
+    /// Makes the logs more verbose, back to the errors after the traces.
+    pub(crate) fn cycle_log_level(&mut self) {
+        let Some(log) = &mut self.log else {
+            self.status_message = Some("Nothing is logged".to_string());
+            return;
+        };
+        self.status_message = Some(match log.cycle_level() {
+            Ok(level) => format!(
+                "Log level set to {level}, logs written to {}",
+                log.current_file().display()
+            ),
+            Err(e) => format!("Fail to change the log level: {e}"),
+        });
+    }
+
+    /// Toggles the listing of only the bugs assigned to the configured
+    /// account, and refreshes the list.
+    pub(crate) fn toggle_assigned_to_me(&mut self) {
+        if self.assignee.is_none() {
+            self.status_message =
+                Some("Set the assignee in the configuration to list your bugs".to_string());
+            return;
+        }
+        self.only_assigned_to_me = !self.only_assigned_to_me;
+        self.get_bugs();
+    }
+
+    /// Sorts the table by `key`, keeping the selected bug selected.
+    pub(crate) fn sort_bugs(&mut self, key: SortKey, descending: bool) {
+        self.sort_key = key;
+        self.sort_descending = descending;
+        self.apply_view_filters();
+    }
+
+    /// Handles a key typed while editing the search filter, the list is
+    /// filtered as the query is typed.
+    pub(crate) fn edit_filter(&mut self, code: KeyCode) {
+        match code {
+            KeyCode::Char(c) => self.filter_query.push(c),
+            KeyCode::Backspace => {
+                self.filter_query.pop();
+            }
+            KeyCode::Enter => self.filter_editing = false,
+            KeyCode::Esc => {
+                self.filter_query.clear();
+                self.filter_editing = false;
+            }
+            _ => return,
+        }
+        self.apply_view_filters();
+    }
+
+    /// Rebuilds the displayed bugs from the fetched ones, keeping the
+    /// selected bug selected if it is still displayed.
+    pub(crate) fn apply_view_filters(&mut self) {
+        let selected_id = self
+            .bug_table_state
+            .selected()
+            .and_then(|i| self.bug_table_items.get(i))
+            .map(|bug| bug.get_id());
+
+        // Commenting on or transitioning a bug requires to open it first, so
+        // the viewed bugs cover every bug I have interacted with.
+        let viewed = &self.session.seen_message_counts;
+        let query = self.filter_query.to_lowercase();
+        self.bug_table_items = self
+            .all_bug_items
+            .iter()
+            .filter(|bug| !self.only_my_activity || viewed.contains_key(&bug.get_id()))
+            .filter(|bug| {
+                query.is_empty()
+                    || bug
+                        .bug_title()
+                        .unwrap_or(&bug.title)
+                        .to_lowercase()
+                        .contains(&query)
+            })
+            .cloned()
+            .collect();
+        sort_bug_tasks(
+            &mut self.bug_table_items,
+            self.sort_key,
+            self.sort_descending,
+        );
+        self.status_counts = status_counts(&self.bug_table_items);
+        self.update_listed_tags();
+        self.rebuild_bug_table_rows();
+
+        let selected = if self.bug_table_items.is_empty() {
+            None
+        } else {
+            Some(
+                selected_id
+                    .and_then(|id| self.bug_table_items.iter().position(|b| b.get_id() == id))
+                    .unwrap_or(0),
+            )
+        };
+        self.bug_table_state.select(selected);
+        self.bug_table_scrollbar_state =
+            ScrollbarState::new(self.bug_table_items.len()).position(selected.unwrap_or(0));
+    }
+
+    /// Summary line like "New: 42  Incomplete: 7", none without bugs.
+    pub(crate) fn status_summary(&self) -> Option<String> {
+        if self.status_counts.is_empty() {
+            return None;
+        }
+        Some(
+            self.status_counts
+                .iter()
+                .map(|(status, count)| format!("{status}: {count}"))
+                .collect::<Vec<_>>()
+                .join("  "),
+        )
+    }
+
+    /// Changes the regex used to parse the bug titles and re-renders the
+    /// table rows from the items already fetched.
+    pub(crate) fn set_title_regex(&mut self, re: Regex) {
+        self.title_regex = re;
+        self.bug_row_cache.clear();
+        self.rebuild_bug_table_rows();
+    }
+
+    fn rebuild_bug_table_rows(&mut self) {
+        let re = &self.title_regex;
+        let unassigned = if self.symbols.unicode { "—" } else { "-" };
+        let cache = &mut self.bug_row_cache;
         self.bug_table_rows = self
             .bug_table_items
             .iter()
             .map(|item: &BugTaskEntry| {
+                if let Some((etag, row)) = cache.get(&item.self_link)
+                    && *etag == item.http_etag
+                {
+                    return row.clone();
+                }
                 let height = 1;
 
-                let extract_from_title = |o| -> (String, String) {
-                    if let Some(caps) = re.captures(o) {
-                        let id = &caps[1];
-                        let title = &caps[2];
-                        (id.to_string(), title.to_string())
-                    } else {
-                        ("".to_string(), "".to_string())
-                    }
-                };
-
-                let (id, title) = extract_from_title(&item.title);
+                let (id, title) = task_id_and_title(re, item);
 
                 let cells = vec![
                     Cell::from(id),
-                    // I think we can unwrap safely as I guess we always have a date_created
-                    Cell::from(item.date_created.unwrap().clone().date_naive().to_string()),
+                    // Launchpad dates in an unknown format are dropped
+                    Cell::from(
+                        item.date_created
+                            .map_or("unknown".to_string(), |date| date.date_naive().to_string()),
+                    ),
+                    Cell::from(item.status.clone()),
+                    Cell::from(item.importance.clone()).style(
+                        Style::default().fg(importance_color(&self.theme, &item.importance)),
+                    ),
+                    Cell::from(item.assignee_name().unwrap_or(unassigned).to_string()),
                     Cell::from(title),
                 ];
-                Row::new(cells).height(height as u16).bottom_margin(1)
+                let row = Row::new(cells).height(height as u16).bottom_margin(1);
+                cache.insert(
+                    item.self_link.clone(),
+                    (item.http_etag.clone(), row.clone()),
+                );
+                row
             })
             .collect();
-        self.bug_table_state.select(Some(0));
-        self.bug_table_scrollbar_state = ScrollbarState::new(self.bug_table_items.len());
-        self.spinner_enabled = false;
     }
 
+    /// Opens a bug, from the cache if it was recently fetched.
     pub(crate) fn get_bug(&mut self, bug_id: u32) {
-        self.spinner_enabled = true;
+        let Some(cached) = self
+            .bug_cache
+            .get(bug_id)
+            .filter(|cached| cached.is_complete())
+            .cloned()
+        else {
+            self.fetch_bug(bug_id);
+            return;
+        };
+        info!("Bug {bug_id} opened from the cache");
+        self.show_bug(cached.bug);
+        self.update_bug_messages(bug_id, cached.messages.unwrap_or_default());
+        self.update_bug_attachments(bug_id, cached.attachments.unwrap_or_default());
+        match cached.tasks {
+            Some(tasks) => self.update_bug_tasks(bug_id, tasks),
+            None => self.get_bug_tasks(bug_id),
+        }
+    }
+
+    /// Fetches a bug from Launchpad, bypassing the cache.
+    pub(crate) fn fetch_bug(&mut self, bug_id: u32) {
+        self.loading.bug = true;
         let sender = self.lp_sender.clone();
         let client = self.launchpad_client.clone();
-        tokio::spawn(async move {
+        self.tasks.spawn("Bug", async move {
             info!("Task to get bug started");
 
-            match lp_get_bug(&*client, bug_id).await {
-                Ok(bug) => {
-                    if let Err(e) = sender.send(LpMessage::Bug(bug.into())).await {
-                        error!("Fail to send message, error {e}");
+            match get_bug_full(&*client, bug_id).await {
+                Ok(details) => {
+                    let mut updates = vec![LpMessage::Bug(details.bug.into())];
+                    match details.messages {
+                        Ok(messages) => updates.push(LpMessage::BugMessages { bug_id, messages }),
+                        Err(e) => warn!("Fail to get messages of bug {bug_id}, error {e}"),
+                    }
+                    match details.attachments {
+                        Ok(attachments) => updates.push(LpMessage::BugAttachments {
+                            bug_id,
+                            attachments,
+                        }),
+                        Err(e) => warn!("Fail to get attachments of bug {bug_id}, error {e}"),
+                    }
+                    for msg in updates {
+                        if let Err(e) = sender.send(msg).await {
+                            error!("Fail to send message, error {e}");
+                        }
                     }
                 }
                 Err(e) => {
@@ -244,16 +1214,1991 @@ impl App {
     }
 
     pub(crate) fn update_bug(&mut self, bug: LaunchpadBug) {
+        let bug_id = bug.id;
+        self.update_bug_tags(bug_id, bug.tags.clone());
+        self.bug_cache.insert(bug.clone());
+        self.show_bug(bug);
+        self.get_bug_tasks(bug_id);
+    }
+
+    fn show_bug(&mut self, bug: LaunchpadBug) {
+        self.new_comments = self
+            .session
+            .record_message_count(bug.id, bug.message_count)
+            .filter(|n| *n > 0);
+        if let Err(e) = self.session.save() {
+            warn!("Fail to save session, error {e}");
+        }
+        self.resolve_person(&bug.owner_link);
+        self.current_bug_tasks.clear();
+        // The messages and attachments are fetched with the bug
+        self.current_bug_messages.clear();
+        self.current_bug_attachments.clear();
+        self.current_bug_activity = None;
+        self.show_activity = false;
+        self.loading.activity = false;
+        self.displayed_conversation = bug.description.clone();
+        self.description_stats = self.reading_time.then(|| reading_stats(&bug.description));
+        *self.gemini_response.lock().unwrap() = bug.description.clone();
+        // The backup belongs to the previous bug
+        self.edit_backup = None;
         self.current_bug = Some(bug);
-        let mut response_guard = self.gemini_response.lock().unwrap();
-        *response_guard = self.current_bug.as_ref().unwrap().description.clone();
         self.bug_desc_scroll = 0;
         self.bug_desc_scroll_to_end = false;
-        self.spinner_enabled = false;
+        self.loading.bug = false;
+    }
+
+    fn get_bug_tasks(&mut self, bug_id: u32) {
+        let sender = self.lp_sender.clone();
+        let client = self.launchpad_client.clone();
+        self.tasks.spawn("Bug tasks", async move {
+            match get_bug_tasks(&*client, bug_id).await {
+                Ok(tasks) => {
+                    if let Err(e) = sender.send(LpMessage::BugTasks { bug_id, tasks }).await {
+                        error!("Fail to send message, error {e}");
+                    }
+                }
+                Err(e) => warn!("Fail to get tasks of bug {bug_id}, error {e}"),
+            }
+        });
+    }
+
+    fn get_bug_messages(&mut self, bug: &LaunchpadBug) {
+        let bug = bug.clone();
+        let sender = self.lp_sender.clone();
+        let client = self.launchpad_client.clone();
+        self.tasks.spawn("Bug messages", async move {
+            match get_bug_messages(&*client, &bug).await {
+                Ok(messages) => {
+                    let msg = LpMessage::BugMessages {
+                        bug_id: bug.id,
+                        messages,
+                    };
+                    if let Err(e) = sender.send(msg).await {
+                        error!("Fail to send message, error {e}");
+                    }
+                }
+                Err(e) => warn!("Fail to get messages of bug {}, error {e}", bug.id),
+            }
+        });
+    }
+
+    pub(crate) fn update_bug_attachments(&mut self, bug_id: u32, attachments: Vec<BugAttachment>) {
+        if let Some(cached) = self.bug_cache.entry_mut(bug_id) {
+            cached.attachments = Some(attachments.clone());
+        }
+        if self.current_bug.as_ref().is_none_or(|bug| bug.id != bug_id) {
+            return;
+        }
+        self.current_bug_attachments = attachments;
+        self.refresh_conversation();
+    }
+
+    pub(crate) fn update_bug_messages(&mut self, bug_id: u32, messages: Vec<BugMessage>) {
+        if let Some(cached) = self.bug_cache.entry_mut(bug_id) {
+            cached.messages = Some(messages.clone());
+        }
+        if self.current_bug.as_ref().is_none_or(|bug| bug.id != bug_id) {
+            return;
+        }
+        for link in messages.iter().filter_map(|m| m.owner_link.clone()) {
+            self.resolve_person(&link);
+        }
+        self.current_bug_messages = messages;
+        self.refresh_conversation();
+    }
+
+    /// Shows the activity of the current bug in the description panel, or
+    /// back its conversation. The activity is fetched the first time.
+    pub(crate) fn toggle_activity(&mut self) {
+        let Some(bug) = &self.current_bug else {
+            return;
+        };
+        self.show_activity = !self.show_activity;
+        self.bug_desc_scroll = 0;
+        if !self.show_activity || self.current_bug_activity.is_some() || self.loading.activity {
+            return;
+        }
+        self.loading.activity = true;
+        let bug = bug.clone();
+        let sender = self.lp_sender.clone();
+        let client = self.launchpad_client.clone();
+        self.tasks.spawn("Bug activity", async move {
+            let result = get_bug_activity(&*client, &bug).await;
+            let msg = LpMessage::BugActivity {
+                bug_id: bug.id,
+                result,
+            };
+            if let Err(e) = sender.send(msg).await {
+                error!("Fail to send message, error {e}");
+            }
+        });
+    }
+
+    pub(crate) fn update_bug_activity(
+        &mut self,
+        bug_id: u32,
+        result: Result<Vec<BugActivity>, LaunchpadError>,
+    ) {
+        if self.current_bug.as_ref().is_none_or(|bug| bug.id != bug_id) {
+            return;
+        }
+        self.loading.activity = false;
+        match result {
+            Ok(activity) => {
+                for link in activity.iter().filter_map(|a| a.person_link.clone()) {
+                    self.resolve_person(&link);
+                }
+                self.current_bug_activity = Some(activity);
+            }
+            Err(e) => {
+                warn!("Fail to get activity of bug {bug_id}, error {e}");
+                self.status_message = Some(format!("Fail to get the bug activity: {e}"));
+                self.show_activity = false;
+            }
+        }
+    }
+
+    /// Returns the changes made to the current bug, one per line.
+    pub(crate) fn activity_timeline(&self) -> String {
+        let Some(activity) = &self.current_bug_activity else {
+            return String::new();
+        };
+        if activity.is_empty() {
+            return "No activity on this bug".to_string();
+        }
+        let value = |value: &Option<String>| match value.as_deref() {
+            None | Some("") => "-".to_string(),
+            Some(value) => value.to_string(),
+        };
+        activity
+            .iter()
+            .map(|change| {
+                let date = change.datechanged.map_or("unknown".to_string(), |d| {
+                    d.format("%Y-%m-%d %H:%M").to_string()
+                });
+                let person = change
+                    .person_link
+                    .as_deref()
+                    .map_or("unknown", |link| self.person_name(link));
+                let mut line = format!("{date} {person}: {}", change.whatchanged);
+                if change.oldvalue.is_some() || change.newvalue.is_some() {
+                    line.push_str(&format!(
+                        " {} -> {}",
+                        value(&change.oldvalue),
+                        value(&change.newvalue)
+                    ));
+                }
+                if let Some(message) = change.message.as_deref().filter(|m| !m.is_empty()) {
+                    line.push_str(&format!(" ({message})"));
+                }
+                line
+            })
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+
+    /// Returns the bug description followed by its comments.
+    pub(crate) fn conversation(&self) -> String {
+        let Some(bug) = &self.current_bug else {
+            return String::new();
+        };
+        let mut text = bug.description.clone();
+        // The first message is the description itself
+        for (i, message) in self.current_bug_messages.iter().enumerate().skip(1) {
+            let author = message
+                .owner_link
+                .as_deref()
+                .map_or("unknown", |link| self.person_name(link));
+            let date = message
+                .date_created
+                .map(|d| format!(" on {}", d.date_naive()))
+                .unwrap_or_default();
+            text.push_str(&format!(
+                "\n\n--- Comment #{i} by {author}{date} ---\n{}",
+                message.content.trim_end()
+            ));
+        }
+        if !self.current_bug_attachments.is_empty() {
+            text.push_str("\n\n--- Attachments ---");
+            for attachment in &self.current_bug_attachments {
+                text.push_str(&format!(
+                    "\n- {} ({})",
+                    attachment.title, attachment.data_link
+                ));
+            }
+        }
+        text
+    }
+
+    /// Updates the description panel with the conversation, unless its
+    /// content was replaced meanwhile.
+    fn refresh_conversation(&mut self) {
+        let conversation = self.conversation();
+        let mut displayed = self.gemini_response.lock().unwrap();
+        if *displayed == self.displayed_conversation {
+            displayed.clone_from(&conversation);
+            self.displayed_conversation = conversation;
+        }
+    }
+
+    pub(crate) fn update_bug_tasks(&mut self, bug_id: u32, tasks: Vec<BugTaskEntry>) {
+        if let Some(cached) = self.bug_cache.entry_mut(bug_id) {
+            cached.tasks = Some(tasks.clone());
+        }
+        // Ignore the tasks of a bug closed in the meantime
+        if self
+            .current_bug
+            .as_ref()
+            .is_some_and(|bug| bug.id == bug_id)
+        {
+            self.current_bug_tasks = tasks;
+        }
+    }
+
+    /// Returns the live status of the current bug if it differs from the listed one.
+    pub(crate) fn status_change(&self) -> Option<&str> {
+        let bug = self.current_bug.as_ref()?;
+        let listed = self.all_bug_items.iter().find(|t| t.get_id() == bug.id)?;
+        let live = self
+            .current_bug_tasks
+            .iter()
+            .find(|t| t.self_link == listed.self_link)?;
+        (live.status != listed.status).then_some(live.status.as_str())
+    }
+
+    /// Fetches the display name of a person unless it is already known.
+    ///
+    /// The account name taken from the link is cached meanwhile, so it is
+    /// displayed while fetching and kept if the fetch fails.
+    pub(crate) fn resolve_person(&mut self, link: &str) {
+        if self.person_names.contains_key(link) {
+            return;
+        }
+        self.person_names
+            .insert(link.to_string(), person_name_from_link(link).to_string());
+
+        let link = link.to_string();
+        let sender = self.lp_sender.clone();
+        let client = self.launchpad_client.clone();
+        self.tasks.spawn("Person", async move {
+            match get_person(&*client, &link).await {
+                Ok(person) => {
+                    let msg = LpMessage::Person {
+                        link,
+                        name: person.display_name,
+                    };
+                    if let Err(e) = sender.send(msg).await {
+                        error!("Fail to send message, error {e}");
+                    }
+                }
+                Err(e) => warn!("Fail to get person {link}, error {e}"),
+            }
+        });
+    }
+
+    pub(crate) fn update_person_name(&mut self, link: String, name: String) {
+        self.person_names.insert(link, name);
+        self.refresh_conversation();
+    }
+
+    /// Returns the display name of a person, or its account name if not resolved.
+    pub(crate) fn person_name<'a>(&'a self, link: &'a str) -> &'a str {
+        self.person_names
+            .get(link)
+            .map(String::as_str)
+            .unwrap_or_else(|| person_name_from_link(link))
+    }
+
+    /// Returns the prompt asking the chat to craft a reply to the displayed
+    /// bug, the initial prompt followed by the bug.
+    pub(crate) fn craft_reply_prompt(&self) -> anyhow::Result<String> {
+        let initial_prompt = get_initial_prompt(self.prompt_file.as_deref())?;
+        let bug = self.gemini_response.lock().unwrap().clone();
+        Ok(format!("{initial_prompt}\n{bug}"))
+    }
+
+    /// Shows the prompt a reply would be crafted with, without sending it.
+    pub(crate) fn preview_prompt(&mut self) {
+        match self.craft_reply_prompt() {
+            Ok(prompt) => {
+                // The prompt is useless without the bug after it
+                if self.gemini_response.lock().unwrap().trim().is_empty() {
+                    self.status_message = Some("The bug description is empty".to_string());
+                }
+                self.popup = Some(Popup::PromptPreview { prompt, scroll: 0 });
+            }
+            Err(e) => self.status_message = Some(format!("{e:#}")),
+        }
+    }
+
+    /// Sends a prompt to the chat, which answers in the reply panel.
+    pub(crate) async fn send_to_chat(&mut self, prompt: String) -> anyhow::Result<()> {
+        self.app_sender.send(prompt.clone()).await?;
+        self.push_chat_turn(ChatRole::User, prompt);
+        self.loading.reply = true;
+        Ok(())
+    }
+
+    fn push_chat_turn(&mut self, role: ChatRole, text: String) {
+        self.chat_history.push(ChatTurn { role, text });
+        self.chat_scroll_to_end = true;
+    }
+
+    /// Streams the chat reply into the reply panel, the spinner runs until
+    /// the reply is complete.
+    pub(crate) fn update_bug_reply(&mut self, msg: ChatMessage) {
+        match msg {
+            ChatMessage::Started => {
+                self.bug_reply_text.clear();
+                self.bug_reply_scroll = 0;
+                self.bug_reply_scroll_to_end = false;
+            }
+            ChatMessage::Chunk(chunk) => self.bug_reply_text.push_str(&chunk),
+            ChatMessage::Done => {
+                info!("Chat response received");
+                self.loading.reply = false;
+                self.push_chat_turn(ChatRole::Model, self.bug_reply_text.clone());
+                self.save_reply_draft();
+            }
+            // The reply is kept, the request can be sent again
+            ChatMessage::Error(e) => {
+                self.loading.reply = false;
+                self.set_error(e);
+            }
+        }
+    }
+
+    /// Aborts the pending AI request, the displayed text is left untouched as
+    /// the answer is only written once complete.
+    pub(crate) fn cancel_ai_request(&mut self) {
+        match self.ai_request.take() {
+            Some(request) if !request.is_finished() => {
+                request.abort();
+                info!("AI request cancelled");
+                self.loading.ai = false;
+                self.status_message = Some("AI request cancelled".to_string());
+            }
+            _ => self.status_message = Some("No AI request in progress".to_string()),
+        }
+    }
+
+    /// Stops the AI loading indicator once the request is answered, showing its error
+    /// if it failed.
+    pub(crate) fn check_ai_request(&mut self) {
+        if self
+            .ai_request
+            .as_ref()
+            .is_some_and(|request| request.is_finished())
+        {
+            self.ai_request = None;
+            self.loading.ai = false;
+            let error = self.ai_error.lock().unwrap().take();
+            if let Some(error) = error {
+                self.set_error(error);
+            }
+        }
+    }
+
+    /// Copies the link of the selected bug to the clipboard.
+    pub(crate) fn copy_bug_link(&mut self) {
+        let link = self
+            .bug_table_state
+            .selected()
+            .and_then(|index| self.bug_table_items.get(index))
+            .map(|bug| bug.web_link.clone());
+        match link {
+            Some(link) => self.copy_to_clipboard("link", &link),
+            None => self.status_message = Some("No bug selected".to_string()),
+        }
+    }
+
+    /// Copies the description of the current bug to the clipboard.
+    pub(crate) fn copy_bug_description(&mut self) {
+        match self.current_bug.as_ref().map(|bug| bug.description.clone()) {
+            Some(description) => self.copy_to_clipboard("description", &description),
+            None => self.status_message = Some("No bug opened yet".to_string()),
+        }
+    }
+
+    fn copy_to_clipboard(&mut self, what: &str, text: &str) {
+        match self.clipboard.copy(text) {
+            Ok(()) => self.status_message = Some(format!("Copied the {what} to the clipboard")),
+            Err(e) => self.set_error(format!("Fail to copy the {what}: {e}")),
+        }
+    }
+
+    /// Asks where to export the current bug as markdown, offering a file
+    /// named after it.
+    pub(crate) fn start_markdown_export(&mut self) {
+        match &self.current_bug {
+            Some(bug) => {
+                let path = format!("{}.md", bug.id);
+                self.popup = Some(Popup::ExportMarkdown(TextInput::new(path)));
+            }
+            None => self.status_message = Some("No bug opened yet".to_string()),
+        }
+    }
+
+    /// Writes the current bug as a markdown file.
+    pub(crate) fn export_bug_markdown(&mut self, path: &str) {
+        let Some(bug) = &self.current_bug else {
+            self.status_message = Some("No bug opened yet".to_string());
+            return;
+        };
+        self.status_message = Some(match std::fs::write(path, bug_to_markdown(bug)) {
+            Ok(()) => format!("Bug exported to {path}"),
+            Err(e) => {
+                error!("Fail to export bug to {path}, error {e}");
+                format!("Fail to export bug to {path}: {e}")
+            }
+        });
+    }
+
+    /// Writes the reply to a file named after the bug, keeping it once the
+    /// application is closed.
+    pub(crate) fn write_reply(&mut self) {
+        let Some(bug) = &self.current_bug else {
+            self.status_message = Some("No bug opened yet".to_string());
+            return;
+        };
+        let path = self.reply_dir.join(format!("bug-{}-reply.txt", bug.id));
+        let result = std::fs::create_dir_all(&self.reply_dir)
+            .and_then(|()| std::fs::write(&path, &self.bug_reply_text));
+        self.status_message = Some(match result {
+            Ok(()) => format!("Reply written to {}", path.display()),
+            Err(e) => {
+                error!("Fail to write reply to {}, error {e}", path.display());
+                format!("Fail to write reply to {}: {e}", path.display())
+            }
+        });
+    }
+
+    /// Opens the bug the current one is a duplicate of.
+    pub(crate) fn open_duplicate_of(&mut self) {
+        match self
+            .current_bug
+            .as_ref()
+            .and_then(LaunchpadBug::duplicate_of)
+        {
+            Some(bug_id) => self.get_bug(bug_id),
+            None => self.status_message = Some("This bug is not a duplicate".to_string()),
+        }
+    }
+
+    /// Opens the bug whose number was typed, even if it is not listed, and
+    /// focuses the description panel.
+    pub(crate) fn go_to_bug(&mut self, text: &str) {
+        let text = text.trim().trim_start_matches('#');
+        match text.parse::<u32>() {
+            Ok(bug_id) if bug_id > 0 => {
+                self.get_bug(bug_id);
+                self.active_panel = ActivePanel::Right;
+            }
+            _ => self.popup = Some(Popup::Error(format!("'{text}' is not a bug number"))),
+        }
+    }
+
+    /// Exports the displayed bugs, so the export matches the filters.
+    pub(crate) fn export_bug_list(&mut self, path: &str) {
+        self.status_message = Some(match export_bugs(Path::new(path), &self.bug_table_items) {
+            Ok(()) => format!("{} bugs exported to {path}", self.bug_table_items.len()),
+            Err(e) => {
+                error!("Fail to export bugs to {path}, error {e}");
+                format!("Fail to export bugs to {path}: {e:#}")
+            }
+        });
+    }
+
+    /// Posts the reply as a comment on the current bug.
+    pub(crate) fn post_reply(&mut self) {
+        let Some(bug) = &self.current_bug else {
+            self.status_message = Some("No bug opened yet".to_string());
+            return;
+        };
+        self.loading.reply = true;
+        let bug_id = bug.id;
+        let content = self.bug_reply_text.clone();
+        let sender = self.lp_sender.clone();
+        let client = self.launchpad_client.clone();
+        self.tasks.spawn("Post comment", async move {
+            info!("Task to post comment started");
+            let result = post_bug_comment(&*client, bug_id, &content).await;
+            if let Err(e) = sender
+                .send(LpMessage::CommentPosted { bug_id, result })
+                .await
+            {
+                error!("Fail to send message, error {e}");
+            }
+            info!("Task to post comment completed");
+        });
+    }
+
+    pub(crate) fn update_comment_posted(
+        &mut self,
+        bug_id: u32,
+        result: Result<(), LaunchpadError>,
+    ) {
+        self.loading.reply = false;
+        if let Err(e) = result {
+            error!("Fail to post comment on bug {bug_id}, error {e}");
+            self.status_message = Some(format!("Fail to post the reply: {e}"));
+            self.mark_incomplete_after_post = None;
+            return;
+        }
+        if let Some((_, task_link)) = self
+            .mark_incomplete_after_post
+            .take_if(|(id, _)| *id == bug_id)
+        {
+            self.set_task_status(bug_id, task_link, StatusFilter::Incomplete);
+        }
+        self.status_message = Some(format!("Reply posted on bug #{bug_id}"));
+        self.reply_dirty = false;
+        // The cached bug misses the reply
+        self.bug_cache.invalidate(bug_id);
+        if let Err(e) = self.session.remove_draft(bug_id) {
+            warn!("Fail to remove reply draft, error {e}");
+        }
+        // Our own comment is not a new one
+        if let Some(seen) = self.session.seen_message_counts.get_mut(&bug_id) {
+            *seen += 1;
+            if let Err(e) = self.session.save() {
+                warn!("Fail to save session, error {e}");
+            }
+        }
+        if let Some(bug) = self.current_bug.clone().filter(|bug| bug.id == bug_id) {
+            self.get_bug_messages(&bug);
+        }
+    }
+
+    /// Posts the reply, then sets the bug Incomplete as the reply asks the
+    /// reporter for more information.
+    pub(crate) fn post_reply_and_mark_incomplete(&mut self) {
+        let Some(bug) = &self.current_bug else {
+            self.status_message = Some("No bug opened yet".to_string());
+            return;
+        };
+        if !self.launchpad_authenticated {
+            self.set_error(
+                "Launchpad credentials are required to change the bug status, set \
+                 LAUNCHPAD_CONSUMER_KEY, LAUNCHPAD_TOKEN and LAUNCHPAD_TOKEN_SECRET"
+                    .to_string(),
+            );
+            return;
+        }
+        let bug_id = bug.id;
+        // The listed task is the one of the project, a bug opened by id is
+        // only known by its tasks
+        let Some(task_link) = self
+            .all_bug_items
+            .iter()
+            .find(|task| task.get_id() == bug_id)
+            .or_else(|| {
+                self.current_bug_tasks
+                    .iter()
+                    .find(|task| task.bug_target_name == self.project)
+            })
+            .map(|task| task.self_link.clone())
+        else {
+            self.status_message = Some(format!("Bug #{bug_id} has no {} task", self.project));
+            return;
+        };
+        self.mark_incomplete_after_post = Some((bug_id, task_link));
+        self.post_reply();
+    }
+
+    fn set_task_status(&mut self, bug_id: u32, task_link: String, status: StatusFilter) {
+        let sender = self.lp_sender.clone();
+        let client = self.launchpad_client.clone();
+        self.tasks.spawn("Change status", async move {
+            info!("Task to change status started");
+            let result = set_bug_task_status(&*client, &task_link, status).await;
+            if let Err(e) = sender
+                .send(LpMessage::StatusChanged {
+                    bug_id,
+                    task_link,
+                    status,
+                    result,
+                })
+                .await
+            {
+                error!("Fail to send message, error {e}");
+            }
+            info!("Task to change status completed");
+        });
+    }
+
+    pub(crate) fn update_status_changed(
+        &mut self,
+        bug_id: u32,
+        task_link: &str,
+        status: StatusFilter,
+        result: Result<(), LaunchpadError>,
+    ) {
+        if let Err(e) = result {
+            self.set_error(format!("Fail to set bug #{bug_id} {status}: {e}"));
+            return;
+        }
+        let cached_tasks = self
+            .bug_cache
+            .entry_mut(bug_id)
+            .and_then(|cached| cached.tasks.as_mut());
+        self.all_bug_items
+            .iter_mut()
+            .chain(self.current_bug_tasks.iter_mut())
+            .chain(cached_tasks.into_iter().flatten())
+            .filter(|task| task.self_link == task_link)
+            .for_each(|task| task.status = status.to_string());
+        // The etag of the task is the one fetched, its row is built again
+        self.bug_row_cache.remove(task_link);
+        self.apply_view_filters();
+        self.status_message = Some(format!("Bug #{bug_id} set {status}"));
+    }
+
+    /// Saves the reply in the outbox so it can be reviewed before being posted.
+    pub(crate) fn save_reply_to_outbox(&mut self) {
+        let Some(bug) = &self.current_bug else {
+            self.status_message = Some("No bug opened yet".to_string());
+            return;
+        };
+        let Some(dir) = outbox_dir() else {
+            self.status_message = Some("No outbox directory available".to_string());
+            return;
+        };
+        self.status_message = Some(
+            match OutboxEntry::new(bug, &self.bug_reply_text).write(&dir) {
+                Ok(path) => {
+                    // It is posted from the outbox
+                    self.reply_dirty = false;
+                    format!("Reply saved to {}", path.display())
+                }
+                Err(e) => {
+                    error!("Fail to save reply to the outbox, error {e}");
+                    format!("Fail to save reply to the outbox: {e}")
+                }
+            },
+        );
+    }
+
+    /// Appends a quoted excerpt of a prior message to the reply.
+    pub(crate) fn quote_in_reply(&mut self, text: &str) {
+        if self.bug_reply_text == REPLY_PLACEHOLDER {
+            self.bug_reply_text.clear();
+        }
+        if !self.bug_reply_text.is_empty() && !self.bug_reply_text.ends_with('\n') {
+            self.bug_reply_text.push('\n');
+        }
+        self.bug_reply_text.push_str(&quote_excerpt(text));
+        self.reply_dirty = true;
+        self.save_reply_draft();
+    }
+
+    /// Replaces the reply with the AI answer and moves to the reply panel.
+    pub(crate) fn use_ai_answer(&mut self) {
+        let answer = self.gemini_response.lock().unwrap().clone();
+        if answer.trim().is_empty() {
+            self.status_message = Some("There is no AI answer to reply with".to_string());
+            return;
+        }
+        self.bug_reply_text = answer;
+        self.reply_dirty = true;
+        self.bug_reply_scroll = 0;
+        self.bug_reply_scroll_to_end = false;
+        self.chat_focused = false;
+        self.current_screen = Screen::BugEditing;
+        self.active_panel = ActivePanel::Left;
+        self.save_reply_draft();
+    }
+
+    /// Whether the application can quit right away, otherwise asks to
+    /// confirm as the edited reply would be lost.
+    pub(crate) fn request_quit(&mut self) -> bool {
+        if !self.reply_dirty {
+            return true;
+        }
+        if !self.confirm_quit {
+            // The status bar is gone with the application, the draft is kept
+            warn!("Quitting with an edited reply without confirmation (confirm_quit = false)");
+            return true;
+        }
+        self.popup = Some(Popup::ConfirmQuit);
+        false
+    }
+
+    /// Whether `action` is done without the confirmation disabled in the
+    /// configuration, which is then told in the status bar.
+    pub(crate) fn skips_confirmation(&mut self, action: Action) -> bool {
+        let (skipped, notice) = match action {
+            Action::PostReply => (
+                !self.confirm_post,
+                "Posting the reply without confirmation (confirm_post = false)",
+            ),
+            // Both the post and the status change must be trusted
+            Action::PostAndMarkIncomplete => (
+                !self.confirm_post && !self.confirm_status_change,
+                "Posting the reply and marking the bug Incomplete without confirmation \
+                 (confirm_post = false, confirm_status_change = false)",
+            ),
+            _ => return false,
+        };
+        if skipped {
+            info!("{notice}");
+            self.status_message = Some(notice.to_string());
+        }
+        skipped
+    }
+
+    /// Autosaves the reply being crafted for the current bug.
+    pub(crate) fn save_reply_draft(&self) {
+        if let Some(bug) = &self.current_bug
+            && let Err(e) = self.session.save_draft(bug.id, &self.bug_reply_text)
+        {
+            warn!("Fail to save reply draft, error {e}");
+        }
+    }
+
+    /// Replaces a text with the content written in the external editor,
+    /// keeping the previous one to undo the edit.
+    pub(crate) fn apply_edit(&mut self, target: EditTarget, updated: String) {
+        let previous = match target {
+            EditTarget::Description => {
+                std::mem::replace(&mut *self.gemini_response.lock().unwrap(), updated)
+            }
+            EditTarget::Reply => {
+                let previous = std::mem::replace(&mut self.bug_reply_text, updated);
+                if previous != self.bug_reply_text {
+                    self.reply_dirty = true;
+                }
+                self.save_reply_draft();
+                previous
+            }
+        };
+        self.edit_backup = Some((target, previous));
+    }
+
+    /// Restores the text as it was before the last external edit.
+    pub(crate) fn undo_edit(&mut self, target: EditTarget) {
+        let Some((_, previous)) = self.edit_backup.take_if(|(edited, _)| *edited == target) else {
+            self.status_message = Some("No edit to undo".to_string());
+            return;
+        };
+        match target {
+            EditTarget::Description => *self.gemini_response.lock().unwrap() = previous,
+            EditTarget::Reply => {
+                self.bug_reply_text = previous;
+                self.reply_dirty = true;
+                self.save_reply_draft();
+            }
+        }
+        self.status_message = Some("Restored the text before the last edit".to_string());
+    }
+
+    /// Continues the reply from its saved draft, which is not posted yet.
+    pub(crate) fn restore_draft(&mut self, draft: String) {
+        self.bug_reply_text = draft;
+        self.reply_dirty = true;
+    }
+
+    /// Starts a new reply, offering to restore the draft saved for this bug if there is one.
+    pub(crate) fn start_bug_reply(&mut self) {
+        self.bug_reply_text = REPLY_PLACEHOLDER.to_string();
+        self.reply_dirty = false;
+        self.edit_backup
+            .take_if(|(target, _)| *target == EditTarget::Reply);
+        self.bug_reply_scroll = 0;
+        self.bug_reply_scroll_to_end = false;
+        if let Some(bug) = &self.current_bug
+            && let Some(draft) = self.session.load_draft(bug.id)
+        {
+            self.popup = Some(Popup::RestoreDraft(draft));
+        }
+    }
+}
+
+/// Returns the number of words of a text and the minutes it takes to read
+/// it, rounded and at least one.
+pub(crate) fn reading_stats(text: &str) -> (usize, usize) {
+    let words = text.split_whitespace().count();
+    let minutes = (words + WORDS_PER_MINUTE / 2) / WORDS_PER_MINUTE;
+    (words, minutes.max(1))
+}
+
+/// Returns the bug id and title of a task, the title regex is only used if
+/// the task title is not in the usual Launchpad form. A title the regex does
+/// not match either is shown as is, with the id of the bug link.
+pub(crate) fn task_id_and_title(re: &Regex, task: &BugTaskEntry) -> (String, String) {
+    match (task.bug_id(), task.bug_title()) {
+        (Some(id), Some(title)) => (id.to_string(), title.to_string()),
+        _ => parse_title(re, &task.title).unwrap_or_else(|| {
+            warn!("Unexpected bug task title \"{}\", shown as is", task.title);
+            (
+                task.bug_id().map(|id| id.to_string()).unwrap_or_default(),
+                task.title.clone(),
+            )
+        }),
+    }
+}
+
+/// Extracts the bug id and the bug title from a bug task title.
+pub(crate) fn parse_title(re: &Regex, title: &str) -> Option<(String, String)> {
+    let caps = re.captures(title)?;
+    Some((caps[1].to_string(), caps[2].to_string()))
+}
+
+/// Sorts bug tasks by a column, Launchpad order is used for the importance
+/// and the status rather than the alphabetical one.
+fn sort_bug_tasks(tasks: &mut [BugTaskEntry], key: SortKey, descending: bool) {
+    let importance_rank = |task: &BugTaskEntry| {
+        Importance::ALL
+            .iter()
+            .position(|i| String::from(*i) == task.importance)
+    };
+    let status_rank = |task: &BugTaskEntry| {
+        task.status
+            .parse::<StatusFilter>()
+            .ok()
+            .and_then(|s| StatusFilter::ALL.iter().position(|a| *a == s))
+    };
+    tasks.sort_by(|a, b| {
+        let ordering = match key {
+            // The tasks without a date stay last whatever the direction
+            SortKey::Date => match (a.date_created, b.date_created) {
+                (Some(a), Some(b)) => a.cmp(&b),
+                (None, None) => return a.get_id().cmp(&b.get_id()),
+                (None, Some(_)) => return Ordering::Greater,
+                (Some(_), None) => return Ordering::Less,
+            },
+            SortKey::Id => a.get_id().cmp(&b.get_id()),
+            SortKey::Importance => importance_rank(a).cmp(&importance_rank(b)),
+            SortKey::Status => status_rank(a).cmp(&status_rank(b)),
+        };
+        if descending {
+            ordering.reverse()
+        } else {
+            ordering
+        }
+    });
+}
+
+/// Returns the statuses saved in the session, only the new bugs if there are none.
+fn session_statuses(session: &Session) -> Vec<StatusFilter> {
+    let statuses: Vec<StatusFilter> = session
+        .statuses
+        .iter()
+        .filter_map(|status| status.parse().ok())
+        .collect();
+    if statuses.is_empty() {
+        vec![StatusFilter::New]
+    } else {
+        statuses
+    }
+}
+
+/// Returns the position of the tag following the one at `current` among
+/// `len` tags, or preceding it when going backward. Without `wrap`, there is
+/// no tag after the last one.
+fn next_tag_position(
+    len: usize,
+    current: Option<usize>,
+    forward: bool,
+    wrap: bool,
+) -> Option<usize> {
+    let last = len.checked_sub(1)?;
+    match (current, forward) {
+        (Some(i), true) if i < last => Some(i + 1),
+        (Some(_), true) => wrap.then_some(0),
+        (Some(i), false) if i > 0 => Some(i - 1),
+        (Some(_), false) => wrap.then_some(last),
+        (None, true) => Some(0),
+        (None, false) => Some(last),
+    }
+}
+
+/// Returns the importance filter following `importance`: all, then Critical
+/// down to Undecided, then all again.
+fn next_importance(importance: Option<Importance>) -> Option<Importance> {
+    match importance {
+        None => Importance::ALL.last().copied(),
+        Some(importance) => Importance::ALL
+            .into_iter()
+            .rev()
+            .skip_while(|i| *i != importance)
+            .nth(1),
+    }
+}
+
+/// Checks a reply for the usual signs of a broken AI output before it is sent.
+pub(crate) fn lint_reply(reply: &str) -> Vec<String> {
+    let mut warnings = Vec::new();
+    let reply = reply.trim();
+    if reply.is_empty() || reply == REPLY_PLACEHOLDER {
+        warnings.push("The reply is empty".to_string());
+    }
+    if reply.contains("*** Start template ***") || reply.contains("*** end template ***") {
+        warnings.push("The reply contains the prompt template markers".to_string());
+    }
+    if reply.starts_with("Error while") {
+        warnings.push("The reply starts with an error message".to_string());
+    }
+    warnings
+}
+
+/// Prefixes every line of a text with "> " so it reads as a quote.
+pub(crate) fn quote_excerpt(text: &str) -> String {
+    text.trim_end()
+        .lines()
+        .map(|line| {
+            if line.is_empty() {
+                ">\n".to_string()
+            } else {
+                format!("> {line}\n")
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fixtures::{bug, bug_task, test_app};
+
+    #[test]
+    fn test_task_id_and_title() {
+        let re = Regex::new(DEFAULT_TITLE_REGEX).unwrap();
+        assert_eq!(
+            task_id_and_title(&re, &bug_task(42, "crash on boot")),
+            ("42".to_string(), "crash on boot".to_string())
+        );
+
+        // A project whose name the title regex does not expect
+        let mut task = bug_task(7, "");
+        task.bug_target_display_name = "Ubuntu: linux (Debian)".to_string();
+        task.title = "Bug #7 in Ubuntu: linux (Debian): \"no \"quotes\" here\"".to_string();
+        assert_eq!(
+            task_id_and_title(&re, &task),
+            ("7".to_string(), "no \"quotes\" here".to_string())
+        );
+
+        // Not the usual form, the regex is used
+        task.title = "Bug #8 in Other: \"fallback\"".to_string();
+        assert_eq!(
+            task_id_and_title(&re, &task),
+            ("8".to_string(), "fallback".to_string())
+        );
+    }
+
+    #[test]
+    fn test_parse_title() {
+        let re = Regex::new(DEFAULT_TITLE_REGEX).unwrap();
+        let title = "Bug #2093869 in OpenStack Compute (nova): \"update conflict\"";
+
+        assert_eq!(
+            parse_title(&re, title),
+            Some(("2093869".to_string(), "update conflict".to_string()))
+        );
+
+        let title = "Bug #42 in Neutron: \"port binding failure\"";
+        assert_eq!(
+            parse_title(&re, title),
+            Some(("42".to_string(), "port binding failure".to_string()))
+        );
+        assert_eq!(parse_title(&re, "Bug 42: no quotes"), None);
+    }
+
+    #[tokio::test]
+    async fn test_unexpected_title_row() {
+        let mut app = test_app();
+        let mut task = bug_task(42, "");
+        task.title = "[SRU] something unusual".to_string();
+        app.update_bugs(vec![task].into_boxed_slice());
+
+        // The raw title and the id of the bug link are shown
+        assert_eq!(
+            task_id_and_title(&app.title_regex, &app.bug_table_items[0]),
+            ("42".to_string(), "[SRU] something unusual".to_string())
+        );
+        assert_eq!(app.bug_table_rows.len(), 1);
+        app.bug_table_previous_item();
+        let selected = app.bug_table_state.selected().unwrap();
+        assert_eq!(app.bug_table_items[selected].get_id(), 42);
+    }
+
+    #[test]
+    fn test_reading_stats() {
+        assert_eq!(reading_stats(""), (0, 1));
+        assert_eq!(reading_stats("nova-compute  fails\n to\tstart"), (4, 1));
+        assert_eq!(reading_stats(&"word ".repeat(412)), (412, 2));
+    }
+
+    #[test]
+    fn test_next_importance() {
+        let mut importance = None;
+        let mut seen = Vec::new();
+        loop {
+            importance = next_importance(importance);
+            match importance {
+                Some(i) => seen.push(i),
+                None => break,
+            }
+        }
+        assert_eq!(seen.first(), Some(&Importance::Critical));
+        assert_eq!(seen.last(), Some(&Importance::Undecided));
+        assert_eq!(seen.len(), Importance::ALL.len());
+    }
+
+    #[tokio::test]
+    async fn test_streamed_bug_reply() {
+        let mut app = test_app();
+        app.bug_reply_text = "previous reply".to_string();
+        app.loading.reply = true;
+
+        app.update_bug_reply(ChatMessage::Started);
+        app.update_bug_reply(ChatMessage::Chunk("Thanks for ".to_string()));
+        app.update_bug_reply(ChatMessage::Chunk("the report.".to_string()));
+        assert_eq!(app.bug_reply_text, "Thanks for the report.");
+        assert!(app.loading.reply);
+
+        app.update_bug_reply(ChatMessage::Done);
+        assert!(!app.loading.reply);
+        assert_eq!(
+            app.chat_history,
+            [ChatTurn {
+                role: ChatRole::Model,
+                text: "Thanks for the report.".to_string()
+            }]
+        );
+        assert!(app.chat_scroll_to_end);
+    }
+
+    #[test]
+    fn test_sort_bug_tasks() {
+        let mut critical = bug_task(1, "a");
+        critical.importance = "Critical".to_string();
+        let mut low = bug_task(2, "b");
+        low.importance = "Low".to_string();
+        low.status = "Incomplete".to_string();
+        let undecided = bug_task(3, "c");
+        let mut tasks = vec![low, undecided, critical];
+
+        let ids = |tasks: &[BugTaskEntry]| tasks.iter().map(|t| t.get_id()).collect::<Vec<_>>();
+        sort_bug_tasks(&mut tasks, SortKey::Importance, true);
+        assert_eq!(ids(&tasks), [1, 2, 3]);
+        sort_bug_tasks(&mut tasks, SortKey::Id, false);
+        assert_eq!(ids(&tasks), [1, 2, 3]);
+        sort_bug_tasks(&mut tasks, SortKey::Id, true);
+        assert_eq!(ids(&tasks), [3, 2, 1]);
+        // New comes before Incomplete in Launchpad
+        sort_bug_tasks(&mut tasks, SortKey::Status, false);
+        assert_eq!(ids(&tasks)[2], 2);
+    }
+
+    #[tokio::test]
+    async fn test_bugs_without_date() {
+        let mut app = test_app();
+        let mut undated = bug_task(1, "a");
+        undated.date_created = None;
+        let mut other_undated = bug_task(2, "b");
+        other_undated.date_created = None;
+        let mut older = bug_task(3, "c");
+        older.date_created = "2024-01-13T08:46:25Z".parse().ok();
+        let newer = bug_task(4, "d");
+        app.update_bugs(Box::new([other_undated, newer, undated, older]));
+        assert_eq!(app.bug_table_rows.len(), 4);
+
+        let ids = |tasks: &[BugTaskEntry]| tasks.iter().map(|t| t.get_id()).collect::<Vec<_>>();
+        let mut tasks = app.bug_table_items.clone();
+        sort_bug_tasks(&mut tasks, SortKey::Date, false);
+        assert_eq!(ids(&tasks), [3, 4, 1, 2]);
+        sort_bug_tasks(&mut tasks, SortKey::Date, true);
+        assert_eq!(ids(&tasks), [4, 3, 1, 2]);
+    }
+
+    #[test]
+    fn test_sort_key_cycle() {
+        let mut key = SortKey::Date;
+        for _ in 0..4 {
+            key = key.next();
+        }
+        assert_eq!(key, SortKey::Date);
+        assert_eq!(SortKey::Importance.column(), "Importance");
+    }
+
+    #[test]
+    fn test_refresh_due() {
+        let mut app = test_app();
+        app.bugs_requested_at = Some(Instant::now());
+        app.bugs_fetched_at = Some(Instant::now());
+        assert!(!app.refresh_due());
+
+        app.refresh_interval = Some(Duration::ZERO);
+        assert!(app.refresh_due());
+
+        app.loading.bugs = true;
+        assert!(!app.refresh_due());
+        app.loading.bugs = false;
+
+        app.current_screen = Screen::BugEditing;
+        assert!(!app.refresh_due());
+        app.current_screen = Screen::BugList;
+
+        // A refresh is not retried before the interval after a failure
+        app.refresh_interval = Some(Duration::from_secs(60));
+        app.bugs_fetched_at = Instant::now().checked_sub(Duration::from_secs(120));
+        assert!(!app.refresh_due());
+        app.bugs_requested_at = app.bugs_fetched_at;
+        assert!(app.refresh_due());
+    }
+
+    #[tokio::test]
+    async fn test_refresh_due_after_failed_first_fetch() {
+        let mut app = test_app();
+        app.refresh_interval = Some(Duration::from_secs(60));
+        app.get_bugs();
+        app.set_error("Launchpad error: HTTP request failed".to_string());
+        assert_eq!(app.bugs_fetched_at, None);
+        // Not retried before the interval
+        assert!(!app.refresh_due());
+
+        app.bugs_requested_at = Instant::now().checked_sub(Duration::from_secs(120));
+        assert!(app.refresh_due());
+    }
+
+    #[tokio::test]
+    async fn test_error_popup() {
+        let mut app = test_app();
+        app.loading = Loading {
+            bugs: true,
+            bug: true,
+            ai: true,
+            reply: false,
+            activity: false,
+        };
+
+        app.set_error("Launchpad error: timeout".to_string());
+        assert_eq!(
+            app.popup,
+            Some(Popup::Error("Launchpad error: timeout".to_string()))
+        );
+        // The AI request is still pending
+        assert_eq!(app.loading.label().as_deref(), Some("Waiting for AI..."));
+
+        app.clear_error();
+        assert_eq!(app.popup, None);
+
+        // Other popups are left alone
+        app.popup = Some(Popup::Help);
+        app.clear_error();
+        assert_eq!(app.popup, Some(Popup::Help));
+    }
+
+    #[tokio::test]
+    async fn test_half_page_moves() {
+        let mut app = test_app();
+        app.update_bugs((1..=8).map(|i| bug_task(i, "bug")).collect());
+
+        app.bug_table_half_page_down();
+        assert_eq!(app.bug_table_state.selected(), Some(5));
+        app.bug_table_half_page_down();
+        assert_eq!(app.bug_table_state.selected(), Some(7));
+        app.bug_table_half_page_up();
+        assert_eq!(app.bug_table_state.selected(), Some(2));
+        app.bug_table_half_page_up();
+        assert_eq!(app.bug_table_state.selected(), Some(0));
+    }
+
+    #[tokio::test]
+    async fn test_page_moves_follow_viewport() {
+        let mut app = test_app();
+        app.update_bugs((1..=50).map(|i| bug_task(i, "bug")).collect());
+        app.bug_table_state.select(Some(0));
+
+        app.bug_table_page_down_item();
+        assert_eq!(app.bug_table_state.selected(), Some(10));
+
+        app.bug_table_viewport = Some(30);
+        app.bug_table_page_down_item();
+        assert_eq!(app.bug_table_state.selected(), Some(40));
+        app.bug_table_half_page_up();
+        assert_eq!(app.bug_table_state.selected(), Some(25));
+        app.bug_table_page_up_item();
+        assert_eq!(app.bug_table_state.selected(), Some(0));
+
+        // A table too small to show a row still moves
+        app.bug_table_viewport = Some(0);
+        app.bug_table_page_down_item();
+        assert_eq!(app.bug_table_state.selected(), Some(10));
+    }
+
+    #[tokio::test]
+    async fn test_cancel_ai_request() {
+        let mut app = test_app();
+        app.cancel_ai_request();
+        assert_eq!(
+            app.status_message.as_deref(),
+            Some("No AI request in progress")
+        );
+
+        let request = app.tasks.spawn("AI answer", std::future::pending());
+        app.ai_request = Some(request.clone());
+        app.loading.ai = true;
+        app.check_ai_request();
+        assert!(app.loading.ai);
+
+        app.cancel_ai_request();
+        assert!(!app.loading.ai);
+        assert!(app.ai_request.is_none());
+        assert_eq!(app.status_message.as_deref(), Some("AI request cancelled"));
+        assert_eq!(app.popup, None);
+        tokio::task::yield_now().await;
+        assert!(request.is_finished());
+
+        // A second cancel has nothing to abort
+        app.cancel_ai_request();
+        assert_eq!(
+            app.status_message.as_deref(),
+            Some("No AI request in progress")
+        );
+    }
+
+    #[tokio::test]
+    async fn test_ai_request_error_popup() {
+        let mut app = test_app();
+        let ai_error = Arc::clone(&app.ai_error);
+        app.ai_request = Some(app.tasks.spawn("AI answer", async move {
+            *ai_error.lock().unwrap() = Some("Gemini did not answer after 30s".to_string());
+        }));
+        app.loading.ai = true;
+
+        while app.ai_request.is_some() {
+            tokio::task::yield_now().await;
+            app.check_ai_request();
+        }
+        assert!(!app.loading.ai);
+        assert_eq!(
+            app.popup,
+            Some(Popup::Error("Gemini did not answer after 30s".to_string()))
+        );
+    }
+
+    #[tokio::test]
+    async fn test_preview_prompt() {
+        let mut app = test_app();
+        app.preview_prompt();
+        assert_eq!(
+            app.status_message.as_deref(),
+            Some("The bug description is empty")
+        );
+
+        *app.gemini_response.lock().unwrap() = "nova-compute fails to start".to_string();
+        app.status_message = None;
+        app.preview_prompt();
+        assert_eq!(app.status_message, None);
+        let Some(Popup::PromptPreview { prompt, scroll: 0 }) = &app.popup else {
+            panic!("no prompt preview");
+        };
+        assert_eq!(*prompt, app.craft_reply_prompt().unwrap());
+        assert!(prompt.ends_with("Here is the bug reported:\nnova-compute fails to start"));
+
+        app.popup = None;
+        app.prompt_file = Some("/does/not/exist".into());
+        app.preview_prompt();
+        assert_eq!(app.popup, None);
+        assert!(app.status_message.unwrap().contains("/does/not/exist"));
+    }
+
+    #[tokio::test]
+    async fn test_open_duplicate_of() {
+        let mut app = test_app();
+        app.current_bug = Some(bug(1, "original"));
+        app.open_duplicate_of();
+        assert_eq!(
+            app.status_message.as_deref(),
+            Some("This bug is not a duplicate")
+        );
+        assert!(!app.loading.bug);
+
+        let mut duplicate = bug(2, "duplicate");
+        duplicate.duplicate_of_link = Some("https://api.launchpad.net/1.0/bugs/1".to_string());
+        app.current_bug = Some(duplicate);
+        app.open_duplicate_of();
+        assert!(app.loading.bug);
+    }
+
+    #[tokio::test]
+    async fn test_go_to_bug() {
+        let mut app = test_app();
+        for text in ["", "abc", "0", "-3"] {
+            app.go_to_bug(text);
+            assert!(matches!(app.popup.take(), Some(Popup::Error(_))));
+            assert!(!app.loading.bug);
+        }
+        assert_eq!(app.active_panel, ActivePanel::Left);
+
+        app.go_to_bug(" #2093869 ");
+        assert_eq!(app.popup, None);
+        assert!(app.loading.bug);
+        assert_eq!(app.active_panel, ActivePanel::Right);
+    }
+
+    #[tokio::test]
+    async fn test_toggle_activity() {
+        let mut app = test_app();
+        app.toggle_activity();
+        assert!(!app.show_activity);
+
+        app.current_bug = Some(bug(1, "one"));
+        app.toggle_activity();
+        assert!(app.show_activity);
+        assert!(app.loading.activity);
+        let change = |what: &str, old: Option<&str>, new: Option<&str>| -> BugActivity {
+            serde_json::from_value(serde_json::json!({
+                "datechanged": "2024-05-27T08:02:11.154871+00:00",
+                "person_link": "https://api.launchpad.net/1.0/~sean-k-mooney",
+                "whatchanged": what,
+                "oldvalue": old,
+                "newvalue": new,
+                "message": null,
+            }))
+            .unwrap()
+        };
+        app.update_bug_activity(
+            1,
+            Ok(vec![
+                change("nova: status", Some("New"), Some("Incomplete")),
+                change("tags", Some(""), Some("libvirt")),
+            ]),
+        );
+        assert!(!app.loading.activity);
+        assert_eq!(
+            app.activity_timeline(),
+            "2024-05-27 08:02 sean-k-mooney: nova: status New -> Incomplete\n\
+             2024-05-27 08:02 sean-k-mooney: tags - -> libvirt"
+        );
+
+        // Shown again without fetching it
+        app.toggle_activity();
+        app.toggle_activity();
+        assert!(app.show_activity);
+        assert!(!app.loading.activity);
+
+        app.current_bug_activity = Some(Vec::new());
+        assert_eq!(app.activity_timeline(), "No activity on this bug");
+
+        // A failed fetch goes back to the description
+        app.current_bug_activity = None;
+        app.update_bug_activity(
+            1,
+            Err(LaunchpadError::HttpStatus {
+                status: 500,
+                url: String::new(),
+            }),
+        );
+        assert!(!app.show_activity);
+        assert!(app.status_message.is_some());
+    }
+
+    #[test]
+    fn test_status_counts() {
+        let mut tasks: Vec<BugTaskEntry> = (1..=6).map(|id| bug_task(id, "bug")).collect();
+        tasks[0].status = "Triaged".to_string();
+        tasks[1].status = "Some Future Status".to_string();
+        tasks[2].status = "Won't Fix".to_string();
+        tasks[3].status = "Triaged".to_string();
+        assert_eq!(
+            status_counts(&tasks),
+            [
+                ("New".to_string(), 2),
+                ("Won't Fix".to_string(), 1),
+                ("Triaged".to_string(), 2),
+                ("Some Future Status".to_string(), 1),
+            ]
+        );
+        assert!(status_counts(&[]).is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_step_tag_filter() {
+        let mut app = test_app();
+        app.update_bugs(vec![bug_task(1, "one"), bug_task(2, "two")].into_boxed_slice());
+        app.step_tag_filter(true);
+        assert_eq!(
+            app.status_message.as_deref(),
+            Some("The tags of the listed bugs are still being fetched")
+        );
+        app.clear_tag_filter();
+        assert_eq!(
+            app.status_message.as_deref(),
+            Some("The bugs are not filtered by tag")
+        );
+
+        app.update_bug_tags(1, vec!["gate-failure".to_string(), "docs".to_string()]);
+        app.update_bug_tags(2, vec!["docs".to_string(), "numa".to_string()]);
+        // Tags of a bug which is not listed
+        app.update_bug_tags(3, vec!["api".to_string()]);
+        assert_eq!(app.listed_tags, ["docs", "gate-failure", "numa"]);
+        let step = |app: &mut App, forward| {
+            app.step_tag_filter(forward);
+            app.tag_filter.clone().unwrap()
+        };
+        assert_eq!(step(&mut app, true), "docs");
+        assert_eq!(step(&mut app, true), "gate-failure");
+        assert_eq!(step(&mut app, true), "numa");
+        assert_eq!(step(&mut app, true), "docs");
+        assert_eq!(step(&mut app, false), "numa");
+        assert!(app.loading.bugs);
+
+        // Only bug 2 carries numa, the selection is clamped to its tags
+        app.update_bugs(vec![bug_task(2, "two")].into_boxed_slice());
+        assert_eq!(app.listed_tags, ["docs", "numa"]);
+        assert_eq!(app.tag_index, Some(1));
+        app.update_bug_tags(2, vec!["docs".to_string()]);
+        assert_eq!(app.tag_index, Some(0));
+        assert_eq!(step(&mut app, false), "docs");
+
+        app.clear_tag_filter();
+        assert_eq!(app.tag_filter, None);
+        assert_eq!(app.tag_index, None);
+
+        app.update_bugs(Box::default());
+        app.step_tag_filter(true);
+        assert_eq!(
+            app.status_message.as_deref(),
+            Some("The listed bugs have no tags")
+        );
+    }
+
+    #[tokio::test]
+    async fn test_bugs_progress() {
+        let mut app = test_app();
+        app.get_bugs();
+        app.update_bugs_progress(75, 300);
+        assert_eq!(app.bugs_progress, Some((75, 300)));
+
+        app.update_bugs(vec![bug_task(1, "one")].into_boxed_slice());
+        assert_eq!(app.bugs_progress, None);
+        // Once the list is there, a late update is ignored
+        app.update_bugs_progress(300, 300);
+        assert_eq!(app.bugs_progress, None);
+    }
+
+    #[tokio::test]
+    async fn test_cycle_tag_filter() {
+        let mut app = test_app();
+        app.cycle_tag_filter();
+        assert_eq!(app.status_message.as_deref(), Some("This bug has no tags"));
+        assert!(!app.loading.bugs);
+
+        let mut bug = bug(1, "tagged");
+        bug.tags = vec!["libvirt".to_string(), "gate-failure".to_string()];
+        app.current_bug = Some(bug);
+        let mut filters = Vec::new();
+        for _ in 0..3 {
+            app.cycle_tag_filter();
+            filters.push(app.tag_filter.clone());
+        }
+        assert_eq!(
+            filters,
+            [
+                Some("libvirt".to_string()),
+                Some("gate-failure".to_string()),
+                None
+            ]
+        );
+        assert!(app.loading.bugs);
+
+        // A filter from another bug restarts at the first tag
+        app.tag_filter = Some("numa".to_string());
+        app.cycle_tag_filter();
+        assert_eq!(app.tag_filter.as_deref(), Some("libvirt"));
+    }
+
+    #[tokio::test]
+    async fn test_set_statuses() {
+        let mut app = test_app();
+        assert_eq!(app.statuses, [StatusFilter::New]);
+
+        app.set_statuses(Vec::new());
+        assert_eq!(app.statuses, [StatusFilter::New]);
+        assert!(!app.loading.bugs);
+
+        app.set_statuses(vec![StatusFilter::New, StatusFilter::FixReleased]);
+        assert!(app.loading.bugs);
+        assert_eq!(app.session.statuses, ["New", "Fix Released"]);
+        assert_eq!(session_statuses(&app.session), app.statuses);
+
+        app.session.statuses = vec!["Bogus".to_string()];
+        assert_eq!(session_statuses(&app.session), [StatusFilter::New]);
+    }
+
+    #[tokio::test]
+    async fn test_refresh_keeps_selection() {
+        let mut app = test_app();
+        app.sort_key = SortKey::Id;
+        app.sort_descending = false;
+        app.update_bugs(Box::new([bug_task(1, "one"), bug_task(2, "two")]));
+        assert_eq!(app.bug_table_state.selected(), Some(0));
+        app.bug_table_state.select(Some(1));
+
+        // The selected bug moved down after a new one was reported
+        app.update_bugs(Box::new([
+            bug_task(1, "one"),
+            bug_task(2, "two"),
+            bug_task(0, "zero"),
+        ]));
+        assert_eq!(app.bug_table_state.selected(), Some(2));
+        assert_eq!(app.bug_table_items[2].bug_id(), Some(2));
+
+        // The selected bug is gone
+        app.update_bugs(Box::new([bug_task(0, "zero"), bug_task(1, "one")]));
+        assert_eq!(app.bug_table_state.selected(), Some(0));
+
+        app.update_bugs(Box::new([]));
+        assert_eq!(app.bug_table_state.selected(), None);
+    }
+
+    #[tokio::test]
+    async fn test_filter_query() {
+        let mut app = test_app();
+        app.update_bugs(
+            vec![
+                bug_task(1, "Crash on boot"),
+                bug_task(2, "Wrong quota"),
+                bug_task(3, "crash on resize"),
+            ]
+            .into_boxed_slice(),
+        );
+        app.bug_table_next_item();
+        app.bug_table_next_item();
+        assert_eq!(app.bug_table_state.selected(), Some(2));
+
+        app.filter_editing = true;
+        for c in "CRASH".chars() {
+            app.edit_filter(KeyCode::Char(c));
+        }
+        let ids: Vec<u32> = app.bug_table_items.iter().map(|b| b.get_id()).collect();
+        assert_eq!(ids, [1, 3]);
+        // Bug 3 is still selected
+        assert_eq!(app.bug_table_state.selected(), Some(1));
+
+        app.edit_filter(KeyCode::Char('x'));
+        assert!(app.bug_table_items.is_empty());
+        assert_eq!(app.bug_table_state.selected(), None);
+
+        app.edit_filter(KeyCode::Esc);
+        assert!(!app.filter_editing);
+        assert!(app.filter_query.is_empty());
+        assert_eq!(app.bug_table_items.len(), 3);
+        assert_eq!(app.bug_table_state.selected(), Some(0));
+    }
+
+    #[tokio::test]
+    async fn test_toggle_my_activity() {
+        let mut app = test_app();
+        app.session.record_message_count(2, 1);
+        app.session.record_message_count(3, 1);
+        app.update_bugs(Box::new([
+            bug_task(1, "one"),
+            bug_task(2, "two"),
+            bug_task(3, "three"),
+        ]));
+        app.bug_table_state.select(Some(2));
+
+        app.toggle_my_activity();
+        let ids: Vec<u32> = app.bug_table_items.iter().map(|b| b.get_id()).collect();
+        assert_eq!(ids, [2, 3]);
+        assert_eq!(app.bug_table_rows.len(), 2);
+        assert_eq!(app.bug_table_state.selected(), Some(1));
+
+        app.toggle_my_activity();
+        assert_eq!(app.bug_table_items.len(), 3);
+        assert_eq!(app.bug_table_state.selected(), Some(2));
+    }
+
+    #[tokio::test]
+    async fn test_toggle_assigned_to_me() {
+        let mut app = test_app();
+        app.toggle_assigned_to_me();
+        assert!(!app.only_assigned_to_me);
+        assert!(app.status_message.is_some());
+
+        app.assignee = Some("sean-k-mooney".to_string());
+        app.toggle_assigned_to_me();
+        assert!(app.only_assigned_to_me);
+        assert!(app.loading.bugs);
+    }
+
+    #[tokio::test]
+    async fn test_status_change() {
+        let mut app = test_app();
+        app.update_bugs(Box::new([bug_task(1, "one")]));
+        app.current_bug = Some(bug(1, "one"));
+
+        let mut live = bug_task(1, "one");
+        app.update_bug_tasks(1, vec![live.clone()]);
+        assert_eq!(app.status_change(), None);
+
+        live.status = "Triaged".to_string();
+        app.update_bug_tasks(1, vec![live.clone()]);
+        assert_eq!(app.status_change(), Some("Triaged"));
+
+        // Tasks of another bug are ignored
+        app.update_bug_tasks(2, Vec::new());
+        assert_eq!(app.status_change(), Some("Triaged"));
+
+        // The refreshed list catches up with the live status
+        app.update_bugs(Box::new([live]));
+        assert_eq!(app.status_change(), None);
+    }
+
+    #[tokio::test]
+    async fn test_mark_incomplete_after_post() {
+        let mut app = test_app();
+        app.update_bugs(Box::new([bug_task(1, "one"), bug_task(2, "two")]));
+        app.current_bug = Some(bug(2, "two"));
+
+        // Nothing is posted without credentials
+        app.post_reply_and_mark_incomplete();
+        assert!(matches!(&app.popup, Some(Popup::Error(e)) if e.contains("credentials")));
+        assert!(!app.loading.reply);
+        app.clear_error();
+
+        app.launchpad_authenticated = true;
+        app.post_reply_and_mark_incomplete();
+        let task_link = "https://api.launchpad.net/1.0/nova/+bug/2".to_string();
+        assert_eq!(app.mark_incomplete_after_post, Some((2, task_link.clone())));
+        app.update_comment_posted(2, Ok(()));
+        assert_eq!(app.mark_incomplete_after_post, None);
+
+        assert_eq!(app.status_summary().as_deref(), Some("New: 2"));
+        app.update_status_changed(2, &task_link, StatusFilter::Incomplete, Ok(()));
+        let statuses: Vec<&str> = app
+            .bug_table_items
+            .iter()
+            .map(|task| task.status.as_str())
+            .collect();
+        assert_eq!(statuses, ["New", "Incomplete"]);
+        assert_eq!(app.bug_table_rows.len(), 2);
+        assert_eq!(
+            app.status_summary().as_deref(),
+            Some("New: 1  Incomplete: 1")
+        );
+
+        app.update_status_changed(
+            2,
+            &task_link,
+            StatusFilter::Incomplete,
+            Err(LaunchpadError::Unauthorized(
+                "change the bug status".to_string(),
+            )),
+        );
+        assert!(matches!(&app.popup, Some(Popup::Error(e)) if e.contains("credentials")));
+    }
+
+    #[tokio::test]
+    async fn test_advance_spinner() {
+        let mut app = test_app();
+        app.symbols = Symbols::ascii();
+        app.spinner_interval = Duration::from_millis(100);
+        let start = app.spinner_step_at;
+
+        // Idle, it does not move
+        assert!(!app.spinning());
+        app.advance_spinner(start + Duration::from_millis(250));
+        assert_eq!(app.spinner_state.index(), 0);
+
+        app.loading.bugs = true;
+        assert!(app.spinning());
+        let start = app.spinner_step_at;
+        app.advance_spinner(start + Duration::from_millis(50));
+        assert_eq!(app.spinner_state.index(), 0);
+        app.advance_spinner(start + Duration::from_millis(250));
+        assert_eq!(app.spinner_state.index(), 2);
+        // The 50ms left count for the next step
+        app.advance_spinner(start + Duration::from_millis(300));
+        assert_eq!(app.spinner_state.index(), 3);
+        // Whole turns of the 4 ASCII symbols are skipped
+        app.advance_spinner(start + Duration::from_millis(800));
+        assert_eq!(app.spinner_state.index(), 4);
+    }
+
+    #[tokio::test]
+    async fn test_write_reply() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut app = test_app();
+        app.reply_dir = dir.path().join("replies");
+        app.bug_reply_text = "Which release are you running?".to_string();
+        app.write_reply();
+        assert_eq!(app.status_message.as_deref(), Some("No bug opened yet"));
+
+        app.current_bug = Some(bug(42, "answered"));
+        app.write_reply();
+        let path = dir.path().join("replies").join("bug-42-reply.txt");
+        assert_eq!(
+            std::fs::read_to_string(&path).unwrap(),
+            "Which release are you running?"
+        );
+        assert_eq!(
+            app.status_message,
+            Some(format!("Reply written to {}", path.display()))
+        );
+    }
+
+    #[tokio::test]
+    async fn test_export_bug_markdown() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut app = test_app();
+        app.start_markdown_export();
+        assert_eq!(app.popup, None);
+        assert_eq!(app.status_message.as_deref(), Some("No bug opened yet"));
+
+        app.current_bug = Some(bug(42, "exported"));
+        app.start_markdown_export();
+        assert_eq!(
+            app.popup,
+            Some(Popup::ExportMarkdown(TextInput::new("42.md")))
+        );
+
+        let path = dir.path().join("42.md");
+        app.export_bug_markdown(path.to_str().unwrap());
+        assert!(std::fs::read_to_string(&path).unwrap().starts_with("# "));
+        assert_eq!(
+            app.status_message,
+            Some(format!("Bug exported to {}", path.display()))
+        );
+    }
+
+    #[tokio::test]
+    async fn test_quit_with_edited_reply() {
+        let mut app = test_app();
+        assert!(app.request_quit());
+        assert_eq!(app.popup, None);
+
+        app.start_bug_reply();
+        app.quote_in_reply("It fails on the compute node");
+        assert!(!app.request_quit());
+        assert_eq!(app.popup, Some(Popup::ConfirmQuit));
+
+        // A new reply starts clean
+        app.popup = None;
+        app.start_bug_reply();
+        assert!(app.request_quit());
+
+        // A restored draft is not posted either
+        app.restore_draft("It fails on the compute node".to_string());
+        assert!(!app.request_quit());
+        assert_eq!(app.popup, Some(Popup::ConfirmQuit));
+    }
+
+    #[tokio::test]
+    async fn test_skip_confirmations() {
+        let mut app = test_app();
+        assert!(!app.skips_confirmation(Action::PostReply));
+        assert!(!app.skips_confirmation(Action::PostAndMarkIncomplete));
+        assert_eq!(app.status_message, None);
+
+        app.confirm_post = false;
+        assert!(app.skips_confirmation(Action::PostReply));
+        assert!(
+            app.status_message
+                .as_ref()
+                .unwrap()
+                .contains("confirm_post = false")
+        );
+        // Setting the bug Incomplete is still confirmed
+        assert!(!app.skips_confirmation(Action::PostAndMarkIncomplete));
+        app.confirm_status_change = false;
+        assert!(app.skips_confirmation(Action::PostAndMarkIncomplete));
+        assert!(app.status_message.as_ref().unwrap().contains("Incomplete"));
+        assert!(!app.skips_confirmation(Action::RefineReply));
+
+        app.confirm_quit = false;
+        app.restore_draft("It fails on the compute node".to_string());
+        assert!(app.request_quit());
+        assert_eq!(app.popup, None);
+    }
+
+    #[tokio::test]
+    async fn test_undo_edit() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut app = test_app();
+        app.session = Session::load_from(dir.path().join("session.json"));
+        app.update_bug(bug(1, "one"));
+        app.apply_edit(EditTarget::Description, String::new());
+        assert_eq!(*app.gemini_response.lock().unwrap(), "");
+        // Only the edited text is restored
+        app.undo_edit(EditTarget::Reply);
+        assert_eq!(app.status_message.as_deref(), Some("No edit to undo"));
+        app.undo_edit(EditTarget::Description);
+        assert_eq!(
+            *app.gemini_response.lock().unwrap(),
+            app.current_bug.as_ref().unwrap().description
+        );
+
+        app.start_bug_reply();
+        app.bug_reply_text = "Thanks".to_string();
+        app.apply_edit(EditTarget::Reply, String::new());
+        assert!(app.reply_dirty);
+        app.undo_edit(EditTarget::Reply);
+        assert_eq!(app.bug_reply_text, "Thanks");
+        // The restored reply is autosaved
+        assert_eq!(app.session.load_draft(1).as_deref(), Some("Thanks"));
+        app.undo_edit(EditTarget::Reply);
+        assert_eq!(app.status_message.as_deref(), Some("No edit to undo"));
+
+        // Nothing to restore once another bug is loaded
+        app.apply_edit(EditTarget::Description, String::new());
+        app.update_bug(bug(2, "two"));
+        app.undo_edit(EditTarget::Description);
+        assert_eq!(app.status_message.as_deref(), Some("No edit to undo"));
+        assert_eq!(
+            *app.gemini_response.lock().unwrap(),
+            app.current_bug.as_ref().unwrap().description
+        );
+        app.apply_edit(EditTarget::Reply, "Edited".to_string());
+        app.update_bug(bug(3, "three"));
+        app.undo_edit(EditTarget::Reply);
+        assert_eq!(app.status_message.as_deref(), Some("No edit to undo"));
+        assert_eq!(app.bug_reply_text, "Edited");
+    }
+
+    #[tokio::test]
+    async fn test_use_ai_answer() {
+        let mut app = test_app();
+        app.use_ai_answer();
+        assert_eq!(
+            app.status_message.as_deref(),
+            Some("There is no AI answer to reply with")
+        );
+        assert_eq!(app.current_screen, Screen::BugList);
+
+        *app.gemini_response.lock().unwrap() = "Please attach the nova-compute logs".to_string();
+        app.use_ai_answer();
+        assert_eq!(app.bug_reply_text, "Please attach the nova-compute logs");
+        assert_eq!(app.current_screen, Screen::BugEditing);
+        assert_eq!(app.active_panel, ActivePanel::Left);
+        assert!(!app.request_quit());
+    }
+
+    #[tokio::test]
+    async fn test_reopen_bug_from_cache() {
+        let mut app = test_app();
+        app.update_bug(bug(1, "one"));
+        app.update_bug_messages(1, Vec::new());
+        app.update_bug_attachments(1, Vec::new());
+        app.update_bug_tasks(1, vec![bug_task(1, "one")]);
+        app.update_bug(bug(2, "two"));
+        assert!(app.current_bug_tasks.is_empty());
+
+        app.get_bug(1);
+        assert_eq!(app.current_bug.as_ref().map(|bug| bug.id), Some(1));
+        assert_eq!(app.current_bug_tasks.len(), 1);
+        assert!(!app.loading.bug);
+
+        // The messages of bug 2 never came, it is fetched again
+        app.get_bug(2);
+        assert!(app.loading.bug);
+        assert_eq!(app.current_bug.as_ref().map(|bug| bug.id), Some(1));
+    }
+
+    #[tokio::test]
+    async fn test_conversation_follows_description() {
+        let mut app = test_app();
+        let bug = bug(1, "one");
+        app.displayed_conversation = bug.description.clone();
+        *app.gemini_response.lock().unwrap() = bug.description.clone();
+        app.current_bug = Some(bug);
+        let link = "https://api.launchpad.net/1.0/~sean-k-mooney".to_string();
+        app.person_names
+            .insert(link.clone(), "sean-k-mooney".to_string());
+        let message = |content: &str| -> BugMessage {
+            serde_json::from_value(serde_json::json!({
+                "self_link": "https://api.launchpad.net/1.0/nova/+bug/1/comments/0",
+                "subject": null,
+                "content": content,
+                "owner_link": link,
+                "date_created": "2024-05-21T10:12:03.114532+00:00"
+            }))
+            .unwrap()
+        };
+
+        app.update_bug_messages(1, vec![message("Description"), message("Which release?")]);
+        assert_eq!(
+            *app.gemini_response.lock().unwrap(),
+            "Description of the bug\n\n--- Comment #1 by sean-k-mooney on 2024-05-21 ---\nWhich release?"
+        );
+
+        app.update_person_name(link.clone(), "Sean Mooney".to_string());
+        assert!(
+            app.gemini_response
+                .lock()
+                .unwrap()
+                .contains("by Sean Mooney on")
+        );
+
+        let attachment: BugAttachment = serde_json::from_value(serde_json::json!({
+            "self_link": "https://api.launchpad.net/1.0/bugs/1/+attachment/2",
+            "title": "nova-compute.log",
+            "type": "Unspecified",
+            "data_link": "https://api.launchpad.net/1.0/bugs/1/+attachment/2/data",
+            "message_link": null
+        }))
+        .unwrap();
+        app.update_bug_attachments(1, vec![attachment]);
+        assert!(app.gemini_response.lock().unwrap().ends_with(
+            "--- Attachments ---\n- nova-compute.log (https://api.launchpad.net/1.0/bugs/1/+attachment/2/data)"
+        ));
+
+        // An AI answer displayed meanwhile is kept
+        *app.gemini_response.lock().unwrap() = "AI answer".to_string();
+        app.update_person_name(link, "Sean".to_string());
+        assert_eq!(*app.gemini_response.lock().unwrap(), "AI answer");
+    }
+
+    #[tokio::test]
+    async fn test_my_activity_without_viewed_bugs() {
+        let mut app = test_app();
+        app.update_bugs(Box::new([bug_task(1, "one")]));
+
+        app.toggle_my_activity();
+        assert!(app.bug_table_items.is_empty());
+        assert_eq!(app.bug_table_state.selected(), None);
+    }
+
+    #[tokio::test]
+    async fn test_empty_bug_table_navigation() {
+        let mut app = test_app();
+
+        app.bug_table_next_item();
+        app.bug_table_previous_item();
+        app.bug_table_page_up_item();
+        app.bug_table_page_down_item();
+        app.bug_table_go_to_start();
+        app.bug_table_go_to_end();
+
+        assert_eq!(app.bug_table_state.selected(), None);
+    }
+
+    #[test]
+    fn test_lint_reply() {
+        assert!(lint_reply("Thanks for reporting this bug.").is_empty());
+        assert_eq!(lint_reply("  \n"), ["The reply is empty"]);
+        assert_eq!(lint_reply(REPLY_PLACEHOLDER), ["The reply is empty"]);
+        assert_eq!(
+            lint_reply("Thanks!\n*** Start template ***\nDescription"),
+            ["The reply contains the prompt template markers"]
+        );
+        assert_eq!(
+            lint_reply("Error while fetching the response: timeout"),
+            ["The reply starts with an error message"]
+        );
     }
 
-    pub(crate) fn update_bug_reply(&mut self, msg: String) {
-        self.bug_reply_text = msg;
-        self.spinner_enabled = false
+    #[test]
+    fn test_quote_excerpt() {
+        assert_eq!(
+            quote_excerpt("first line\n\nsecond line\n"),
+            "> first line\n>\n> second line\n"
+        );
+        assert_eq!(quote_excerpt(""), "");
     }
 }
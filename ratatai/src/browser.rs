@@ -0,0 +1,52 @@
+// src/browser.rs
+
+//! Opening of the bugs in a web browser.
+
+/// Returns the program and the arguments opening `url`, with the `browser`
+/// command if set, otherwise with the launcher of the platform.
+pub(crate) fn browser_command(url: &str, browser: Option<&str>) -> (String, Vec<String>) {
+    // $BROWSER may hold a list of browsers separated by colons
+    if let Some(mut words) = browser
+        .and_then(|browser| browser.split(':').next())
+        .map(str::split_whitespace)
+        && let Some(program) = words.next()
+    {
+        let mut args: Vec<String> = words.map(str::to_string).collect();
+        args.push(url.to_string());
+        return (program.to_string(), args);
+    }
+
+    if cfg!(target_os = "macos") {
+        ("open".to_string(), vec![url.to_string()])
+    } else if cfg!(target_os = "windows") {
+        // The empty string is the title of the window opened by start
+        let args = ["/c", "start", "", url].map(str::to_string);
+        ("cmd".to_string(), args.to_vec())
+    } else {
+        ("xdg-open".to_string(), vec![url.to_string()])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_browser_command() {
+        let url = "https://bugs.launchpad.net/nova/+bug/1";
+
+        let (program, args) = browser_command(url, Some("firefox --new-window:chromium"));
+        assert_eq!(program, "firefox");
+        assert_eq!(args, ["--new-window", url]);
+
+        let (program, args) = browser_command(url, Some("  "));
+        assert_ne!(program, "");
+        assert_eq!(args.last().map(String::as_str), Some(url));
+
+        #[cfg(target_os = "linux")]
+        assert_eq!(
+            browser_command(url, None),
+            ("xdg-open".to_string(), vec![url.to_string()])
+        );
+    }
+}
@@ -0,0 +1,125 @@
+// src/cache.rs
+
+//! Cache of the recently opened bugs, so going back to one of them does not
+//! query Launchpad again.
+
+use launchpad_api_client::{BugAttachment, BugMessage, BugTaskEntry, LaunchpadBug};
+use lru::LruCache;
+use std::{
+    num::NonZeroUsize,
+    time::{Duration, Instant},
+};
+
+pub(crate) const DEFAULT_CACHE_SIZE: usize = 50;
+pub(crate) const DEFAULT_CACHE_TTL: Duration = Duration::from_secs(300);
+
+/// A bug and the details fetched with it, filled as they arrive.
+#[derive(Debug, Clone)]
+pub(crate) struct CachedBug {
+    pub bug: LaunchpadBug,
+    pub messages: Option<Vec<BugMessage>>,
+    pub attachments: Option<Vec<BugAttachment>>,
+    pub tasks: Option<Vec<BugTaskEntry>>,
+}
+
+impl CachedBug {
+    /// The messages and attachments came with the bug, a bug without them
+    /// is better fetched again.
+    pub(crate) fn is_complete(&self) -> bool {
+        self.messages.is_some() && self.attachments.is_some()
+    }
+}
+
+/// Least recently used bugs, each kept at most `ttl`. A zero capacity
+/// disables the cache.
+#[derive(Debug)]
+pub(crate) struct BugCache {
+    entries: Option<LruCache<u32, (Instant, CachedBug)>>,
+    ttl: Duration,
+}
+
+impl BugCache {
+    pub(crate) fn new(capacity: usize, ttl: Duration) -> Self {
+        Self {
+            entries: NonZeroUsize::new(capacity).map(LruCache::new),
+            ttl,
+        }
+    }
+
+    /// Returns the bug if it was fetched less than `ttl` ago.
+    pub(crate) fn get(&mut self, bug_id: u32) -> Option<&CachedBug> {
+        let entries = self.entries.as_mut()?;
+        if entries
+            .peek(&bug_id)
+            .is_some_and(|(fetched_at, _)| fetched_at.elapsed() >= self.ttl)
+        {
+            entries.pop(&bug_id);
+        }
+        entries.get(&bug_id).map(|(_, cached)| cached)
+    }
+
+    /// Stores a freshly fetched bug, replacing its previous details.
+    pub(crate) fn insert(&mut self, bug: LaunchpadBug) {
+        if let Some(entries) = &mut self.entries {
+            let cached = CachedBug {
+                bug,
+                messages: None,
+                attachments: None,
+                tasks: None,
+            };
+            entries.put(cached.bug.id, (Instant::now(), cached));
+        }
+    }
+
+    /// Gives access to a cached bug to add its details, without making it
+    /// more recently used.
+    pub(crate) fn entry_mut(&mut self, bug_id: u32) -> Option<&mut CachedBug> {
+        self.entries
+            .as_mut()?
+            .peek_mut(&bug_id)
+            .map(|(_, cached)| cached)
+    }
+
+    pub(crate) fn invalidate(&mut self, bug_id: u32) {
+        if let Some(entries) = &mut self.entries {
+            entries.pop(&bug_id);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fixtures::bug;
+
+    #[test]
+    fn test_bug_cache() {
+        let mut cache = BugCache::new(2, Duration::from_secs(60));
+        cache.insert(bug(1, "one"));
+        cache.insert(bug(2, "two"));
+        assert!(!cache.get(1).unwrap().is_complete());
+        cache.entry_mut(1).unwrap().messages = Some(Vec::new());
+        cache.entry_mut(1).unwrap().attachments = Some(Vec::new());
+        assert!(cache.get(1).unwrap().is_complete());
+
+        // 2 is the least recently used one
+        cache.insert(bug(3, "three"));
+        assert!(cache.get(2).is_none());
+        assert!(cache.get(1).is_some());
+
+        cache.invalidate(1);
+        assert!(cache.get(1).is_none());
+        assert!(cache.entry_mut(1).is_none());
+    }
+
+    #[test]
+    fn test_bug_cache_expires() {
+        let mut cache = BugCache::new(2, Duration::ZERO);
+        cache.insert(bug(1, "one"));
+        assert!(cache.get(1).is_none());
+
+        let mut disabled = BugCache::new(0, Duration::from_secs(60));
+        disabled.insert(bug(1, "one"));
+        assert!(disabled.get(1).is_none());
+    }
+}
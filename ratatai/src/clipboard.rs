@@ -0,0 +1,29 @@
+// src/clipboard.rs
+
+//! Access to the system clipboard, which may be missing on headless systems.
+
+/// Clipboard opened on first use, then kept open because on X11 the copied
+/// text is only served as long as its owner lives.
+#[derive(Default)]
+pub(crate) struct Clipboard {
+    inner: Option<arboard::Clipboard>,
+}
+
+impl std::fmt::Debug for Clipboard {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Clipboard")
+            .field("opened", &self.inner.is_some())
+            .finish()
+    }
+}
+
+impl Clipboard {
+    /// Copies `text` to the system clipboard.
+    pub(crate) fn copy(&mut self, text: &str) -> Result<(), arboard::Error> {
+        let clipboard = match &mut self.inner {
+            Some(clipboard) => clipboard,
+            None => self.inner.insert(arboard::Clipboard::new()?),
+        };
+        clipboard.set_text(text)
+    }
+}
@@ -0,0 +1,527 @@
+// src/config.rs
+
+//! Settings read from `~/.config/ratatai/config.toml`.
+
+use anyhow::Context;
+use ratatui::style::Color;
+use serde::{Deserialize, Deserializer};
+use std::{
+    collections::BTreeMap,
+    fs,
+    path::{Path, PathBuf},
+    str::FromStr,
+    time::Duration,
+};
+
+use crate::{
+    ai::{
+        DEFAULT_GEMINI_MODEL, DEFAULT_MAX_OUTPUT_TOKENS, DEFAULT_TEMPERATURE, GenerationSettings,
+    },
+    cache::{DEFAULT_CACHE_SIZE, DEFAULT_CACHE_TTL},
+    keymap::KeyMap,
+};
+use launchpad_api_client::client::DEFAULT_TIMEOUT;
+
+const DEFAULT_PROJECT: &str = "nova";
+const DEFAULT_EDITOR: &str = "nvim";
+const DEFAULT_SPINNER_INTERVAL: Duration = Duration::from_millis(120);
+
+/// Returns the default configuration file path.
+fn config_path() -> Option<PathBuf> {
+    dirs::config_dir().map(|dir| dir.join("ratatai").join("config.toml"))
+}
+
+/// Content of the configuration file, unset values fall back to the
+/// environment and then to the defaults.
+#[derive(Debug, Default, Clone, PartialEq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Config {
+    pub project: Option<String>,
+    pub editor: Option<String>,
+    /// Arguments given to the editor instead of the edited file, like
+    /// "--wait {file}", where `{file}` is the edited file
+    pub editor_args: Option<String>,
+    /// Root of the Launchpad API, like "https://api.qastaging.launchpad.net/1.0"
+    /// to try things out, the production one if unset
+    pub launchpad_url: Option<String>,
+    /// Launchpad account listed by the "assigned to me" filter
+    pub assignee: Option<String>,
+    /// Command opening the bugs, the platform launcher if unset
+    pub browser: Option<String>,
+    pub gemini_model: Option<String>,
+    /// Randomness of the AI answers, from 0.0 to 2.0
+    pub temperature: Option<f32>,
+    /// Cumulative probability of the tokens sampled, from 0.0 to 1.0
+    pub top_p: Option<f32>,
+    /// Longest AI answer, in tokens
+    pub max_output_tokens: Option<u32>,
+    pub prompt_file: Option<PathBuf>,
+    /// Directory where the replies are written with 'w', the current one if unset
+    pub reply_dir: Option<PathBuf>,
+    /// Browse the bugs without the AI features, as without `GOOGLE_API_KEY`
+    pub offline: Option<bool>,
+    /// Seconds between two automatic refreshes of the bug list
+    pub refresh_interval: Option<u64>,
+    /// Seconds given to Launchpad and Gemini to answer a request
+    pub request_timeout: Option<u64>,
+    /// Render the bug descriptions as markdown rather than raw text
+    pub markdown: Option<bool>,
+    /// Show the word count and reading time of the bug descriptions
+    pub reading_time: Option<bool>,
+    /// Show the reply next to the description on the bug list screen
+    pub split_view: Option<bool>,
+    /// Number of recently opened bugs kept in memory, zero disables the cache
+    pub cache_size: Option<usize>,
+    /// Seconds before a cached bug is fetched again
+    pub cache_ttl: Option<u64>,
+    /// Ask before quitting with an edited reply
+    pub confirm_quit: Option<bool>,
+    /// Ask before posting a reply on Launchpad
+    pub confirm_post: Option<bool>,
+    /// Ask before changing the status of a bug
+    pub confirm_status_change: Option<bool>,
+    /// Characters of the spinner, braille or ascii depending on the locale if unset
+    pub spinner: Option<SpinnerStyle>,
+    /// Milliseconds between two steps of the spinner
+    pub spinner_interval: Option<u64>,
+    pub theme: ThemeConfig,
+    /// The `[keys]` table, the keys of an action by its name, like
+    /// `ai_generate = "F2"` or `nav_down = ["Down", "n"]`
+    #[serde(deserialize_with = "key_lists")]
+    pub keys: BTreeMap<String, Vec<String>>,
+}
+
+impl Config {
+    /// Reads the configuration from `path`, or from the default path if it
+    /// exists. A missing default file gives the default configuration.
+    pub fn load(path: Option<&Path>) -> anyhow::Result<Self> {
+        let path = match path {
+            Some(path) => path.to_path_buf(),
+            None => match config_path() {
+                Some(path) if path.exists() => path,
+                _ => return Ok(Self::default()),
+            },
+        };
+        let content = fs::read_to_string(&path)
+            .with_context(|| format!("Fail to read the configuration {}", path.display()))?;
+        let config: Self = toml::from_str(&content)
+            .with_context(|| format!("Invalid configuration {}", path.display()))?;
+        config.keymap().with_context(|| {
+            format!(
+                "Invalid key bindings in the configuration {}",
+                path.display()
+            )
+        })?;
+        Ok(config)
+    }
+
+    /// The default key bindings with the configured keys.
+    pub(crate) fn keymap(&self) -> anyhow::Result<KeyMap> {
+        KeyMap::with_overrides(&self.keys)
+    }
+
+    /// Fills the values missing from the file with the environment variables.
+    pub fn with_env(self) -> Self {
+        self.with_env_from(|name| std::env::var(name).ok())
+    }
+
+    fn with_env_from(mut self, var: impl Fn(&str) -> Option<String>) -> Self {
+        self.editor = self.editor.or_else(|| var("EDITOR"));
+        self.browser = self.browser.or_else(|| var("BROWSER"));
+        self.gemini_model = self.gemini_model.or_else(|| var("GEMINI_MODEL"));
+        self.prompt_file = self
+            .prompt_file
+            .or_else(|| var("RATATAI_PROMPT").map(PathBuf::from));
+        self
+    }
+
+    pub fn project(&self) -> &str {
+        self.project.as_deref().unwrap_or(DEFAULT_PROJECT)
+    }
+
+    pub fn editor(&self) -> &str {
+        self.editor.as_deref().unwrap_or(DEFAULT_EDITOR)
+    }
+
+    pub fn gemini_model(&self) -> &str {
+        self.gemini_model.as_deref().unwrap_or(DEFAULT_GEMINI_MODEL)
+    }
+
+    /// Generation settings of Gemini, out of range values are clamped.
+    pub(crate) fn generation(&self) -> GenerationSettings {
+        GenerationSettings {
+            temperature: self
+                .temperature
+                .map_or(DEFAULT_TEMPERATURE, |t| t.clamp(0.0, 2.0)),
+            top_p: self.top_p.map(|p| p.clamp(0.0, 1.0)),
+            max_output_tokens: self
+                .max_output_tokens
+                .filter(|tokens| *tokens > 0)
+                .unwrap_or(DEFAULT_MAX_OUTPUT_TOKENS),
+        }
+    }
+
+    pub fn offline(&self) -> bool {
+        self.offline.unwrap_or(false)
+    }
+
+    pub fn request_timeout(&self) -> Duration {
+        self.request_timeout
+            .map_or(DEFAULT_TIMEOUT, Duration::from_secs)
+    }
+
+    pub fn markdown(&self) -> bool {
+        self.markdown.unwrap_or(true)
+    }
+
+    pub fn reading_time(&self) -> bool {
+        self.reading_time.unwrap_or(true)
+    }
+
+    pub fn split_view(&self) -> bool {
+        self.split_view.unwrap_or(false)
+    }
+
+    pub fn cache_size(&self) -> usize {
+        self.cache_size.unwrap_or(DEFAULT_CACHE_SIZE)
+    }
+
+    pub fn cache_ttl(&self) -> Duration {
+        self.cache_ttl
+            .map_or(DEFAULT_CACHE_TTL, Duration::from_secs)
+    }
+
+    pub fn confirm_quit(&self) -> bool {
+        self.confirm_quit.unwrap_or(true)
+    }
+
+    pub fn confirm_post(&self) -> bool {
+        self.confirm_post.unwrap_or(true)
+    }
+
+    pub fn confirm_status_change(&self) -> bool {
+        self.confirm_status_change.unwrap_or(true)
+    }
+
+    pub fn spinner_interval(&self) -> Duration {
+        self.spinner_interval
+            .filter(|millis| *millis > 0)
+            .map_or(DEFAULT_SPINNER_INTERVAL, Duration::from_millis)
+    }
+
+    /// Interval of the automatic refreshes, none if unset or zero.
+    pub fn refresh_interval(&self) -> Option<Duration> {
+        self.refresh_interval
+            .filter(|secs| *secs > 0)
+            .map(Duration::from_secs)
+    }
+}
+
+/// Characters sets of the spinner.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum SpinnerStyle {
+    Braille,
+    Dots,
+    Line,
+    Ascii,
+}
+
+/// Built-in color themes, the colors of the configuration override them.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Deserialize, clap::ValueEnum)]
+#[serde(rename_all = "kebab-case")]
+pub enum ThemePreset {
+    #[default]
+    Dark,
+    Light,
+    HighContrast,
+}
+
+/// Colors of the interface, each one used for a role.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Theme {
+    /// Border of the active panel
+    pub active_border: Color,
+    /// Border of the other panels
+    pub border: Color,
+    /// Background of the selected row
+    pub highlight_bg: Color,
+    /// Text of the selected row, and of the badges
+    pub highlight_fg: Color,
+    /// Headers of the bug table
+    pub header: Color,
+    /// Bottom bar, medium importance and the user prompts
+    pub accent: Color,
+    /// Spinner and the chat answers
+    pub secondary: Color,
+    /// Popups, loading indicators, high importance and security bugs
+    pub warning: Color,
+    /// Errors, critical importance and private bugs
+    pub error: Color,
+    /// Public bugs
+    pub success: Color,
+    /// Secondary details and low importance
+    pub muted: Color,
+}
+
+impl Theme {
+    pub fn preset(preset: ThemePreset) -> Self {
+        match preset {
+            ThemePreset::Dark => Self {
+                active_border: Color::Green,
+                border: Color::White,
+                highlight_bg: Color::LightCyan,
+                highlight_fg: Color::Black,
+                header: Color::Red,
+                accent: Color::Cyan,
+                secondary: Color::Magenta,
+                warning: Color::Yellow,
+                error: Color::Red,
+                success: Color::Green,
+                muted: Color::DarkGray,
+            },
+            ThemePreset::Light => Self {
+                active_border: Color::Blue,
+                border: Color::DarkGray,
+                highlight_bg: Color::Blue,
+                highlight_fg: Color::White,
+                header: Color::Red,
+                accent: Color::Blue,
+                secondary: Color::Magenta,
+                warning: Color::Rgb(0xaf, 0x5f, 0x00),
+                error: Color::Red,
+                success: Color::Green,
+                muted: Color::Gray,
+            },
+            ThemePreset::HighContrast => Self {
+                active_border: Color::LightYellow,
+                border: Color::White,
+                highlight_bg: Color::White,
+                highlight_fg: Color::Black,
+                header: Color::LightRed,
+                accent: Color::LightCyan,
+                secondary: Color::LightMagenta,
+                warning: Color::LightYellow,
+                error: Color::LightRed,
+                success: Color::LightGreen,
+                muted: Color::Gray,
+            },
+        }
+    }
+}
+
+impl Default for Theme {
+    fn default() -> Self {
+        Self::preset(ThemePreset::default())
+    }
+}
+
+/// The `[theme]` table, colors are named ("green") or in hex ("#00ff00").
+#[derive(Debug, Default, Clone, PartialEq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct ThemeConfig {
+    pub preset: Option<ThemePreset>,
+    #[serde(deserialize_with = "color")]
+    pub active_border: Option<Color>,
+    #[serde(deserialize_with = "color")]
+    pub border: Option<Color>,
+    #[serde(deserialize_with = "color")]
+    pub highlight_bg: Option<Color>,
+    #[serde(deserialize_with = "color")]
+    pub highlight_fg: Option<Color>,
+    #[serde(deserialize_with = "color")]
+    pub header: Option<Color>,
+    #[serde(deserialize_with = "color")]
+    pub accent: Option<Color>,
+    #[serde(deserialize_with = "color")]
+    pub secondary: Option<Color>,
+    #[serde(deserialize_with = "color")]
+    pub warning: Option<Color>,
+    #[serde(deserialize_with = "color")]
+    pub error: Option<Color>,
+    #[serde(deserialize_with = "color")]
+    pub success: Option<Color>,
+    #[serde(deserialize_with = "color")]
+    pub muted: Option<Color>,
+}
+
+impl ThemeConfig {
+    /// The preset theme with the configured colors.
+    pub fn theme(&self) -> Theme {
+        let preset = Theme::preset(self.preset.unwrap_or_default());
+        Theme {
+            active_border: self.active_border.unwrap_or(preset.active_border),
+            border: self.border.unwrap_or(preset.border),
+            highlight_bg: self.highlight_bg.unwrap_or(preset.highlight_bg),
+            highlight_fg: self.highlight_fg.unwrap_or(preset.highlight_fg),
+            header: self.header.unwrap_or(preset.header),
+            accent: self.accent.unwrap_or(preset.accent),
+            secondary: self.secondary.unwrap_or(preset.secondary),
+            warning: self.warning.unwrap_or(preset.warning),
+            error: self.error.unwrap_or(preset.error),
+            success: self.success.unwrap_or(preset.success),
+            muted: self.muted.unwrap_or(preset.muted),
+        }
+    }
+}
+
+fn color<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Option<Color>, D::Error> {
+    let name = String::deserialize(deserializer)?;
+    Color::from_str(&name)
+        .map(Some)
+        .map_err(|_| serde::de::Error::custom(format!("invalid color {name}")))
+}
+
+/// Keys of the actions, a single key may be given without a list.
+fn key_lists<'de, D: Deserializer<'de>>(
+    deserializer: D,
+) -> Result<BTreeMap<String, Vec<String>>, D::Error> {
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum Keys {
+        One(String),
+        Many(Vec<String>),
+    }
+
+    let keys = BTreeMap::<String, Keys>::deserialize(deserializer)?;
+    Ok(keys
+        .into_iter()
+        .map(|(action, keys)| match keys {
+            Keys::One(key) => (action, vec![key]),
+            Keys::Many(keys) => (action, keys),
+        })
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_defaults_without_file() {
+        let config = Config::default();
+
+        assert_eq!(config.project(), "nova");
+        assert_eq!(config.editor(), "nvim");
+        assert_eq!(config.gemini_model(), DEFAULT_GEMINI_MODEL);
+        assert!(!config.offline());
+        assert!(config.markdown());
+        assert!(config.reading_time());
+        assert!(!config.split_view());
+        assert_eq!(config.request_timeout(), Duration::from_secs(30));
+        assert_eq!(config.refresh_interval(), None);
+        assert_eq!(config.cache_size(), 50);
+        assert_eq!(config.cache_ttl(), Duration::from_secs(300));
+        assert!(config.confirm_quit());
+        assert!(config.confirm_post());
+        assert!(config.confirm_status_change());
+        assert_eq!(config.spinner, None);
+        assert_eq!(config.spinner_interval(), Duration::from_millis(120));
+        assert_eq!(config.generation(), GenerationSettings::default());
+        assert_eq!(config.theme.theme(), Theme::default());
+    }
+
+    #[test]
+    fn test_load_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("config.toml");
+        fs::write(
+            &path,
+            r##"
+project = "neutron"
+gemini_model = "gemini-2.5-pro"
+temperature = 3.5
+max_output_tokens = 1024
+refresh_interval = 300
+cache_size = 0
+spinner = "dots"
+spinner_interval = 80
+editor_args = "-n {file}"
+assignee = "sean-k-mooney"
+confirm_post = false
+launchpad_url = "https://api.qastaging.launchpad.net/1.0/"
+
+[keys]
+ai_generate = "F2"
+nav_down = ["Down", "n"]
+
+[theme]
+preset = "light"
+active_border = "yellow"
+highlight_bg = "#112233"
+"##,
+        )
+        .unwrap();
+
+        let config = Config::load(Some(&path)).unwrap();
+        assert_eq!(config.project(), "neutron");
+        assert_eq!(config.gemini_model(), "gemini-2.5-pro");
+        assert_eq!(
+            config.generation(),
+            GenerationSettings {
+                temperature: 2.0,
+                top_p: None,
+                max_output_tokens: 1024,
+            }
+        );
+        assert_eq!(config.refresh_interval(), Some(Duration::from_secs(300)));
+        assert_eq!(config.cache_size(), 0);
+        assert_eq!(config.spinner, Some(SpinnerStyle::Dots));
+        assert_eq!(config.spinner_interval(), Duration::from_millis(80));
+        assert_eq!(config.editor_args.as_deref(), Some("-n {file}"));
+        assert_eq!(config.keys["nav_down"], ["Down", "n"]);
+        assert_eq!(config.keys["ai_generate"], ["F2"]);
+        assert_eq!(config.assignee.as_deref(), Some("sean-k-mooney"));
+        assert!(config.confirm_quit());
+        assert!(!config.confirm_post());
+        assert!(config.confirm_status_change());
+        assert_eq!(
+            config.launchpad_url.as_deref(),
+            Some("https://api.qastaging.launchpad.net/1.0/")
+        );
+        let theme = config.theme.theme();
+        assert_eq!(theme.active_border, Color::Yellow);
+        assert_eq!(theme.highlight_bg, Color::Rgb(0x11, 0x22, 0x33));
+        // The other colors come from the preset
+        assert_eq!(theme.highlight_fg, Color::White);
+        assert_eq!(
+            theme,
+            Theme {
+                active_border: Color::Yellow,
+                highlight_bg: Color::Rgb(0x11, 0x22, 0x33),
+                ..Theme::preset(ThemePreset::Light)
+            }
+        );
+
+        fs::write(&path, "[keys]\nopen_bug = \"q\"").unwrap();
+        let error = format!("{:#}", Config::load(Some(&path)).unwrap_err());
+        assert!(error.contains("'q' is bound to both quit and open_bug in Bug list"));
+        fs::write(&path, "[keys]\nai_generate = 1").unwrap();
+        assert!(Config::load(Some(&path)).is_err());
+        fs::write(&path, "[theme]\nborder = \"not a color\"").unwrap();
+        assert!(Config::load(Some(&path)).is_err());
+        fs::write(&path, "[theme]\npreset = \"solarized\"").unwrap();
+        assert!(Config::load(Some(&path)).is_err());
+        assert!(Config::load(Some(&dir.path().join("missing.toml"))).is_err());
+    }
+
+    #[test]
+    fn test_file_overrides_env() {
+        let env = |name: &str| match name {
+            "EDITOR" => Some("vim".to_string()),
+            "BROWSER" => Some("firefox".to_string()),
+            "GEMINI_MODEL" => Some("gemini-2.5-flash-lite".to_string()),
+            _ => None,
+        };
+        let config = Config {
+            gemini_model: Some("gemini-2.5-pro".to_string()),
+            ..Config::default()
+        }
+        .with_env_from(env);
+
+        assert_eq!(config.editor(), "vim");
+        assert_eq!(config.browser.as_deref(), Some("firefox"));
+        assert_eq!(config.gemini_model(), "gemini-2.5-pro");
+        assert_eq!(config.prompt_file, None);
+    }
+}
@@ -0,0 +1,73 @@
+// src/editor.rs
+
+//! Command line of the external editor.
+
+use anyhow::Context;
+use std::path::Path;
+
+/// Placeholder of the edited file in the editor arguments.
+const FILE_PLACEHOLDER: &str = "{file}";
+
+/// Returns the program and the arguments editing `file`. The `editor` is
+/// split like a shell would, so `EDITOR="code --wait"` works. The `args`
+/// template replaces the arguments of the editor, the file is appended if it
+/// does not hold `{file}`.
+pub(crate) fn editor_command(
+    editor: &str,
+    args: Option<&str>,
+    file: &Path,
+) -> anyhow::Result<(String, Vec<String>)> {
+    let split = |text: &str| {
+        shlex::split(text).with_context(|| format!("Unbalanced quotes in the editor \"{text}\""))
+    };
+    let mut words = split(editor)?.into_iter();
+    let program = words.next().context("The editor command is empty")?;
+    let mut args = match args {
+        Some(template) => split(template)?,
+        None => words.collect(),
+    };
+    let file = file.to_string_lossy();
+    if args.iter().any(|arg| arg.contains(FILE_PLACEHOLDER)) {
+        for arg in &mut args {
+            *arg = arg.replace(FILE_PLACEHOLDER, &file);
+        }
+    } else {
+        args.push(file.into_owned());
+    }
+    Ok((program, args))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_editor_command() {
+        let file = Path::new("/tmp/reply.md");
+        let command = |editor, args| editor_command(editor, args, file).unwrap();
+
+        assert_eq!(
+            command("nvim", None),
+            ("nvim".to_string(), vec!["/tmp/reply.md".to_string()])
+        );
+        assert_eq!(
+            command("code --wait", None),
+            (
+                "code".to_string(),
+                vec!["--wait".to_string(), "/tmp/reply.md".to_string()]
+            )
+        );
+        assert_eq!(
+            command("'/opt/My Editor/bin/edit' -n", None).0,
+            "/opt/My Editor/bin/edit"
+        );
+        // The template replaces the arguments of the editor
+        assert_eq!(
+            command("vim -u NONE", Some("+'set ft=markdown' --file={file}")).1,
+            ["+set ft=markdown", "--file=/tmp/reply.md"]
+        );
+
+        assert!(editor_command("", None, file).is_err());
+        assert!(editor_command("code \"--wait", None, file).is_err());
+    }
+}
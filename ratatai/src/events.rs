@@ -1,21 +1,24 @@
-use anyhow::bail;
 use crossterm::{
     ExecutableCommand,
     event::{KeyCode, KeyEvent, KeyEventKind},
     terminal::{EnterAlternateScreen, LeaveAlternateScreen, disable_raw_mode, enable_raw_mode},
 };
 use google_ai_rs::GenerativeModel;
+use launchpad_api_client::StatusFilter;
 use ratatui::Terminal;
 use ratatui::backend::CrosstermBackend;
-use std::{env, sync::Arc};
+use std::{path::Path, sync::Arc};
 use tempfile::NamedTempFile;
 use tokio::{fs::File, io::AsyncReadExt, process::Command};
 use tracing::error;
 
 use crate::{
-    PROJECT,
-    ai::{get_gemini_response, get_initial_prompt},
-    app::{ActivePanel, App, Screen},
+    ai::{OFFLINE_MESSAGE, get_gemini_response},
+    app::{ActivePanel, App, EditTarget, Popup, Screen, lint_reply, page_rows},
+    browser::browser_command,
+    editor::editor_command,
+    input::TextInput,
+    keymap::{Action, Key},
 };
 
 #[derive(Debug, PartialEq)]
@@ -31,25 +34,67 @@ pub async fn handle_key_events(
     terminal: &mut Terminal<CrosstermBackend<std::io::Stdout>>,
 ) -> anyhow::Result<QuitApp> {
     if key.kind == KeyEventKind::Press {
-        if let QuitApp::Yes = handle_global_keys(key, app)? {
+        app.status_message = None;
+
+        if app.popup.is_some() {
+            return handle_popup_keys(key, app).await;
+        }
+
+        if app.filter_editing {
+            app.edit_filter(key.code);
+            return Ok(QuitApp::No);
+        }
+
+        if let Some(input) = &mut app.chat_input {
+            match key.code {
+                KeyCode::Enter => {
+                    let prompt = input.text().trim().to_string();
+                    app.chat_input = None;
+                    if !prompt.is_empty() {
+                        app.send_to_chat(prompt).await?;
+                    }
+                }
+                KeyCode::Esc => app.chat_input = None,
+                code => input.edit(code),
+            }
+            return Ok(QuitApp::No);
+        }
+
+        let key = Key::from(key);
+        // A repeated key waits for its second press, any other key cancels it
+        if key.is_repeated() && app.pending_key.take() != Some(key) {
+            app.pending_key = Some(key);
+            return Ok(QuitApp::No);
+        }
+        app.pending_key = None;
+
+        let Some(action) = app.keymap.action(app.key_context(), key) else {
+            return Ok(QuitApp::No);
+        };
+        if app.offline && action.needs_ai() {
+            app.status_message = Some(OFFLINE_MESSAGE.to_string());
+            return Ok(QuitApp::No);
+        }
+
+        if let QuitApp::Yes = handle_global_keys(action, app)? {
             return Ok(QuitApp::Yes);
         }
 
         if let QuitApp::Yes = match app.current_screen {
-            Screen::BugList => handle_bug_list_screen_keys(key, app)?,
-            Screen::BugEditing => handle_bug_editing_screen_keys(key, app)?,
+            Screen::BugList => handle_bug_list_screen_keys(action, app)?,
+            Screen::BugEditing => handle_bug_editing_screen_keys(action, app)?,
         } {
             return Ok(QuitApp::Yes);
         }
 
         if let QuitApp::Yes = match app.current_screen {
             Screen::BugList => match app.active_panel {
-                ActivePanel::Left => handle_bug_table(key, app).await?,
-                ActivePanel::Right => handle_bug_description(key, app, terminal).await?,
+                ActivePanel::Left => handle_bug_table(action, app).await?,
+                ActivePanel::Right => handle_bug_description(action, app, terminal).await?,
             },
             Screen::BugEditing => match app.active_panel {
-                ActivePanel::Left => handle_bug_description(key, app, terminal).await?,
-                ActivePanel::Right => handle_bug_reply(key, app, terminal).await?,
+                ActivePanel::Left => handle_bug_description(action, app, terminal).await?,
+                ActivePanel::Right => handle_bug_reply(action, app, terminal).await?,
             },
         } {
             return Ok(QuitApp::Yes);
@@ -59,35 +104,131 @@ pub async fn handle_key_events(
     Ok(QuitApp::No) // Return false if no exit condition was met
 }
 
-fn handle_global_keys(key: KeyEvent, app: &mut App) -> anyhow::Result<QuitApp> {
-    match key.code {
-        KeyCode::Char('s') => {
+async fn handle_popup_keys(key: KeyEvent, app: &mut App) -> anyhow::Result<QuitApp> {
+    if let Some(Popup::Error(_)) = app.popup {
+        app.clear_error();
+        return Ok(QuitApp::No);
+    }
+    match app.popup.take() {
+        Some(Popup::RestoreDraft(draft)) => match key.code {
+            KeyCode::Char('y') => app.restore_draft(draft),
+            KeyCode::Char('n') | KeyCode::Esc => {}
+            _ => app.popup = Some(Popup::RestoreDraft(draft)),
+        },
+        Some(Popup::ConfirmReply { action, warnings }) => match key.code {
+            KeyCode::Char('y') => send_reply(action, app).await?,
+            KeyCode::Char('n') | KeyCode::Esc => {}
+            _ => app.popup = Some(Popup::ConfirmReply { action, warnings }),
+        },
+        Some(Popup::ExportBugs(mut input)) => match key.code {
+            KeyCode::Enter => app.export_bug_list(input.text().trim()),
+            KeyCode::Esc => {}
+            code => {
+                input.edit(code);
+                app.popup = Some(Popup::ExportBugs(input));
+            }
+        },
+        Some(Popup::ExportMarkdown(mut input)) => match key.code {
+            KeyCode::Enter => app.export_bug_markdown(input.text().trim()),
+            KeyCode::Esc => {}
+            code => {
+                input.edit(code);
+                app.popup = Some(Popup::ExportMarkdown(input));
+            }
+        },
+        Some(Popup::PromptPreview { prompt, scroll }) => {
+            let scroll = match key.code {
+                KeyCode::Esc => return Ok(QuitApp::No),
+                KeyCode::Up => scroll.saturating_sub(1),
+                KeyCode::Down => scroll.saturating_add(1),
+                KeyCode::PageUp => scroll.saturating_sub(10),
+                KeyCode::PageDown => scroll.saturating_add(10),
+                _ => scroll,
+            };
+            app.popup = Some(Popup::PromptPreview { prompt, scroll });
+        }
+        Some(Popup::GoToBug(mut input)) => match key.code {
+            KeyCode::Enter => app.go_to_bug(input.text()),
+            KeyCode::Esc => {}
+            code => {
+                input.edit(code);
+                app.popup = Some(Popup::GoToBug(input));
+            }
+        },
+        Some(Popup::ConfirmQuit) => match key.code {
+            KeyCode::Char('y') => return Ok(QuitApp::Yes),
+            KeyCode::Char('n') | KeyCode::Esc => {}
+            _ => app.popup = Some(Popup::ConfirmQuit),
+        },
+        Some(Popup::StatusFilter {
+            mut checked,
+            mut cursor,
+        }) => match key.code {
+            KeyCode::Enter => {
+                let statuses = StatusFilter::ALL
+                    .into_iter()
+                    .zip(checked)
+                    .filter_map(|(status, checked)| checked.then_some(status))
+                    .collect();
+                app.set_statuses(statuses);
+            }
+            KeyCode::Esc => {}
+            code => {
+                match code {
+                    KeyCode::Up | KeyCode::Char('k') => cursor = cursor.saturating_sub(1),
+                    KeyCode::Down | KeyCode::Char('j') => {
+                        cursor = (cursor + 1).min(checked.len() - 1)
+                    }
+                    KeyCode::Char(' ') => checked[cursor] = !checked[cursor],
+                    _ => {}
+                }
+                app.popup = Some(Popup::StatusFilter { checked, cursor });
+            }
+        },
+        // Any key closes the help
+        Some(Popup::Help | Popup::Error(_)) | None => {}
+    }
+    Ok(QuitApp::No)
+}
+
+fn handle_global_keys(action: Action, app: &mut App) -> anyhow::Result<QuitApp> {
+    match action {
+        Action::ToggleSpinner => {
             app.toggle_spinner();
         }
-        KeyCode::Char('q') => return Ok(QuitApp::Yes),
+        Action::Help => app.popup = Some(Popup::Help),
+        Action::ToggleCompactHints => app.compact_hints = !app.compact_hints,
+        Action::CycleLogLevel => app.cycle_log_level(),
+        Action::Quit if app.request_quit() => return Ok(QuitApp::Yes),
         _ => {}
     }
     Ok(QuitApp::No)
 }
 
-fn handle_bug_list_screen_keys(key: KeyEvent, app: &mut App) -> anyhow::Result<QuitApp> {
-    if let KeyCode::Tab = key.code {
-        if app.active_panel == ActivePanel::Right {
-            app.active_panel = ActivePanel::Left
-        } else {
-            app.active_panel = ActivePanel::Right
+fn handle_bug_list_screen_keys(action: Action, app: &mut App) -> anyhow::Result<QuitApp> {
+    match action {
+        Action::SwitchPanel => {
+            if app.active_panel == ActivePanel::Right {
+                app.active_panel = ActivePanel::Left
+            } else {
+                app.active_panel = ActivePanel::Right
+            }
         }
+        Action::GoToBug => app.popup = Some(Popup::GoToBug(TextInput::default())),
+        Action::ToggleSplitView => app.split_view = !app.split_view,
+        _ => (),
     }
     Ok(QuitApp::No)
 }
 
-fn handle_bug_editing_screen_keys(key: KeyEvent, app: &mut App) -> anyhow::Result<QuitApp> {
-    match key.code {
-        KeyCode::Esc => {
+fn handle_bug_editing_screen_keys(action: Action, app: &mut App) -> anyhow::Result<QuitApp> {
+    match action {
+        Action::AskChat => app.chat_input = Some(TextInput::default()),
+        Action::Back => {
             app.current_screen = Screen::BugList;
             app.active_panel = ActivePanel::Left;
         }
-        KeyCode::Tab => {
+        Action::SwitchPanel => {
             if app.active_panel == ActivePanel::Right {
                 app.active_panel = ActivePanel::Left
             } else {
@@ -100,20 +241,47 @@ fn handle_bug_editing_screen_keys(key: KeyEvent, app: &mut App) -> anyhow::Resul
 }
 
 // Bug table is activated
-async fn handle_bug_table(key: KeyEvent, app: &mut App) -> anyhow::Result<QuitApp> {
-    match key.code {
-        KeyCode::Up => app.bug_table_previous_item(),
-        KeyCode::Down => app.bug_table_next_item(),
-        KeyCode::PageUp => app.bug_table_page_up_item(),
-        KeyCode::PageDown => app.bug_table_page_down_item(),
-        KeyCode::Home => app.bug_table_go_to_start(),
-        KeyCode::End => app.bug_table_go_to_end(),
-        KeyCode::Char('r') => app.get_bugs(PROJECT.to_string()),
-        KeyCode::Enter => {
-            if let Some(index) = app.bug_table_state.selected() {
-                if let Some(bug_entry) = app.bug_table_items.get(index) {
-                    app.get_bug(bug_entry.get_id());
-                }
+async fn handle_bug_table(action: Action, app: &mut App) -> anyhow::Result<QuitApp> {
+    match action {
+        Action::NavUp => app.bug_table_previous_item(),
+        Action::NavDown => app.bug_table_next_item(),
+        Action::PageUp => app.bug_table_page_up_item(),
+        Action::PageDown => app.bug_table_page_down_item(),
+        Action::HalfPageUp => app.bug_table_half_page_up(),
+        Action::HalfPageDown => app.bug_table_half_page_down(),
+        Action::GoToStart => app.bug_table_go_to_start(),
+        Action::GoToEnd => app.bug_table_go_to_end(),
+        Action::RefreshList => app.get_bugs(),
+        Action::ToggleMyActivity => app.toggle_my_activity(),
+        Action::ToggleAssignedToMe => app.toggle_assigned_to_me(),
+        Action::ToggleDuplicates => {
+            app.omit_duplicates = !app.omit_duplicates;
+            app.get_bugs();
+        }
+        Action::SelectStatuses => app.open_status_filter(),
+        Action::CycleImportance => app.cycle_importance(),
+        Action::Search => app.filter_editing = true,
+        Action::ExportBugs => {
+            let path = format!("{}-bugs.csv", app.project);
+            app.popup = Some(Popup::ExportBugs(TextInput::new(path)));
+        }
+        Action::NextTag => app.step_tag_filter(true),
+        Action::PreviousTag => app.step_tag_filter(false),
+        Action::ClearTag => app.clear_tag_filter(),
+        Action::CycleSort => app.sort_bugs(app.sort_key.next(), app.sort_descending),
+        Action::ReverseSort => app.sort_bugs(app.sort_key, !app.sort_descending),
+        Action::OpenBug => {
+            if let Some(index) = app.bug_table_state.selected()
+                && let Some(bug_entry) = app.bug_table_items.get(index)
+            {
+                app.get_bug(bug_entry.get_id());
+            }
+        }
+        Action::ReloadBug => {
+            if let Some(index) = app.bug_table_state.selected()
+                && let Some(bug_entry) = app.bug_table_items.get(index)
+            {
+                app.fetch_bug(bug_entry.get_id());
             }
         }
         _ => {}
@@ -122,172 +290,263 @@ async fn handle_bug_table(key: KeyEvent, app: &mut App) -> anyhow::Result<QuitAp
 }
 
 async fn handle_bug_description(
-    key: KeyEvent,
+    action: Action,
     app: &mut App,
     terminal: &mut Terminal<CrosstermBackend<std::io::Stdout>>,
 ) -> anyhow::Result<QuitApp> {
-    match key.code {
-        KeyCode::Up => {
+    match action {
+        Action::NavUp => {
             app.bug_desc_scroll = app.bug_desc_scroll.saturating_sub(1);
             app.bug_desc_scroll_to_end = false;
         }
-        KeyCode::Down => {
+        Action::NavDown => {
             app.bug_desc_scroll = app.bug_desc_scroll.saturating_add(1);
             app.bug_desc_scroll_to_end = false;
         }
-        KeyCode::PageUp => {
-            app.bug_desc_scroll = app.bug_desc_scroll.saturating_sub(10);
+        Action::PageUp => {
+            app.bug_desc_scroll = app
+                .bug_desc_scroll
+                .saturating_sub(page_rows(app.bug_desc_viewport));
             app.bug_desc_scroll_to_end = false;
         }
-        KeyCode::PageDown => {
-            app.bug_desc_scroll = app.bug_desc_scroll.saturating_add(10);
+        Action::PageDown => {
+            app.bug_desc_scroll = app
+                .bug_desc_scroll
+                .saturating_add(page_rows(app.bug_desc_viewport));
             app.bug_desc_scroll_to_end = false;
         }
-        KeyCode::Home => {
+        Action::GoToStart => {
             app.bug_desc_scroll = 0;
             app.bug_desc_scroll_to_end = false;
         }
-        KeyCode::End => {
+        Action::GoToEnd => {
             app.bug_desc_scroll_to_end = true;
         }
-        KeyCode::Char('v') => {
-            if let Some(index) = app.bug_table_state.selected() {
-                if let Some(bug_entry) = app.bug_table_items.get(index) {
-                    let status = Command::new("xdg-open")
-                        .arg(&bug_entry.web_link)
-                        .status()
-                        .await?;
-
-                    if !status.success() {
-                        error!("Fail to open url: {:?}", status.code());
+        Action::OpenBrowser => {
+            if let Some(index) = app.bug_table_state.selected()
+                && let Some(bug_entry) = app.bug_table_items.get(index)
+            {
+                let (program, args) = browser_command(&bug_entry.web_link, app.browser.as_deref());
+                match Command::new(&program).args(&args).status().await {
+                    Ok(status) if status.success() => {}
+                    Ok(status) => error!("Fail to open url with {program}: {:?}", status.code()),
+                    Err(e) => {
+                        error!("Fail to run {program} to open url, error {e}");
+                        app.status_message = Some(format!(
+                            "Fail to run {program}, set the browser in the configuration"
+                        ));
                     }
                 }
             }
         }
-        KeyCode::Char('a') => {
+        Action::CancelAi => app.cancel_ai_request(),
+        Action::ReloadBug => {
+            if let Some(bug_id) = app.current_bug.as_ref().map(|bug| bug.id) {
+                app.fetch_bug(bug_id);
+            }
+        }
+        Action::FilterTag => app.cycle_tag_filter(),
+        Action::ToggleMarkdown => app.markdown = !app.markdown,
+        Action::ToggleActivity => app.toggle_activity(),
+        Action::OpenDuplicateOf => app.open_duplicate_of(),
+        Action::CopyLink => app.copy_bug_link(),
+        Action::CopyDescription => app.copy_bug_description(),
+        Action::ExportMarkdown => app.start_markdown_export(),
+        Action::RefreshList => app.get_bugs(),
+        Action::AiGenerate => {
             let client = Arc::clone(&app.gemini_client);
+            let gemini_model = app.gemini_model.clone();
+            let generation = app.generation;
             let gemini_response_text_for_spawn = Arc::clone(&app.gemini_response);
             let prompt = { gemini_response_text_for_spawn.lock().unwrap().clone() };
+            let token_usage = Arc::clone(&app.token_usage);
+            let ai_error = Arc::clone(&app.ai_error);
+            let timeout = app.request_timeout;
 
-            tokio::spawn(async move {
-                let model = GenerativeModel::new(&client, "gemini-2.5-flash");
+            // A new request replaces the pending one
+            if let Some(request) = app.ai_request.take() {
+                request.abort();
+            }
+            app.loading.ai = true;
+            *app.ai_error.lock().unwrap() = None;
+            let request = app.tasks.spawn("AI answer", async move {
+                let response = match client.get().await {
+                    Ok(client) => {
+                        let model = generation.apply(GenerativeModel::new(client, &gemini_model));
+                        tokio::time::timeout(timeout, get_gemini_response(model, prompt.clone()))
+                            .await
+                            .unwrap_or_else(|_| {
+                                Err(anyhow::anyhow!(
+                                    "Gemini did not answer after {}s",
+                                    timeout.as_secs()
+                                ))
+                            })
+                    }
+                    Err(e) => Err(e),
+                };
 
-                match get_gemini_response(model, prompt).await {
+                match response {
                     Ok(response) => {
+                        let usage = response.usage_metadata;
+                        let answer = response.text();
+                        token_usage.lock().unwrap().record(usage, &prompt, &answer);
                         let mut response_guard = gemini_response_text_for_spawn.lock().unwrap();
-                        *response_guard = response.text();
+                        *response_guard = answer;
                     }
+                    // Shown in a popup, keeping the displayed text
                     Err(e) => {
-                        let mut response_guard = gemini_response_text_for_spawn.lock().unwrap();
-                        *response_guard = format!("Error while fetching the response: {e}");
+                        error!("Error while fetching the AI response: {e}");
+                        *ai_error.lock().unwrap() =
+                            Some(format!("Error while fetching the response: {e}"));
                     }
                 }
             });
-            // Ai request
+            app.ai_request = Some(request);
         }
-        KeyCode::Char('e') => {
+        Action::EditExternal => {
             let initial_content = { app.gemini_response.lock().unwrap().clone() };
-            let updated = edit_content_in_editor(terminal, initial_content).await?;
-            {
-                let mut response_guard = app.gemini_response.lock().unwrap();
-                *response_guard = updated;
+            if let Some(updated) = edit_content_in_editor(app, terminal, initial_content).await? {
+                app.apply_edit(EditTarget::Description, updated);
             }
         }
-        KeyCode::Enter => {
-            if app.current_screen == Screen::BugList {
-                app.current_screen = Screen::BugEditing;
-                app.active_panel = ActivePanel::Left;
-                app.bug_reply_text = "No bug replied yet.".to_string();
-            } else {
-                let bug_guard = { app.gemini_response.lock().unwrap().clone() };
-
-                let prompt = format!("{}\n{}", get_initial_prompt(), bug_guard);
-                app.app_sender.send(prompt).await?;
-                app.spinner_enabled = true;
-            }
+        Action::UndoEdit => app.undo_edit(EditTarget::Description),
+        Action::Reply => {
+            app.current_screen = Screen::BugEditing;
+            app.active_panel = ActivePanel::Left;
+            app.start_bug_reply();
+        }
+        Action::QuoteInReply => {
+            let description = { app.gemini_response.lock().unwrap().clone() };
+            app.quote_in_reply(&description);
         }
+        Action::UseAiAnswer => app.use_ai_answer(),
+        Action::CraftReply => match app.craft_reply_prompt() {
+            Ok(prompt) => app.send_to_chat(prompt).await?,
+            Err(e) => app.status_message = Some(format!("{e:#}")),
+        },
+        Action::PreviewPrompt => app.preview_prompt(),
         _ => {}
     }
     Ok(QuitApp::No)
 }
 
 async fn handle_bug_reply(
-    key: KeyEvent,
+    action: Action,
     app: &mut App,
     terminal: &mut Terminal<CrosstermBackend<std::io::Stdout>>,
 ) -> anyhow::Result<QuitApp> {
-    match key.code {
-        // KeyCode::Up => {
-        //     app.bug_desc_scroll = app.bug_desc_scroll.saturating_sub(1);
-        //     app.bug_desc_scroll_to_end = false;
-        // }
-        // KeyCode::Down => {
-        //     app.bug_desc_scroll = app.bug_desc_scroll.saturating_add(1);
-        //     app.bug_desc_scroll_to_end = false;
-        // }
-        // KeyCode::PageUp => {
-        //     app.bug_desc_scroll = app.bug_desc_scroll.saturating_sub(10);
-        //     app.bug_desc_scroll_to_end = false;
-        // }
-        // KeyCode::PageDown => {
-        //     app.bug_desc_scroll = app.bug_desc_scroll.saturating_add(10);
-        //     app.bug_desc_scroll_to_end = false;
-        // }
-        // KeyCode::Home => {
-        //     app.bug_desc_scroll = 0;
-        //     app.bug_desc_scroll_to_end = false;
-        // }
-        // KeyCode::End => {
-        //     app.bug_desc_scroll_to_end = true;
-        // }
-        KeyCode::Enter => {
-            app.app_sender.send(app.bug_reply_text.clone()).await?;
-            app.spinner_enabled = true;
-        }
-        KeyCode::Char('e') => {
+    // The navigation keys scroll the focused part of the panel
+    let (scroll, scroll_to_end) = if app.chat_focused {
+        (&mut app.chat_scroll, &mut app.chat_scroll_to_end)
+    } else {
+        (&mut app.bug_reply_scroll, &mut app.bug_reply_scroll_to_end)
+    };
+    match action {
+        Action::NavUp => {
+            *scroll = scroll.saturating_sub(1);
+            *scroll_to_end = false;
+        }
+        Action::NavDown => {
+            *scroll = scroll.saturating_add(1);
+            *scroll_to_end = false;
+        }
+        Action::PageUp => {
+            *scroll = scroll.saturating_sub(10);
+            *scroll_to_end = false;
+        }
+        Action::PageDown => {
+            *scroll = scroll.saturating_add(10);
+            *scroll_to_end = false;
+        }
+        Action::GoToStart => {
+            *scroll = 0;
+            *scroll_to_end = false;
+        }
+        Action::GoToEnd => {
+            *scroll_to_end = true;
+        }
+        Action::FocusChatHistory => {
+            if app.chat_history.is_empty() {
+                app.status_message = Some("Nothing was asked to the chat yet".to_string());
+            } else {
+                app.chat_focused = !app.chat_focused;
+            }
+        }
+        Action::RefineReply | Action::PostReply | Action::PostAndMarkIncomplete => {
+            let warnings = lint_reply(&app.bug_reply_text);
+            // Posting is public, so it is confirmed unless configured otherwise
+            if warnings.is_empty()
+                && (action == Action::RefineReply || app.skips_confirmation(action))
+            {
+                send_reply(action, app).await?;
+            } else {
+                app.popup = Some(Popup::ConfirmReply { action, warnings });
+            }
+        }
+        Action::EditExternal => {
             let initial_content = app.bug_reply_text.clone();
-            let updated = edit_content_in_editor(terminal, initial_content).await?;
-            app.bug_reply_text = updated;
+            if let Some(updated) = edit_content_in_editor(app, terminal, initial_content).await? {
+                app.apply_edit(EditTarget::Reply, updated);
+            }
         }
+        Action::UndoEdit => app.undo_edit(EditTarget::Reply),
+        Action::SaveToOutbox => app.save_reply_to_outbox(),
+        Action::WriteReply => app.write_reply(),
         _ => {}
     }
     Ok(QuitApp::No)
 }
 
+/// Sends the reply to the chat to refine it, or posts it on Launchpad.
+async fn send_reply(action: Action, app: &mut App) -> anyhow::Result<()> {
+    match action {
+        Action::PostReply => app.post_reply(),
+        Action::PostAndMarkIncomplete => app.post_reply_and_mark_incomplete(),
+        _ => app.send_to_chat(app.bug_reply_text.clone()).await?,
+    }
+    Ok(())
+}
+
+/// Writes the content to edit in a temporary file, removed once dropped.
+async fn write_temp_file(content: String) -> anyhow::Result<NamedTempFile> {
+    let file = tokio::task::spawn_blocking(move || {
+        let mut temp = NamedTempFile::new()?;
+        std::io::Write::write_all(&mut temp, content.as_bytes())?;
+        Ok::<_, std::io::Error>(temp)
+    })
+    .await??;
+    Ok(file)
+}
+
+async fn read_temp_file(path: &Path) -> anyhow::Result<String> {
+    let mut content = String::new();
+    let mut file = File::open(path).await?;
+    AsyncReadExt::read_to_string(&mut file, &mut content).await?;
+    Ok(content)
+}
+
+/// Opens the content in the external editor. An editor which fails to start
+/// or exits with an error is shown in a popup and gives `None`, the content
+/// is then left as is.
 async fn edit_content_in_editor<S>(
+    app: &mut App,
     terminal: &mut Terminal<CrosstermBackend<std::io::Stdout>>,
     content: S,
-) -> anyhow::Result<String>
+) -> anyhow::Result<Option<String>>
 where
     S: Into<String>,
 {
-    // Prepare the file with the given content
-    let (file_path, _file) = tokio::task::spawn_blocking({
-        let content = content.into();
-        move || {
-            let mut temp = NamedTempFile::new()?;
-            std::io::Write::write_all(&mut temp, content.as_bytes())?;
-            let path = temp.path().to_owned();
-            Ok::<_, std::io::Error>((path, temp))
-        }
-    })
-    .await??;
+    let file = write_temp_file(content.into()).await?;
 
     // Exit Ratatui mode
     ExecutableCommand::execute(&mut std::io::stdout(), LeaveAlternateScreen)?;
     disable_raw_mode()?;
 
     // Launch the external editor
-    let editor = env::var("EDITOR").unwrap_or_else(|_| "nvim".to_string());
-    let status = Command::new(&editor).arg(&file_path).status().await?;
-    if !status.success() {
-        bail!("The editor exited with an error: {:?}", status.code());
-    }
-
-    // Read updated content
-    let mut updated_content = String::new();
-    let mut file = File::open(file_path).await?;
-    AsyncReadExt::read_to_string(&mut file, &mut updated_content).await?;
+    let status = match editor_command(&app.editor, app.editor_args.as_deref(), file.path()) {
+        Ok((program, args)) => Command::new(program).args(args).status().await,
+        Err(e) => Err(std::io::Error::other(e)),
+    };
 
     // Re-enable Ratatui mode
     std::io::stdout().execute(EnterAlternateScreen)?;
@@ -295,5 +554,38 @@ where
     terminal.clear()?;
     terminal.hide_cursor()?;
 
-    Ok(updated_content)
+    let failure = match status {
+        Ok(status) if status.success() => return Ok(Some(read_temp_file(file.path()).await?)),
+        Ok(status) => match status.code() {
+            Some(code) => format!("The editor exited with the code {code}"),
+            None => "The editor was killed".to_string(),
+        },
+        Err(e) => format!("Fail to start the editor {}: {e}", app.editor),
+    };
+    app.set_error(format!("{failure}, the text is unchanged"));
+    Ok(None)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_temp_file_round_trip() {
+        let file = write_temp_file("Steps to reproduce\n* déjà vu\n".to_string())
+            .await
+            .unwrap();
+        assert_eq!(
+            read_temp_file(file.path()).await.unwrap(),
+            "Steps to reproduce\n* déjà vu\n"
+        );
+
+        // What the editor saved is read back
+        std::fs::write(file.path(), "").unwrap();
+        assert_eq!(read_temp_file(file.path()).await.unwrap(), "");
+
+        let path = file.path().to_owned();
+        drop(file);
+        assert!(read_temp_file(&path).await.is_err());
+    }
 }
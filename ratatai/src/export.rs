@@ -0,0 +1,142 @@
+// src/export.rs
+
+use anyhow::bail;
+use chrono::{DateTime, Utc};
+use launchpad_api_client::{BugTaskEntry, LaunchpadBug};
+use serde::Serialize;
+use std::{fs, path::Path};
+
+/// Formats a bug as markdown, ready to be pasted into a wiki page or a pull request.
+pub(crate) fn bug_to_markdown(bug: &LaunchpadBug) -> String {
+    let date = |d: Option<chrono::DateTime<chrono::Utc>>| {
+        d.map(|d| d.date_naive().to_string())
+            .unwrap_or_else(|| "unknown".to_string())
+    };
+
+    let mut markdown = format!("# Bug #{}: {}\n\n", bug.id, bug.title);
+    markdown.push_str(&format!("- **Link:** {}\n", bug.web_link));
+    markdown.push_str(&format!("- **Visibility:** {}\n", bug.information_type));
+    markdown.push_str(&format!("- **Created:** {}\n", date(bug.date_created)));
+    markdown.push_str(&format!(
+        "- **Last updated:** {}\n",
+        date(bug.date_last_updated)
+    ));
+    if !bug.tags.is_empty() {
+        markdown.push_str(&format!("- **Tags:** {}\n", bug.tags.join(", ")));
+    }
+    markdown.push_str(&format!("- **Heat:** {}\n", bug.heat));
+    markdown.push_str(&format!("- **Comments:** {}\n", bug.message_count));
+    markdown.push_str(&format!(
+        "- **Users affected:** {}\n\n",
+        bug.users_affected_count
+    ));
+
+    for line in bug.description.lines() {
+        if line.is_empty() {
+            markdown.push_str(">\n");
+        } else {
+            markdown.push_str(&format!("> {line}\n"));
+        }
+    }
+    markdown
+}
+
+/// A listed bug as exported, with what the table shows and its link.
+#[derive(Debug, Serialize)]
+struct ExportedBug<'a> {
+    id: u32,
+    date_created: Option<DateTime<Utc>>,
+    status: &'a str,
+    importance: &'a str,
+    title: &'a str,
+    web_link: &'a str,
+}
+
+impl<'a> From<&'a BugTaskEntry> for ExportedBug<'a> {
+    fn from(task: &'a BugTaskEntry) -> Self {
+        Self {
+            id: task.bug_id().unwrap_or_else(|| task.get_id()),
+            date_created: task.date_created,
+            status: &task.status,
+            importance: &task.importance,
+            title: task.bug_title().unwrap_or(&task.title),
+            web_link: &task.web_link,
+        }
+    }
+}
+
+/// Quotes a CSV field if it holds a separator, a quote or a line break.
+fn csv_field(field: &str) -> String {
+    if field.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_string()
+    }
+}
+
+fn bugs_to_csv(bugs: &[ExportedBug]) -> String {
+    let mut csv = "id,date_created,status,importance,title,web_link\n".to_string();
+    for bug in bugs {
+        let date = bug.date_created.map(|d| d.to_rfc3339()).unwrap_or_default();
+        let fields = [
+            bug.id.to_string(),
+            date,
+            csv_field(bug.status),
+            csv_field(bug.importance),
+            csv_field(bug.title),
+            csv_field(bug.web_link),
+        ];
+        csv.push_str(&fields.join(","));
+        csv.push('\n');
+    }
+    csv
+}
+
+/// Writes the bugs to `path` as CSV or JSON, depending on its extension.
+pub(crate) fn export_bugs(path: &Path, tasks: &[BugTaskEntry]) -> anyhow::Result<()> {
+    let bugs: Vec<ExportedBug> = tasks.iter().map(ExportedBug::from).collect();
+    let content = match path.extension().and_then(|ext| ext.to_str()) {
+        Some("csv") => bugs_to_csv(&bugs),
+        Some("json") => serde_json::to_string_pretty(&bugs)?,
+        _ => bail!("Unknown export format, use a .csv or .json file"),
+    };
+    fs::write(path, content)?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fixtures::bug_task;
+
+    #[test]
+    fn test_export_bugs() {
+        let dir = tempfile::tempdir().unwrap();
+        let tasks = [
+            bug_task(1, "nova-compute fails, again"),
+            bug_task(2, "resize \"stuck\""),
+        ];
+
+        let csv = dir.path().join("bugs.csv");
+        export_bugs(&csv, &tasks).unwrap();
+        assert_eq!(
+            fs::read_to_string(&csv).unwrap(),
+            "id,date_created,status,importance,title,web_link\n\
+             1,2025-01-13T08:46:25.105013+00:00,New,Undecided,\"nova-compute fails, again\",https://bugs.launchpad.net/nova/+bug/1\n\
+             2,2025-01-13T08:46:25.105013+00:00,New,Undecided,\"resize \"\"stuck\"\"\",https://bugs.launchpad.net/nova/+bug/2\n"
+        );
+
+        let json = dir.path().join("bugs.json");
+        export_bugs(&json, &tasks).unwrap();
+        let exported: serde_json::Value =
+            serde_json::from_str(&fs::read_to_string(&json).unwrap()).unwrap();
+        assert_eq!(exported[1]["id"], 2);
+        assert_eq!(exported[1]["title"], "resize \"stuck\"");
+        assert_eq!(
+            exported[0]["web_link"],
+            "https://bugs.launchpad.net/nova/+bug/1"
+        );
+
+        assert!(export_bugs(&dir.path().join("bugs.txt"), &tasks).is_err());
+    }
+}
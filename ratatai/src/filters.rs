@@ -0,0 +1,74 @@
+// src/filters.rs
+
+//! Hooks to transform the bug tasks once fetched, before they are displayed.
+
+use launchpad_api_client::BugTaskEntry;
+use std::{collections::HashSet, fmt::Debug};
+
+/// Filters or annotates the bug tasks after each fetch.
+pub trait BugFilter: Debug + Send {
+    fn apply(&self, bugs: &mut Vec<BugTaskEntry>);
+}
+
+/// Drops the bug tasks that are complete (fixed, invalid, won't fix...).
+#[derive(Debug, Default)]
+pub struct DropClosed;
+
+impl BugFilter for DropClosed {
+    fn apply(&self, bugs: &mut Vec<BugTaskEntry>) {
+        bugs.retain(|bug| !bug.is_complete);
+    }
+}
+
+/// Keeps only the bugs carrying a tag.
+///
+/// Bug tasks don't expose the tags of their bug, so the ids of the tagged
+/// bugs have to be provided, e.g. from a search filtered on the tag.
+#[derive(Debug)]
+pub struct OnlyTagged {
+    pub tag: String,
+    tagged_bugs: HashSet<u32>,
+}
+
+impl OnlyTagged {
+    pub fn new(tag: impl Into<String>, tagged_bugs: impl IntoIterator<Item = u32>) -> Self {
+        Self {
+            tag: tag.into(),
+            tagged_bugs: tagged_bugs.into_iter().collect(),
+        }
+    }
+}
+
+impl BugFilter for OnlyTagged {
+    fn apply(&self, bugs: &mut Vec<BugTaskEntry>) {
+        bugs.retain(|bug| self.tagged_bugs.contains(&bug.get_id()));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fixtures::bug_task;
+
+    #[test]
+    fn test_drop_closed() {
+        let mut closed = bug_task(2, "closed");
+        closed.is_complete = true;
+        let mut bugs = vec![bug_task(1, "open"), closed];
+
+        DropClosed.apply(&mut bugs);
+
+        assert_eq!(bugs.len(), 1);
+        assert_eq!(bugs[0].get_id(), 1);
+    }
+
+    #[test]
+    fn test_only_tagged() {
+        let mut bugs = vec![bug_task(1, "one"), bug_task(2, "two"), bug_task(3, "three")];
+
+        OnlyTagged::new("gate-failure", [1, 3]).apply(&mut bugs);
+
+        let ids: Vec<u32> = bugs.iter().map(|b| b.get_id()).collect();
+        assert_eq!(ids, [1, 3]);
+    }
+}
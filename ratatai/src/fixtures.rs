@@ -0,0 +1,98 @@
+// src/fixtures.rs
+
+use launchpad_api_client::{BugTaskEntry, LaunchpadBug, client::ReqwestClient};
+use serde_json::json;
+use tokio::sync::mpsc;
+
+use crate::{ai::LazyGeminiClient, app::App, config::Config, session::Session};
+
+/// Builds an application without AI nor persisted session, the channels
+/// receivers are dropped so background tasks results are discarded.
+pub(crate) fn test_app() -> App {
+    let (lp_sender, _) = mpsc::channel(5);
+    let (app_sender, _) = mpsc::channel(5);
+    let (_, chat_receiver) = mpsc::channel(5);
+    App::new(
+        &Config::default(),
+        LazyGeminiClient::new(None),
+        ReqwestClient::new(),
+        lp_sender,
+        app_sender,
+        chat_receiver,
+        Session::default(),
+    )
+}
+
+/// Builds a bug task of the nova project as returned by Launchpad.
+pub(crate) fn bug_task(id: u32, title: &str) -> BugTaskEntry {
+    serde_json::from_value(json!({
+        "self_link": format!("https://api.launchpad.net/1.0/nova/+bug/{id}"),
+        "web_link": format!("https://bugs.launchpad.net/nova/+bug/{id}"),
+        "resource_type_link": "https://api.launchpad.net/1.0/#bug_task",
+        "bug_link": format!("https://api.launchpad.net/1.0/bugs/{id}"),
+        "milestone_link": null,
+        "status": "New",
+        "importance": "Undecided",
+        "assignee_link": null,
+        "bug_target_display_name": "OpenStack Compute (nova)",
+        "bug_target_name": "nova",
+        "bug_watch_link": null,
+        "date_created": "2025-01-13T08:46:25.105013+00:00",
+        "owner_link": "https://api.launchpad.net/1.0/~reporter",
+        "target_link": "https://api.launchpad.net/1.0/nova",
+        "title": format!("Bug #{id} in OpenStack Compute (nova): \"{title}\""),
+        "related_tasks_collection_link": format!("https://api.launchpad.net/1.0/nova/+bug/{id}/related_tasks"),
+        "is_complete": false,
+        "http_etag": format!("\"etag-{id}\"")
+    }))
+    .unwrap()
+}
+
+/// Builds a public bug as returned by Launchpad.
+pub(crate) fn bug(id: u32, title: &str) -> LaunchpadBug {
+    let link = format!("https://api.launchpad.net/1.0/bugs/{id}");
+    serde_json::from_value(json!({
+        "self_link": link,
+        "web_link": format!("https://bugs.launchpad.net/bugs/{id}"),
+        "resource_type_link": "https://api.launchpad.net/1.0/#bug",
+        "id": id,
+        "private": false,
+        "information_type": "Public",
+        "name": null,
+        "title": title,
+        "description": "Description of the bug",
+        "owner_link": "https://api.launchpad.net/1.0/~reporter",
+        "bug_tasks_collection_link": format!("{link}/bug_tasks"),
+        "duplicate_of_link": null,
+        "date_created": "2025-01-13T08:46:25.105013+00:00",
+        "activity_collection_link": format!("{link}/activity"),
+        "can_expire": false,
+        "subscriptions_collection_link": format!("{link}/subscriptions"),
+        "date_last_updated": "2025-01-13T08:46:25.105013+00:00",
+        "who_made_private_link": null,
+        "date_made_private": null,
+        "heat": 6,
+        "bug_watches_collection_link": format!("{link}/bug_watches"),
+        "cves_collection_link": format!("{link}/cves"),
+        "vulnerabilities_collection_link": format!("{link}/vulnerabilities"),
+        "duplicates_collection_link": format!("{link}/duplicates"),
+        "attachments_collection_link": format!("{link}/attachments"),
+        "security_related": false,
+        "latest_patch_uploaded": null,
+        "tags": [],
+        "date_last_message": "2025-01-13T08:46:25.105013+00:00",
+        "number_of_duplicates": 0,
+        "message_count": 1,
+        "users_affected_count": 1,
+        "users_unaffected_count": 0,
+        "users_affected_collection_link": format!("{link}/users_affected"),
+        "users_unaffected_collection_link": format!("{link}/users_unaffected"),
+        "users_affected_count_with_dupes": 1,
+        "other_users_affected_count_with_dupes": 1,
+        "users_affected_with_dupes_collection_link": format!("{link}/users_affected_with_dupes"),
+        "messages_collection_link": format!("{link}/messages"),
+        "linked_branches_collection_link": format!("{link}/linked_branches"),
+        "http_etag": format!("\"etag-{id}\"")
+    }))
+    .unwrap()
+}
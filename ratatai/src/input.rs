@@ -0,0 +1,113 @@
+// src/input.rs
+
+//! Single line text input, like the prompt typed to the chat.
+
+use crossterm::event::KeyCode;
+
+/// Text being typed with the position of the cursor, in characters.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub(crate) struct TextInput {
+    text: String,
+    cursor: usize,
+}
+
+impl TextInput {
+    /// Starts with `text`, the cursor at its end.
+    pub(crate) fn new(text: impl Into<String>) -> Self {
+        let text = text.into();
+        Self {
+            cursor: text.chars().count(),
+            text,
+        }
+    }
+
+    pub(crate) fn text(&self) -> &str {
+        &self.text
+    }
+
+    pub(crate) fn cursor(&self) -> usize {
+        self.cursor
+    }
+
+    /// Byte index of the character at `index`, the end of the text past it.
+    fn byte_index(&self, index: usize) -> usize {
+        self.text
+            .char_indices()
+            .nth(index)
+            .map_or(self.text.len(), |(i, _)| i)
+    }
+
+    /// Applies an editing key, the other keys are ignored.
+    pub(crate) fn edit(&mut self, code: KeyCode) {
+        let len = self.text.chars().count();
+        match code {
+            KeyCode::Char(c) => {
+                let at = self.byte_index(self.cursor);
+                self.text.insert(at, c);
+                self.cursor += 1;
+            }
+            KeyCode::Backspace if self.cursor > 0 => {
+                self.cursor -= 1;
+                let at = self.byte_index(self.cursor);
+                self.text.remove(at);
+            }
+            KeyCode::Delete if self.cursor < len => {
+                let at = self.byte_index(self.cursor);
+                self.text.remove(at);
+            }
+            KeyCode::Left => self.cursor = self.cursor.saturating_sub(1),
+            KeyCode::Right => self.cursor = (self.cursor + 1).min(len),
+            KeyCode::Home => self.cursor = 0,
+            KeyCode::End => self.cursor = len,
+            _ => {}
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn type_text(input: &mut TextInput, text: &str) {
+        for c in text.chars() {
+            input.edit(KeyCode::Char(c));
+        }
+    }
+
+    #[test]
+    fn test_text_input_editing() {
+        let mut input = TextInput::default();
+        type_text(&mut input, "make it shorter");
+        input.edit(KeyCode::Home);
+        type_text(&mut input, "please ");
+        input.edit(KeyCode::End);
+        input.edit(KeyCode::Backspace);
+        assert_eq!(input.text(), "please make it shorte");
+        assert_eq!(input.cursor(), 21);
+
+        input.edit(KeyCode::Left);
+        input.edit(KeyCode::Left);
+        input.edit(KeyCode::Delete);
+        assert_eq!(input.text(), "please make it shore");
+
+        // The cursor stays within the text
+        input.edit(KeyCode::Right);
+        input.edit(KeyCode::Right);
+        input.edit(KeyCode::Right);
+        assert_eq!(input.cursor(), 20);
+        input.edit(KeyCode::Delete);
+        assert_eq!(input.text(), "please make it shore");
+    }
+
+    #[test]
+    fn test_text_input_multibyte() {
+        let mut input = TextInput::default();
+        type_text(&mut input, "déjà");
+        input.edit(KeyCode::Left);
+        input.edit(KeyCode::Backspace);
+        assert_eq!(input.text(), "déà");
+        input.edit(KeyCode::Home);
+        input.edit(KeyCode::Backspace);
+        assert_eq!(input.text(), "déà");
+    }
+}
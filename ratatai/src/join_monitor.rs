@@ -3,9 +3,10 @@ use std::pin::Pin;
 use std::sync::Arc;
 use std::task::{Context, Poll, Wake, Waker};
 
-use tokio::task::JoinHandle;
+use tokio::task::{AbortHandle, JoinHandle};
 use tracing::{error, warn};
 
+#[derive(Debug)]
 pub struct JoinHandleMonitor<T> {
     handle: Pin<Box<JoinHandle<T>>>,
     waker: Waker,
@@ -20,6 +21,11 @@ impl<T> JoinHandleMonitor<T> {
         }
     }
 
+    /// Gives back the handle, to wait for the task rather than poll it.
+    pub fn into_handle(self) -> JoinHandle<T> {
+        *Pin::into_inner(self.handle)
+    }
+
     pub fn is_finished(&mut self) -> Option<Result<T, tokio::task::JoinError>> {
         let mut cx = Context::from_waker(&self.waker);
         match self.handle.as_mut().poll(&mut cx) {
@@ -37,13 +43,103 @@ fn dummy_waker() -> Waker {
     std::task::Waker::from(Arc::new(Dummy))
 }
 
-pub fn check_monitor(monitor: &mut JoinHandleMonitor<()>) -> bool {
-    if let Some(result) = monitor.is_finished() {
-        match result {
-            Ok(_) => warn!("⚠️ Chat task stopped."),
-            Err(e) => error!("💥 Chat task panicked : {e}"),
+/// Returns the result of the monitored task once it is finished, logging how
+/// it ended and letting the caller decide what to do.
+pub fn check_monitor<T>(
+    monitor: &mut JoinHandleMonitor<T>,
+    name: &str,
+) -> Option<Result<T, tokio::task::JoinError>> {
+    let result = monitor.is_finished()?;
+    match &result {
+        Ok(_) => warn!("⚠️ {name} task stopped."),
+        Err(e) => error!("💥 {name} task panicked : {e}"),
+    }
+    Some(result)
+}
+
+/// Registry of named background tasks, to report which one finished.
+#[derive(Debug, Default)]
+pub struct TaskMonitor {
+    tasks: Vec<(String, JoinHandleMonitor<()>)>,
+}
+
+impl TaskMonitor {
+    /// Spawns `future` and monitors it under `name`, the returned handle
+    /// aborts it.
+    pub fn spawn<F>(&mut self, name: &str, future: F) -> AbortHandle
+    where
+        F: Future<Output = ()> + Send + 'static,
+    {
+        let handle = tokio::spawn(future);
+        let abort_handle = handle.abort_handle();
+        self.add(name, handle);
+        abort_handle
+    }
+
+    pub fn add(&mut self, name: &str, handle: JoinHandle<()>) {
+        self.tasks
+            .push((name.to_string(), JoinHandleMonitor::new(handle)));
+    }
+
+    /// Stops monitoring the finished tasks and returns their names with
+    /// their join results.
+    pub fn check(&mut self) -> Vec<(String, Result<(), tokio::task::JoinError>)> {
+        let mut finished = Vec::new();
+        self.tasks
+            .retain_mut(|(name, monitor)| match check_monitor(monitor, name) {
+                Some(result) => {
+                    finished.push((name.clone(), result));
+                    false
+                }
+                None => true,
+            });
+        finished
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_check_monitor_returns_join_result() {
+        let mut monitor = JoinHandleMonitor::new(tokio::spawn(async { 42 }));
+        let result = loop {
+            if let Some(result) = check_monitor(&mut monitor, "Test") {
+                break result;
+            }
+            tokio::task::yield_now().await;
+        };
+        assert_eq!(result.unwrap(), 42);
+
+        let mut monitor = JoinHandleMonitor::new(tokio::spawn(async { panic!("boom") }));
+        let result = loop {
+            if let Some(result) = check_monitor::<()>(&mut monitor, "Test") {
+                break result;
+            }
+            tokio::task::yield_now().await;
+        };
+        assert!(result.unwrap_err().is_panic());
+    }
+
+    #[tokio::test]
+    async fn test_task_monitor_reports_finished_tasks() {
+        let mut tasks = TaskMonitor::default();
+        tasks.spawn("Ok", async {});
+        tasks.spawn("Panic", async { panic!("boom") });
+        tasks.spawn("Pending", std::future::pending());
+
+        let mut finished = Vec::new();
+        while finished.len() < 2 {
+            finished.extend(tasks.check());
+            tokio::task::yield_now().await;
         }
-        return true;
+        finished.sort_by(|a, b| a.0.cmp(&b.0));
+
+        assert_eq!(tasks.tasks.len(), 1);
+        assert_eq!(finished[0].0, "Ok");
+        assert!(finished[0].1.is_ok());
+        assert_eq!(finished[1].0, "Panic");
+        assert!(finished[1].1.as_ref().unwrap_err().is_panic());
     }
-    false
 }
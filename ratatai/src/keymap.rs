@@ -0,0 +1,841 @@
+// src/keymap.rs
+
+use anyhow::{Context, bail};
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use serde::{Deserialize, de::IntoDeserializer};
+use std::{collections::BTreeMap, str::FromStr};
+
+/// Logical actions the user can trigger from the keyboard, named in snake
+/// case in the `[keys]` table of the configuration.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub(crate) enum Action {
+    Quit,
+    ToggleSpinner,
+    Help,
+    ToggleCompactHints,
+    CycleLogLevel,
+    SwitchPanel,
+    Back,
+    NavUp,
+    NavDown,
+    PageUp,
+    PageDown,
+    HalfPageUp,
+    HalfPageDown,
+    GoToStart,
+    GoToEnd,
+    RefreshList,
+    ToggleMyActivity,
+    ToggleAssignedToMe,
+    ToggleDuplicates,
+    CycleImportance,
+    SelectStatuses,
+    Search,
+    CycleSort,
+    ReverseSort,
+    ExportBugs,
+    OpenBug,
+    GoToBug,
+    OpenDuplicateOf,
+    ReloadBug,
+    OpenBrowser,
+    CopyLink,
+    CopyDescription,
+    FilterTag,
+    NextTag,
+    PreviousTag,
+    ClearTag,
+    ToggleMarkdown,
+    ToggleActivity,
+    ToggleSplitView,
+    ExportMarkdown,
+    AiGenerate,
+    CancelAi,
+    EditExternal,
+    UndoEdit,
+    Reply,
+    CraftReply,
+    PreviewPrompt,
+    QuoteInReply,
+    UseAiAnswer,
+    RefineReply,
+    AskChat,
+    FocusChatHistory,
+    SaveToOutbox,
+    WriteReply,
+    PostReply,
+    PostAndMarkIncomplete,
+}
+
+impl Action {
+    /// Parses the name of an action in the configuration, like `open_bug`.
+    pub(crate) fn from_name(name: &str) -> anyhow::Result<Self> {
+        Action::deserialize(name.into_deserializer())
+            .map_err(|e: serde::de::value::Error| anyhow::anyhow!("{e}"))
+    }
+
+    /// Whether the action asks the AI, which is unavailable offline.
+    pub(crate) fn needs_ai(&self) -> bool {
+        matches!(
+            self,
+            Action::AiGenerate | Action::CraftReply | Action::RefineReply | Action::AskChat
+        )
+    }
+
+    /// Name of the action in the configuration.
+    pub(crate) fn name(&self) -> String {
+        let mut name = String::new();
+        for c in format!("{self:?}").chars() {
+            if c.is_uppercase() && !name.is_empty() {
+                name.push('_');
+            }
+            name.push(c.to_ascii_lowercase());
+        }
+        name
+    }
+
+    /// Short text used in the bottom command bar, actions sharing it are grouped.
+    pub(crate) fn hint(&self) -> &'static str {
+        match self {
+            Action::Quit => "quit",
+            Action::ToggleSpinner => "toggle spinner",
+            Action::Help => "help",
+            Action::ToggleCompactHints => "compact",
+            Action::CycleLogLevel => "log level",
+            Action::SwitchPanel => "selection",
+            Action::Back => "back",
+            Action::NavUp
+            | Action::NavDown
+            | Action::PageUp
+            | Action::PageDown
+            | Action::HalfPageUp
+            | Action::HalfPageDown
+            | Action::GoToStart
+            | Action::GoToEnd => "navigate",
+            Action::RefreshList => "refresh list",
+            Action::ToggleMyActivity => "my activity",
+            Action::ToggleAssignedToMe => "assigned to me",
+            Action::ToggleDuplicates => "duplicates",
+            Action::CycleImportance => "importance",
+            Action::SelectStatuses => "statuses",
+            Action::Search => "search",
+            Action::CycleSort | Action::ReverseSort => "sort",
+            Action::ExportBugs => "export",
+            Action::OpenBug => "open bug",
+            Action::GoToBug => "go to bug",
+            Action::OpenDuplicateOf => "open original",
+            Action::ReloadBug => "reload bug",
+            Action::OpenBrowser => "open in browser",
+            Action::CopyLink | Action::CopyDescription => "copy",
+            Action::FilterTag => "filter tag",
+            Action::NextTag | Action::PreviousTag => "tags",
+            Action::ClearTag => "clear tag",
+            Action::ToggleMarkdown => "markdown",
+            Action::ToggleActivity => "activity",
+            Action::ToggleSplitView => "split view",
+            Action::ExportMarkdown => "export as markdown",
+            Action::AiGenerate => "ask AI",
+            Action::CancelAi => "cancel AI",
+            Action::EditExternal => "edit",
+            Action::UndoEdit => "undo edit",
+            Action::Reply => "reply to this bug",
+            Action::CraftReply => "craft a reply to this bug",
+            Action::PreviewPrompt => "preview prompt",
+            Action::QuoteInReply => "quote in reply",
+            Action::UseAiAnswer => "reply with AI answer",
+            Action::RefineReply => "ask chat to refine this bug",
+            Action::AskChat => "ask chat",
+            Action::FocusChatHistory => "chat history",
+            Action::SaveToOutbox => "save to outbox",
+            Action::WriteReply => "write to file",
+            Action::PostReply => "post",
+            Action::PostAndMarkIncomplete => "post + incomplete",
+        }
+    }
+
+    /// Full description displayed in the help popup.
+    pub(crate) fn description(&self) -> &'static str {
+        match self {
+            Action::Quit => "Quit the application",
+            Action::ToggleSpinner => "Toggle the spinner and change its label",
+            Action::Help => "Show or hide this help",
+            Action::ToggleCompactHints => "Only show the help key in the bottom bar",
+            Action::CycleLogLevel => "Make the logs more verbose, back to errors after traces",
+            Action::SwitchPanel => "Switch the active panel",
+            Action::Back => "Go back to the bug list",
+            Action::NavUp => "Move up",
+            Action::NavDown => "Move down",
+            Action::PageUp => "Move one page up",
+            Action::PageDown => "Move one page down",
+            Action::HalfPageUp => "Move half a page up",
+            Action::HalfPageDown => "Move half a page down",
+            Action::GoToStart => "Go to the start",
+            Action::GoToEnd => "Go to the end",
+            Action::RefreshList => "Refresh the bug list",
+            Action::ToggleMyActivity => "Only show the bugs I have interacted with",
+            Action::ToggleAssignedToMe => {
+                "Only list the bugs assigned to the configured assignee and refresh the list"
+            }
+            Action::ToggleDuplicates => "Include or omit the duplicate bugs and refresh the list",
+            Action::CycleImportance => {
+                "Only show the bugs of the next importance and refresh the list"
+            }
+            Action::SelectStatuses => "Choose the statuses of the listed bugs",
+            Action::Search => "Only show the bugs whose title contains the typed text",
+            Action::CycleSort => "Sort the bugs by the next column: date, id, importance, status",
+            Action::ReverseSort => "Reverse the sort order",
+            Action::ExportBugs => "Export the listed bugs to a .csv or .json file",
+            Action::OpenBug => "Open the selected bug",
+            Action::GoToBug => "Type the number of a bug to open, listed or not",
+            Action::OpenDuplicateOf => "Open the bug this one is a duplicate of",
+            Action::ReloadBug => "Fetch the bug again from Launchpad rather than from the cache",
+            Action::OpenBrowser => "Open the bug in a web browser",
+            Action::CopyLink => "Copy the link of the bug to the clipboard",
+            Action::CopyDescription => "Copy the description of the bug to the clipboard",
+            Action::FilterTag => {
+                "Only list the bugs with the next tag of this bug, all of them after the last tag"
+            }
+            Action::NextTag => "Only list the bugs with the next tag found in the list",
+            Action::PreviousTag => "Only list the bugs with the previous tag found in the list",
+            Action::ClearTag => "List the bugs whatever their tags",
+            Action::ToggleMarkdown => "Render the description as markdown or as raw text",
+            Action::ToggleActivity => "Show the changes made to the bug, or back its description",
+            Action::ToggleSplitView => {
+                "Show the reply next to the description, if the terminal is wide enough"
+            }
+            Action::ExportMarkdown => "Export the bug to a markdown file",
+            Action::AiGenerate => "Ask the AI about the displayed text",
+            Action::CancelAi => "Cancel the pending AI request",
+            Action::EditExternal => "Edit the text in an external editor",
+            Action::UndoEdit => "Restore the text as it was before the last external edit",
+            Action::Reply => "Start a reply to this bug",
+            Action::CraftReply => "Ask the chat to craft a reply to this bug",
+            Action::PreviewPrompt => {
+                "Show the prompt a reply would be crafted with, without sending it"
+            }
+            Action::QuoteInReply => "Quote the bug description in the reply",
+            Action::UseAiAnswer => "Replace the reply with the AI answer and edit it",
+            Action::RefineReply => "Ask the chat to refine the reply",
+            Action::AskChat => "Type a prompt to send to the chat",
+            Action::FocusChatHistory => "Scroll the chat history rather than the draft, or back",
+            Action::SaveToOutbox => "Save the reply in the outbox to be reviewed before posting",
+            Action::WriteReply => "Write the reply to a file named after the bug",
+            Action::PostReply => "Post the reply as a comment on Launchpad",
+            Action::PostAndMarkIncomplete => {
+                "Post the reply, then set the bug Incomplete while waiting for an answer"
+            }
+        }
+    }
+}
+
+/// Where a binding is active, `Global` bindings apply everywhere.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub(crate) enum KeyContext {
+    Global,
+    BugTable,
+    BugDescription,
+    ReplyDescription,
+    BugReply,
+}
+
+impl KeyContext {
+    pub(crate) const ALL: [KeyContext; 5] = [
+        KeyContext::Global,
+        KeyContext::BugTable,
+        KeyContext::BugDescription,
+        KeyContext::ReplyDescription,
+        KeyContext::BugReply,
+    ];
+
+    pub(crate) fn title(&self) -> &'static str {
+        match self {
+            KeyContext::Global => "Global",
+            KeyContext::BugTable => "Bug list",
+            KeyContext::BugDescription => "Bug description",
+            KeyContext::ReplyDescription => "Bug description (reply screen)",
+            KeyContext::BugReply => "Bug reply",
+        }
+    }
+}
+
+/// A key with its modifiers, shift is ignored for characters as it is part of the char.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub(crate) struct Key {
+    pub code: KeyCode,
+    pub modifiers: KeyModifiers,
+}
+
+impl Key {
+    pub(crate) const fn new(code: KeyCode) -> Self {
+        Self {
+            code,
+            modifiers: KeyModifiers::NONE,
+        }
+    }
+
+    pub(crate) const fn ctrl(c: char) -> Self {
+        Self {
+            code: KeyCode::Char(c),
+            modifiers: KeyModifiers::CONTROL,
+        }
+    }
+
+    /// Whether the key only triggers its action when pressed twice in a row,
+    /// like `gg` in vim.
+    pub(crate) fn is_repeated(&self) -> bool {
+        *self == Key::new(KeyCode::Char('g'))
+    }
+
+    /// Returns a printable name for the key, `unicode` selects arrows over words.
+    pub(crate) fn label(&self, unicode: bool) -> String {
+        let code = match self.code {
+            KeyCode::Char(c) if self.is_repeated() => format!("'{c}{c}'"),
+            KeyCode::Char(c) => format!("'{c}'"),
+            KeyCode::Up if unicode => "↑".to_string(),
+            KeyCode::Down if unicode => "↓".to_string(),
+            KeyCode::Left if unicode => "←".to_string(),
+            KeyCode::Right if unicode => "→".to_string(),
+            KeyCode::PageUp => "PgUp".to_string(),
+            KeyCode::PageDown => "PgDown".to_string(),
+            code => code.to_string(),
+        };
+        if self.modifiers.contains(KeyModifiers::CONTROL) {
+            format!("Ctrl-{}", code.trim_matches('\''))
+        } else {
+            code
+        }
+    }
+}
+
+/// Parses a key of the configuration: a character ("a", "?"), a named key
+/// ("Enter", "PgDown", "F5") or a control character ("Ctrl-s").
+impl FromStr for Key {
+    type Err = anyhow::Error;
+
+    fn from_str(name: &str) -> Result<Self, Self::Err> {
+        let single_char = |name: &str| {
+            let mut chars = name.chars();
+            match (chars.next(), chars.next()) {
+                (Some(c), None) => Some(c),
+                _ => None,
+            }
+        };
+        if let Some(c) = single_char(name) {
+            return Ok(Key::new(KeyCode::Char(c)));
+        }
+        let lowercase = name.to_lowercase();
+        if let Some(c) = ["ctrl-", "ctrl+"]
+            .iter()
+            .find_map(|prefix| lowercase.strip_prefix(prefix))
+            .and_then(single_char)
+        {
+            return Ok(Key::ctrl(c));
+        }
+        let code = match lowercase.as_str() {
+            "enter" => KeyCode::Enter,
+            "esc" | "escape" => KeyCode::Esc,
+            "tab" => KeyCode::Tab,
+            "backtab" => KeyCode::BackTab,
+            "backspace" => KeyCode::Backspace,
+            "delete" | "del" => KeyCode::Delete,
+            "space" => KeyCode::Char(' '),
+            "up" => KeyCode::Up,
+            "down" => KeyCode::Down,
+            "left" => KeyCode::Left,
+            "right" => KeyCode::Right,
+            "home" => KeyCode::Home,
+            "end" => KeyCode::End,
+            "pageup" | "pgup" => KeyCode::PageUp,
+            "pagedown" | "pgdown" => KeyCode::PageDown,
+            function => match function.strip_prefix('f').map(u8::from_str) {
+                Some(Ok(n @ 1..=12)) => KeyCode::F(n),
+                _ => bail!("unknown key \"{name}\""),
+            },
+        };
+        Ok(Key::new(code))
+    }
+}
+
+impl From<KeyEvent> for Key {
+    fn from(event: KeyEvent) -> Self {
+        let mut modifiers = event.modifiers;
+        if let KeyCode::Char(_) = event.code {
+            modifiers.remove(KeyModifiers::SHIFT);
+        }
+        Self {
+            code: event.code,
+            modifiers,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct Binding {
+    pub context: KeyContext,
+    pub key: Key,
+    pub action: Action,
+    /// Listed in the bottom bar, not only in the help
+    pub in_hints: bool,
+}
+
+/// Maps the keys to actions for each context, ordered as they are displayed.
+#[derive(Debug, Clone)]
+pub(crate) struct KeyMap {
+    bindings: Vec<Binding>,
+}
+
+impl Default for KeyMap {
+    fn default() -> Self {
+        use Action::*;
+        use KeyContext::*;
+
+        let navigation = [
+            (KeyCode::Up, NavUp),
+            (KeyCode::Down, NavDown),
+            (KeyCode::PageUp, PageUp),
+            (KeyCode::PageDown, PageDown),
+            (KeyCode::Home, GoToStart),
+            (KeyCode::End, GoToEnd),
+        ];
+
+        let mut bindings = vec![
+            (Global, KeyCode::Char('q'), Quit),
+            (Global, KeyCode::Char('s'), ToggleSpinner),
+            (Global, KeyCode::Char('?'), Help),
+            (Global, KeyCode::Char('H'), ToggleCompactHints),
+            (Global, KeyCode::Char('L'), CycleLogLevel),
+        ];
+        for context in [BugTable, BugDescription, ReplyDescription, BugReply] {
+            bindings.push((context, KeyCode::Tab, SwitchPanel));
+            bindings.extend(navigation.map(|(code, action)| (context, code, action)));
+        }
+        bindings.extend([
+            (BugTable, KeyCode::Char('r'), RefreshList),
+            (BugTable, KeyCode::Char('i'), ToggleMyActivity),
+            (BugTable, KeyCode::Char('A'), ToggleAssignedToMe),
+            (BugTable, KeyCode::Char('D'), ToggleDuplicates),
+            (BugTable, KeyCode::Char('I'), CycleImportance),
+            (BugTable, KeyCode::Char('f'), SelectStatuses),
+            (BugTable, KeyCode::Char('/'), Search),
+            (BugTable, KeyCode::Char('o'), CycleSort),
+            (BugTable, KeyCode::Char('O'), ReverseSort),
+            (BugTable, KeyCode::Char('x'), ExportBugs),
+            (BugTable, KeyCode::Char('t'), NextTag),
+            (BugTable, KeyCode::Char('T'), PreviousTag),
+            (BugTable, KeyCode::Char('c'), ClearTag),
+            (BugTable, KeyCode::Enter, OpenBug),
+            (BugTable, KeyCode::Char(':'), GoToBug),
+            (BugTable, KeyCode::Char('|'), ToggleSplitView),
+            (BugTable, KeyCode::Char('R'), ReloadBug),
+            (BugDescription, KeyCode::Char('r'), RefreshList),
+            (BugDescription, KeyCode::Char('R'), ReloadBug),
+            (BugDescription, KeyCode::Char(':'), GoToBug),
+            (BugDescription, KeyCode::Char('|'), ToggleSplitView),
+            (BugDescription, KeyCode::Char('d'), OpenDuplicateOf),
+            (BugDescription, KeyCode::Char('p'), PreviewPrompt),
+            (BugDescription, KeyCode::Char('v'), OpenBrowser),
+            (BugDescription, KeyCode::Char('y'), CopyLink),
+            (BugDescription, KeyCode::Char('Y'), CopyDescription),
+            (BugDescription, KeyCode::Char('t'), FilterTag),
+            (BugDescription, KeyCode::Char('M'), ToggleMarkdown),
+            (BugDescription, KeyCode::Char('h'), ToggleActivity),
+            (BugDescription, KeyCode::Char('m'), ExportMarkdown),
+            (BugDescription, KeyCode::Char('a'), AiGenerate),
+            (BugDescription, KeyCode::Char('c'), CancelAi),
+            (BugDescription, KeyCode::Char('e'), EditExternal),
+            (BugDescription, KeyCode::Char('U'), UndoEdit),
+            (BugDescription, KeyCode::Char('u'), UseAiAnswer),
+            (BugDescription, KeyCode::Enter, Reply),
+            (ReplyDescription, KeyCode::Esc, Back),
+            (ReplyDescription, KeyCode::Char('v'), OpenBrowser),
+            (ReplyDescription, KeyCode::Char('y'), CopyLink),
+            (ReplyDescription, KeyCode::Char('Y'), CopyDescription),
+            (ReplyDescription, KeyCode::Char('M'), ToggleMarkdown),
+            (ReplyDescription, KeyCode::Char('h'), ToggleActivity),
+            (ReplyDescription, KeyCode::Char('m'), ExportMarkdown),
+            (ReplyDescription, KeyCode::Char('a'), AiGenerate),
+            (ReplyDescription, KeyCode::Char('c'), CancelAi),
+            (ReplyDescription, KeyCode::Char('e'), EditExternal),
+            (ReplyDescription, KeyCode::Char('U'), UndoEdit),
+            (ReplyDescription, KeyCode::Char('>'), QuoteInReply),
+            (ReplyDescription, KeyCode::Char('u'), UseAiAnswer),
+            (ReplyDescription, KeyCode::Enter, CraftReply),
+            (ReplyDescription, KeyCode::Char('p'), PreviewPrompt),
+            (ReplyDescription, KeyCode::Char('i'), AskChat),
+            (BugReply, KeyCode::Esc, Back),
+            (BugReply, KeyCode::Char('e'), EditExternal),
+            (BugReply, KeyCode::Char('U'), UndoEdit),
+            (BugReply, KeyCode::Char('o'), SaveToOutbox),
+            (BugReply, KeyCode::Char('w'), WriteReply),
+            (BugReply, KeyCode::Char('I'), PostAndMarkIncomplete),
+            (BugReply, KeyCode::Char('i'), AskChat),
+            (BugReply, KeyCode::Char('h'), FocusChatHistory),
+            (BugReply, KeyCode::Enter, RefineReply),
+        ]);
+
+        let mut bindings: Vec<Binding> = bindings
+            .into_iter()
+            .map(|(context, code, action)| Binding {
+                context,
+                key: Key::new(code),
+                action,
+                in_hints: true,
+            })
+            .collect();
+        bindings.push(Binding {
+            context: BugReply,
+            key: Key::ctrl('s'),
+            action: PostReply,
+            in_hints: true,
+        });
+        // Vim keys, only listed in the help to keep the bottom bar short
+        bindings.extend(
+            [
+                (Key::new(KeyCode::Char('j')), NavDown),
+                (Key::new(KeyCode::Char('k')), NavUp),
+                (Key::ctrl('d'), HalfPageDown),
+                (Key::ctrl('u'), HalfPageUp),
+                (Key::new(KeyCode::Char('g')), GoToStart),
+                (Key::new(KeyCode::Char('G')), GoToEnd),
+            ]
+            .map(|(key, action)| Binding {
+                context: BugTable,
+                key,
+                action,
+                in_hints: false,
+            }),
+        );
+
+        Self { bindings }
+    }
+}
+
+impl KeyMap {
+    /// Builds the default bindings with the keys of some actions replaced,
+    /// an action keeps the contexts it is bound in. Fails on unknown names,
+    /// and on keys given twice or bound to another action of the context.
+    pub(crate) fn with_overrides(keys: &BTreeMap<String, Vec<String>>) -> anyhow::Result<Self> {
+        let mut keymap = Self::default();
+        for (name, key_names) in keys {
+            let action = Action::from_name(name)?;
+            if key_names.is_empty() {
+                bail!("no key given for {name}");
+            }
+            let mut new_keys: Vec<Key> = Vec::new();
+            for key_name in key_names {
+                let key = key_name
+                    .parse()
+                    .with_context(|| format!("invalid key for {name}"))?;
+                if new_keys.contains(&key) {
+                    bail!("{key_name} is given twice for {name}");
+                }
+                new_keys.push(key);
+            }
+            keymap.rebind(action, &new_keys);
+        }
+        keymap.check_conflicts()?;
+        Ok(keymap)
+    }
+
+    /// Replaces the keys of an action, in place of its first binding of
+    /// each context.
+    fn rebind(&mut self, action: Action, keys: &[Key]) {
+        for context in KeyContext::ALL {
+            let Some(position) = self
+                .bindings
+                .iter()
+                .position(|b| b.context == context && b.action == action)
+            else {
+                continue;
+            };
+            let in_hints = self
+                .bindings
+                .iter()
+                .any(|b| b.context == context && b.action == action && b.in_hints);
+            self.bindings
+                .retain(|b| b.context != context || b.action != action);
+            self.bindings.splice(
+                position..position,
+                keys.iter().map(|key| Binding {
+                    context,
+                    key: *key,
+                    action,
+                    in_hints,
+                }),
+            );
+        }
+    }
+
+    /// Fails when a key triggers two actions of a context, a global binding
+    /// hiding the binding of a context.
+    fn check_conflicts(&self) -> anyhow::Result<()> {
+        for (i, binding) in self.bindings.iter().enumerate() {
+            let conflict = self.bindings[i + 1..].iter().find(|other| {
+                other.key == binding.key
+                    && (other.context == binding.context
+                        || other.context == KeyContext::Global
+                        || binding.context == KeyContext::Global)
+            });
+            if let Some(other) = conflict {
+                bail!(
+                    "{} is bound to both {} and {} in {}",
+                    binding.key.label(false),
+                    binding.action.name(),
+                    other.action.name(),
+                    other.context.title()
+                );
+            }
+        }
+        Ok(())
+    }
+
+    /// Returns the action bound to a key, global bindings take precedence.
+    pub(crate) fn action(&self, context: KeyContext, key: Key) -> Option<Action> {
+        [KeyContext::Global, context].iter().find_map(|ctx| {
+            self.bindings
+                .iter()
+                .find(|b| b.context == *ctx && b.key == key)
+                .map(|b| b.action)
+        })
+    }
+
+    pub(crate) fn bindings(&self, context: KeyContext) -> impl Iterator<Item = &Binding> {
+        self.bindings.iter().filter(move |b| b.context == context)
+    }
+
+    /// Builds the command bar text of a context, grouping the keys sharing the same hint.
+    pub(crate) fn hint(&self, context: KeyContext, unicode: bool) -> String {
+        let mut groups: Vec<(&str, Vec<String>)> = Vec::new();
+        for binding in self.bindings(context).filter(|b| b.in_hints) {
+            let hint = binding.action.hint();
+            let label = binding.key.label(unicode);
+            match groups.iter_mut().find(|(h, _)| *h == hint) {
+                Some((_, keys)) => keys.push(label),
+                None => groups.push((hint, vec![label])),
+            }
+        }
+        groups
+            .iter()
+            .map(|(hint, keys)| format!("{} {hint}", keys.join("/")))
+            .collect::<Vec<_>>()
+            .join(", ")
+    }
+
+    /// Builds the command bar text reduced to the key opening the help.
+    pub(crate) fn compact_hint(&self, unicode: bool) -> String {
+        self.bindings(KeyContext::Global)
+            .find(|b| b.action == Action::Help)
+            .map(|b| format!("{} {}", b.key.label(unicode), b.action.hint()))
+            .unwrap_or_default()
+    }
+
+    /// Builds the help text listing every binding, grouped by context.
+    pub(crate) fn help(&self, unicode: bool) -> Vec<String> {
+        let mut lines = Vec::new();
+        for context in KeyContext::ALL {
+            lines.push(format!("{}:", context.title()));
+            for binding in self.bindings(context) {
+                lines.push(format!(
+                    "  {:<10} {}",
+                    binding.key.label(unicode),
+                    binding.action.description()
+                ));
+            }
+            lines.push(String::new());
+        }
+        lines
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_action_lookup() {
+        let keymap = KeyMap::default();
+
+        let quit = keymap.action(KeyContext::BugReply, Key::new(KeyCode::Char('q')));
+        assert_eq!(quit, Some(Action::Quit));
+
+        let enter = Key::new(KeyCode::Enter);
+        assert_eq!(
+            keymap.action(KeyContext::BugTable, enter),
+            Some(Action::OpenBug)
+        );
+        assert_eq!(
+            keymap.action(KeyContext::BugDescription, enter),
+            Some(Action::Reply)
+        );
+        assert_eq!(
+            keymap.action(KeyContext::BugTable, Key::new(KeyCode::Char('v'))),
+            None
+        );
+        assert_eq!(
+            keymap.action(KeyContext::BugReply, Key::ctrl('s')),
+            Some(Action::PostReply)
+        );
+        assert_eq!(Key::ctrl('s').label(true), "Ctrl-s");
+    }
+
+    #[test]
+    fn test_parse_key() {
+        assert_eq!("a".parse::<Key>().unwrap(), Key::new(KeyCode::Char('a')));
+        assert_eq!("?".parse::<Key>().unwrap(), Key::new(KeyCode::Char('?')));
+        assert_eq!("Enter".parse::<Key>().unwrap(), Key::new(KeyCode::Enter));
+        assert_eq!(
+            "pgdown".parse::<Key>().unwrap(),
+            Key::new(KeyCode::PageDown)
+        );
+        assert_eq!("F5".parse::<Key>().unwrap(), Key::new(KeyCode::F(5)));
+        assert_eq!("Ctrl-s".parse::<Key>().unwrap(), Key::ctrl('s'));
+        assert_eq!("ctrl+q".parse::<Key>().unwrap(), Key::ctrl('q'));
+        assert!("Ctrl-Enter".parse::<Key>().is_err());
+        assert!("F13".parse::<Key>().is_err());
+        assert!("foo".parse::<Key>().is_err());
+    }
+
+    #[test]
+    fn test_action_names() {
+        assert_eq!(Action::OpenBug.name(), "open_bug");
+        assert_eq!(
+            Action::from_name("ai_generate").unwrap(),
+            Action::AiGenerate
+        );
+        assert!(Action::from_name("AiGenerate").is_err());
+    }
+
+    #[test]
+    fn test_actions_needing_ai() {
+        assert!(Action::AiGenerate.needs_ai());
+        assert!(Action::RefineReply.needs_ai());
+        // Posting and editing the reply work offline
+        assert!(!Action::PostReply.needs_ai());
+        assert!(!Action::EditExternal.needs_ai());
+    }
+
+    #[test]
+    fn test_keymap_overrides() {
+        let keys = |entries: &[(&str, &[&str])]| -> BTreeMap<String, Vec<String>> {
+            entries
+                .iter()
+                .map(|(name, keys)| {
+                    (
+                        name.to_string(),
+                        keys.iter().map(|k| k.to_string()).collect(),
+                    )
+                })
+                .collect()
+        };
+
+        let keymap = KeyMap::with_overrides(&keys(&[
+            ("ai_generate", &["A", "F2"]),
+            ("quit", &["Ctrl-q"]),
+        ]))
+        .unwrap();
+        for context in [KeyContext::BugDescription, KeyContext::ReplyDescription] {
+            assert_eq!(
+                keymap.action(context, Key::new(KeyCode::F(2))),
+                Some(Action::AiGenerate)
+            );
+            assert_eq!(keymap.action(context, Key::new(KeyCode::Char('a'))), None);
+        }
+        assert_eq!(
+            keymap.action(KeyContext::BugTable, Key::ctrl('q')),
+            Some(Action::Quit)
+        );
+        assert_eq!(
+            keymap.action(KeyContext::BugTable, Key::new(KeyCode::Char('q'))),
+            None
+        );
+        // The help lists the effective bindings
+        assert!(
+            keymap
+                .help(false)
+                .iter()
+                .any(|line| line.contains("Ctrl-q"))
+        );
+        assert!(
+            keymap
+                .hint(KeyContext::BugDescription, false)
+                .contains("'A'/F2 ask AI")
+        );
+
+        let error = |entries| {
+            KeyMap::with_overrides(&keys(entries))
+                .unwrap_err()
+                .to_string()
+        };
+        assert!(error(&[("open_bugs", &["o"])]).contains("unknown variant"));
+        assert_eq!(error(&[("reply", &[])]), "no key given for reply");
+        assert_eq!(
+            error(&[("reply", &["r", "r"])]),
+            "r is given twice for reply"
+        );
+        assert_eq!(
+            error(&[("open_browser", &["e"])]),
+            "'e' is bound to both open_browser and edit_external in Bug description"
+        );
+        // A global key hides the keys of every context
+        assert_eq!(
+            error(&[("quit", &["v"])]),
+            "'v' is bound to both quit and open_browser in Bug description"
+        );
+    }
+
+    #[test]
+    fn test_vim_keys() {
+        let keymap = KeyMap::default();
+        let key = |c| Key::new(KeyCode::Char(c));
+
+        assert_eq!(
+            keymap.action(KeyContext::BugTable, key('j')),
+            Some(Action::NavDown)
+        );
+        assert_eq!(
+            keymap.action(KeyContext::BugTable, Key::ctrl('u')),
+            Some(Action::HalfPageUp)
+        );
+        // Ctrl-d is not 'D'
+        assert_eq!(
+            keymap.action(KeyContext::BugTable, Key::ctrl('d')),
+            Some(Action::HalfPageDown)
+        );
+        assert!(key('g').is_repeated());
+        assert!(!key('G').is_repeated());
+        assert_eq!(key('g').label(true), "'gg'");
+        assert!(!keymap.hint(KeyContext::BugTable, true).contains("'j'"));
+        assert!(keymap.help(true).iter().any(|l| l.contains("'gg'")));
+    }
+
+    #[test]
+    fn test_help_lists_every_binding() {
+        let keymap = KeyMap::default();
+        let help = keymap.help(true);
+
+        for binding in &keymap.bindings {
+            let line = format!(
+                "  {:<10} {}",
+                binding.key.label(true),
+                binding.action.description()
+            );
+            assert!(help.contains(&line), "missing help line: {line}");
+        }
+    }
+
+    #[test]
+    fn test_compact_hint() {
+        assert_eq!(KeyMap::default().compact_hint(true), "'?' help");
+    }
+
+    #[test]
+    fn test_hint_groups_navigation_keys() {
+        let keymap = KeyMap::default();
+
+        assert_eq!(
+            keymap.hint(KeyContext::BugTable, false),
+            "Tab selection, Up/Down/PgUp/PgDown/Home/End navigate, 'r' refresh list, 'i' my activity, 'A' assigned to me, 'D' duplicates, 'I' importance, 'f' statuses, '/' search, 'o'/'O' sort, 'x' export, 't'/'T' tags, 'c' clear tag, Enter open bug, ':' go to bug, '|' split view, 'R' reload bug"
+        );
+    }
+}
@@ -3,149 +3,496 @@
 // Import the modules we are going to create
 mod ai;
 mod app;
+mod browser;
+mod cache;
+mod clipboard;
+pub mod config;
+mod editor;
 mod events;
+mod export;
+pub mod filters;
+#[cfg(test)]
+mod fixtures;
+mod input;
 mod join_monitor;
+mod keymap;
+pub mod logging;
+mod markdown;
+mod outbox;
+mod scroll;
+mod session;
 mod ui;
 
-use anyhow::bail;
+use anyhow::{Context, bail};
+use chrono::Local;
 use crossterm::{
     ExecutableCommand,
     event::{self, Event as CrosstermEvent},
     terminal::{EnterAlternateScreen, LeaveAlternateScreen, disable_raw_mode, enable_raw_mode},
 };
-use google_ai_rs::Client;
-use launchpad_api_client::{BugTaskEntry, LaunchpadError};
+use launchpad_api_client::{
+    BugAttachment, BugMessage, BugTaskEntry, LaunchpadError, StatusFilter,
+    client::{ReqwestClient, RetryClient, TimeoutClient},
+    post_bug_comment,
+};
+use logging::LogControl;
 use ratatui::Terminal;
 use ratatui::backend::CrosstermBackend;
 use regex::Regex;
 use std::{
     io::{Write, stdout},
+    sync::Arc,
     time::Duration,
 };
 use tokio::{
-    sync::mpsc::{self, error},
+    sync::mpsc::{self, Receiver, Sender},
+    task::JoinHandle,
     time::Instant,
 };
-use tracing::{debug, error, info};
+use tracing::{debug, error, info, warn};
 use ui::draw_ui;
 
 use crate::{
+    ai::{GenerationSettings, LazyGeminiClient, OFFLINE_MESSAGE, check_api_key},
     app::App,
+    config::Config,
     events::{QuitApp, handle_key_events},
+    filters::BugFilter,
     join_monitor::{JoinHandleMonitor, check_monitor},
+    session::Session,
 };
 
-const PROJECT: &str = "nova";
-
 #[derive(Debug)]
 enum LpMessage {
     Bugs(Box<[BugTaskEntry]>),
+    BugsProgress {
+        fetched: usize,
+        total: u32,
+    },
     Bug(Box<launchpad_api_client::LaunchpadBug>),
+    Person {
+        link: String,
+        name: String,
+    },
+    BugTasks {
+        bug_id: u32,
+        tasks: Vec<BugTaskEntry>,
+    },
+    BugMessages {
+        bug_id: u32,
+        messages: Vec<BugMessage>,
+    },
+    BugAttachments {
+        bug_id: u32,
+        attachments: Vec<BugAttachment>,
+    },
+    BugActivity {
+        bug_id: u32,
+        result: Result<Vec<launchpad_api_client::BugActivity>, LaunchpadError>,
+    },
+    CommentPosted {
+        bug_id: u32,
+        result: Result<(), LaunchpadError>,
+    },
+    StatusChanged {
+        bug_id: u32,
+        task_link: String,
+        status: StatusFilter,
+        result: Result<(), LaunchpadError>,
+    },
+    BugTags {
+        bug_id: u32,
+        tags: Vec<String>,
+    },
     Error(LaunchpadError),
 }
 
+/// Messages of the chat task, a reply is streamed as chunks between
+/// `Started` and `Done`.
+#[derive(Debug)]
+enum ChatMessage {
+    Started,
+    Chunk(String),
+    Done,
+    Error(String),
+}
+
+/// Number of times the chat task is restarted before giving up.
+const MAX_CHAT_RESTARTS: u32 = 3;
+/// Time given to the chat task to end on exit, it may be streaming a reply.
+const CHAT_SHUTDOWN_TIMEOUT: Duration = Duration::from_secs(2);
+
 /// Main function of the TUI application.
-pub async fn run(terminal: &mut Terminal<CrosstermBackend<std::io::Stdout>>) -> anyhow::Result<()> {
-    dotenvy::dotenv().ok();
-    let api_key = std::env::var("GOOGLE_API_KEY")?;
+pub async fn run(
+    terminal: &mut Terminal<CrosstermBackend<std::io::Stdout>>,
+    config: Config,
+    bug_filter: Option<Box<dyn BugFilter>>,
+    log: Option<LogControl>,
+) -> anyhow::Result<()> {
+    let api_key = gemini_api_key(&config)?;
+    let offline = api_key.is_none();
 
     let (lp_sender, mut lp_receiver) = mpsc::channel::<LpMessage>(5);
-    let (app_sender, mut app_receiver) = mpsc::channel::<String>(5);
-    let (chat_sender, chat_receiver) = mpsc::channel::<String>(5);
+    let (app_sender, app_receiver) = mpsc::channel::<String>(5);
+    let (chat_sender, chat_receiver) = mpsc::channel::<ChatMessage>(5);
 
     // Create a new instance of our application
     let mut app = App::new(
-        Client::new(api_key).await?,
-        launchpad_api_client::client::ReqwestClient::new(),
+        &config,
+        LazyGeminiClient::new(api_key),
+        launchpad_client(&config),
         lp_sender,
         app_sender,
         chat_receiver,
+        Session::load(),
     );
+    app.bug_filter = bug_filter;
+    app.log = log;
+    app.offline = offline;
 
-    // Start the asynchronous task for gemini chat"
-    let client = app.gemini_client.clone();
-
-    let chat_task = tokio::spawn(async move {
-        let chat = client.generative_model("gemini-2.5-flash");
-        let mut session = chat.start_chat();
-        info!("Chat started");
-
-        while let Some(msg) = app_receiver.recv().await {
-            info!("Chat message received");
-            debug!("Message: {msg}");
-
-            match session.send_message(msg).await {
-                Ok(response) => {
-                    if let Err(e) = chat_sender.send(response.text()).await {
-                        error!("Error sending message: {e}");
-                        break;
-                    }
-                }
-                Err(e) => {
-                    error!("Error calling gemini: {e}");
-                    break;
-                }
-            }
-            tokio::time::sleep(tokio::time::Duration::from_millis(500)).await;
-        }
-
-        info!("Chat terminated");
-    });
-
-    let mut monitor = JoinHandleMonitor::new(chat_task);
+    // Start the asynchronous task for gemini chat, there is nothing to chat
+    // with offline
+    let mut monitor = if offline {
+        info!("Offline, the AI features are disabled");
+        app.status_message = Some(OFFLINE_MESSAGE.to_string());
+        None
+    } else {
+        Some(JoinHandleMonitor::new(tokio::spawn(chat_task(
+            app.gemini_client.clone(),
+            app.gemini_model.clone(),
+            app.generation,
+            app_receiver,
+            chat_sender,
+        ))))
+    };
+    let mut chat_restarts = 0;
+    let mut chat_restart_at: Option<Instant> = None;
 
-    app.get_bugs(PROJECT.to_string());
-    let project_regexp = Regex::new(r#"#(\d+).*?OpenStack Compute \(nova\):\s+"([^"]+)""#).unwrap();
+    app.get_bugs();
+    // Paint the loading state right away rather than after the first tick
+    terminal.draw(|f| draw_ui(f, &mut app))?;
+    // Let the user adapt the parsing of the bug titles to another project
+    if let Ok(re) = std::env::var("RATATAI_TITLE_REGEX") {
+        app.set_title_regex(Regex::new(&re)?);
+    }
 
-    let tick_rate = Duration::from_millis(120);
+    // Faster spinners need more frequent draws
+    let tick_rate = Duration::from_millis(120).min(app.spinner_interval);
     let mut last_tick = Instant::now();
+    // Only drawn when something changed, or each second for the clock
+    let mut dirty = true;
+    let mut drawn_second = 0;
     // Main application loop
     loop {
-        if check_monitor(&mut monitor) {
-            break;
+        // The background tasks change what is loading without a message
+        let loading = app.loading;
+        // Restart the chat task with a growing delay if it stops
+        if let Some(task) = monitor.as_mut()
+            && check_monitor(task, "Chat").is_some()
+        {
+            monitor = None;
+            dirty = true;
+            // A pending reply will never come
+            app.loading.reply = false;
+            if chat_restarts < MAX_CHAT_RESTARTS {
+                let delay = Duration::from_secs(1 << chat_restarts);
+                chat_restarts += 1;
+                chat_restart_at = Some(Instant::now() + delay);
+                app.status_message = Some(format!(
+                    "The chat stopped, restarting it in {}s",
+                    delay.as_secs()
+                ));
+            } else {
+                app.set_error(format!(
+                    "The chat stopped {MAX_CHAT_RESTARTS} times, the AI features are unavailable"
+                ));
+            }
+        }
+        app.check_ai_request();
+        if app.refresh_due() {
+            info!("Refreshing the bug list");
+            app.get_bugs();
+        }
+        // Results of a panicked task are lost, don't wait for them
+        for (name, result) in app.tasks.check() {
+            if let Err(e) = result
+                && e.is_panic()
+            {
+                app.set_error(format!("{name} task panicked, see the logs"));
+                dirty = true;
+            }
+        }
+        if chat_restart_at.is_some_and(|at| Instant::now() >= at) {
+            chat_restart_at = None;
+            dirty = true;
+            info!("Restarting chat task ({chat_restarts}/{MAX_CHAT_RESTARTS})");
+            monitor = Some(JoinHandleMonitor::new(restart_chat(&mut app)));
         }
         // Draw the user interface by passing the reference to the app object
-        terminal.draw(|f| draw_ui(f, &mut app))?;
+        let second = Local::now().timestamp();
+        if dirty || app.loading != loading || app.spinning() || second != drawn_second {
+            terminal.draw(|f| draw_ui(f, &mut app))?;
+            dirty = false;
+            drawn_second = second;
+        }
 
         // Manage message from launchpad
-        match lp_receiver.try_recv() {
-            Err(error::TryRecvError::Empty) => {}
-            Err(error::TryRecvError::Disconnected) => {}
-            Ok(msg) => match msg {
-                LpMessage::Bugs(bugs) => app.update_bugs(bugs, &project_regexp),
+        if let Ok(msg) = lp_receiver.try_recv() {
+            dirty = true;
+            match msg {
+                LpMessage::Bugs(bugs) => app.update_bugs(bugs),
+                LpMessage::BugsProgress { fetched, total } => {
+                    app.update_bugs_progress(fetched, total)
+                }
                 LpMessage::Bug(bug) => app.update_bug(*bug),
-                LpMessage::Error(e) => bail!(e),
-            },
-        };
+                LpMessage::Person { link, name } => app.update_person_name(link, name),
+                LpMessage::BugTasks { bug_id, tasks } => app.update_bug_tasks(bug_id, tasks),
+                LpMessage::BugMessages { bug_id, messages } => {
+                    app.update_bug_messages(bug_id, messages)
+                }
+                LpMessage::BugAttachments {
+                    bug_id,
+                    attachments,
+                } => app.update_bug_attachments(bug_id, attachments),
+                LpMessage::BugActivity { bug_id, result } => {
+                    app.update_bug_activity(bug_id, result)
+                }
+                LpMessage::CommentPosted { bug_id, result } => {
+                    app.update_comment_posted(bug_id, result)
+                }
+                LpMessage::StatusChanged {
+                    bug_id,
+                    task_link,
+                    status,
+                    result,
+                } => app.update_status_changed(bug_id, &task_link, status, result),
+                LpMessage::BugTags { bug_id, tags } => app.update_bug_tags(bug_id, tags),
+                LpMessage::Error(LaunchpadError::InvalidProject(project)) => {
+                    bail!("Launchpad project '{project}' does not exist")
+                }
+                // Nothing can be listed without a project, anything else may
+                // be a transient failure
+                LpMessage::Error(e) => app.set_error(format!("Launchpad error: {e}")),
+            }
+        }
 
         // Manage message from gemini chat
-        match app.chat_receiver.try_recv() {
-            Err(error::TryRecvError::Empty) => {}
-            Err(error::TryRecvError::Disconnected) => {}
-            Ok(msg) => {
-                info!("Chat response received");
-                debug!("Response: {msg}");
-                app.update_bug_reply(msg);
-            }
-        };
+        // Drain the chunks received since the last tick
+        while let Ok(msg) = app.chat_receiver.try_recv() {
+            dirty = true;
+            debug!("Chat message: {msg:?}");
+            app.update_bug_reply(msg);
+        }
 
         // Handle input events
         let timeout = tick_rate.saturating_sub(last_tick.elapsed());
         if event::poll(timeout)? {
-            if let CrosstermEvent::Key(key) = event::read()? {
-                let exit = handle_key_events(key, &mut app, terminal).await?;
-                if exit == QuitApp::Yes {
-                    break;
+            dirty = true;
+            match event::read()? {
+                CrosstermEvent::Key(key) => {
+                    let exit = handle_key_events(key, &mut app, terminal).await?;
+                    if exit == QuitApp::Yes {
+                        break;
+                    }
                 }
+                // Redrawn right away on the next iteration
+                CrosstermEvent::Resize(width, height) => {
+                    debug!("Terminal resized to {width}x{height}");
+                    app.handle_resize();
+                    terminal.autoresize()?;
+                }
+                _ => {}
             }
         }
         if last_tick.elapsed() >= tick_rate {
             last_tick = Instant::now();
         }
     }
+    if let Some(monitor) = monitor {
+        shutdown_chat(&mut app, monitor).await;
+    }
     Ok(())
 }
 
+/// Closes the channel of the chat task so it ends, aborting it if it does
+/// not in time.
+async fn shutdown_chat(app: &mut App, monitor: JoinHandleMonitor<()>) {
+    let (closed_sender, _) = mpsc::channel(1);
+    drop(std::mem::replace(&mut app.app_sender, closed_sender));
+    let mut handle = monitor.into_handle();
+    match tokio::time::timeout(CHAT_SHUTDOWN_TIMEOUT, &mut handle).await {
+        Ok(Ok(())) => info!("Chat task shut down"),
+        Ok(Err(e)) => error!("💥 Chat task panicked while shutting down: {e}"),
+        Err(_) => {
+            warn!(
+                "Chat task still running after {}s, aborting it",
+                CHAT_SHUTDOWN_TIMEOUT.as_secs()
+            );
+            handle.abort();
+        }
+    }
+}
+
+/// Chat with gemini, forwarding the messages of the app and streaming back
+/// the replies.
+async fn chat_task(
+    client: Arc<LazyGeminiClient>,
+    gemini_model: String,
+    generation: GenerationSettings,
+    mut app_receiver: Receiver<String>,
+    chat_sender: Sender<ChatMessage>,
+) {
+    // Connect to gemini only once the first message is sent
+    let (client, first_msg) = loop {
+        let Some(msg) = app_receiver.recv().await else {
+            info!("Chat terminated, the application is closing");
+            return;
+        };
+        match client.get().await {
+            Ok(client) => break (client, msg),
+            Err(e) => {
+                error!("Error connecting to gemini: {e}");
+                let msg = format!("Error while connecting to gemini: {e}");
+                if chat_sender.send(ChatMessage::Error(msg)).await.is_err() {
+                    return;
+                }
+            }
+        }
+    };
+    let chat = generation.apply(client.generative_model(&gemini_model));
+    let mut session = chat.start_chat();
+    info!("Chat started");
+
+    let mut first_msg = Some(first_msg);
+    loop {
+        let msg = match first_msg.take() {
+            Some(msg) => msg,
+            None => {
+                tokio::time::sleep(tokio::time::Duration::from_millis(500)).await;
+                let Some(msg) = app_receiver.recv().await else {
+                    info!("Chat terminated, the application is closing");
+                    return;
+                };
+                msg
+            }
+        };
+        info!("Chat message received");
+        debug!("Message: {msg}");
+
+        let mut stream = match session.stream_send_message(msg).await {
+            Ok(stream) => stream,
+            // Likely a rejected API key, shown while the chat waits for the
+            // next message
+            Err(e) => {
+                error!("Error calling gemini: {e}");
+                let error = ChatMessage::Error(format!("Error calling gemini: {e}"));
+                if chat_sender.send(error).await.is_err() {
+                    info!("Chat terminated");
+                    return;
+                }
+                continue;
+            }
+        };
+        let mut next_chunk = Some(ChatMessage::Started);
+        while let Some(chunk) = next_chunk {
+            next_chunk = match chunk {
+                ChatMessage::Started | ChatMessage::Chunk(_) => match stream.next().await {
+                    Ok(Some(response)) => Some(ChatMessage::Chunk(response.text())),
+                    Ok(None) => Some(ChatMessage::Done),
+                    Err(e) => {
+                        error!("Error streaming gemini response: {e}");
+                        Some(ChatMessage::Error(format!("Error calling gemini: {e}")))
+                    }
+                },
+                ChatMessage::Done | ChatMessage::Error(_) => None,
+            };
+            if let Err(e) = chat_sender.send(chunk).await {
+                error!("Error sending message: {e}");
+                info!("Chat terminated");
+                return;
+            }
+        }
+    }
+}
+
+/// Returns the `GOOGLE_API_KEY` of the environment or the `.env` file, none
+/// offline. Fails if the key cannot be a Google API key, rather than on the
+/// first AI request.
+pub fn gemini_api_key(config: &Config) -> anyhow::Result<Option<String>> {
+    dotenvy::dotenv().ok();
+    let Some(api_key) = std::env::var("GOOGLE_API_KEY")
+        .ok()
+        .filter(|_| !config.offline())
+    else {
+        return Ok(None);
+    };
+    check_api_key(&api_key).context("Invalid GOOGLE_API_KEY, fix it or start with --offline")?;
+    Ok(Some(api_key))
+}
+
+/// Spawns a new chat task, replacing the channels of the app.
+fn restart_chat(app: &mut App) -> JoinHandle<()> {
+    let (app_sender, app_receiver) = mpsc::channel::<String>(5);
+    let (chat_sender, chat_receiver) = mpsc::channel::<ChatMessage>(5);
+    app.app_sender = app_sender;
+    app.chat_receiver = chat_receiver;
+    tokio::spawn(chat_task(
+        app.gemini_client.clone(),
+        app.gemini_model.clone(),
+        app.generation,
+        app_receiver,
+        chat_sender,
+    ))
+}
+
+/// Returns a Launchpad client authenticated with the `LAUNCHPAD_CONSUMER_KEY`,
+/// `LAUNCHPAD_TOKEN` and `LAUNCHPAD_TOKEN_SECRET` variables, or an anonymous one.
+fn launchpad_client(config: &Config) -> ReqwestClient {
+    let var = |name| std::env::var(name).ok();
+    let client = match (
+        var("LAUNCHPAD_CONSUMER_KEY"),
+        var("LAUNCHPAD_TOKEN"),
+        var("LAUNCHPAD_TOKEN_SECRET"),
+    ) {
+        (Some(consumer_key), Some(token), Some(secret)) => {
+            ReqwestClient::with_oauth(&consumer_key, &token, &secret)
+        }
+        _ => ReqwestClient::new(),
+    };
+    match &config.launchpad_url {
+        Some(url) => client.with_base_url(url),
+        None => client,
+    }
+}
+
+/// Posts the replies waiting in the outbox, then moves them to its `sent` directory.
+/// Fails if some replies could not be posted.
+pub async fn send_outbox(config: &Config) -> anyhow::Result<()> {
+    let Some(dir) = outbox::outbox_dir() else {
+        bail!("Unable to find the outbox directory");
+    };
+    dotenvy::dotenv().ok();
+    let client = RetryClient::new(TimeoutClient::new(launchpad_client(config)));
+    let mut failures = 0;
+    for (path, entry) in outbox::read_entries(&dir)? {
+        match post_bug_comment(&client, entry.bug_id, &entry.reply).await {
+            Ok(()) => {
+                outbox::mark_sent(&path)?;
+                println!("Posted reply on bug #{} ({})", entry.bug_id, entry.title);
+            }
+            Err(e) => {
+                eprintln!("Fail to post reply on bug #{}: {e}", entry.bug_id);
+                failures += 1;
+            }
+        }
+    }
+    match failures {
+        0 => Ok(()),
+        1 => bail!("1 reply was not posted and is left in the outbox"),
+        n => bail!("{n} replies were not posted and are left in the outbox"),
+    }
+}
+
 pub fn exit_gui(
     mut terminal: Terminal<CrosstermBackend<std::io::Stdout>>,
 ) -> Result<(), anyhow::Error> {
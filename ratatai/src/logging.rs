@@ -0,0 +1,92 @@
+// src/logging.rs
+
+//! Where the logs are written and how verbose they are, changed while
+//! running to capture the debug logs of a problem.
+
+use chrono::Utc;
+use std::path::PathBuf;
+use tracing::level_filters::LevelFilter;
+use tracing_subscriber::{EnvFilter, Registry, reload};
+
+/// Levels cycled through, from the least to the most verbose.
+const LEVELS: [LevelFilter; 5] = [
+    LevelFilter::ERROR,
+    LevelFilter::WARN,
+    LevelFilter::INFO,
+    LevelFilter::DEBUG,
+    LevelFilter::TRACE,
+];
+
+/// Handle on the filter of the logging subscriber, created in `main`.
+#[derive(Debug)]
+pub struct LogControl {
+    path: PathBuf,
+    level: LevelFilter,
+    handle: reload::Handle<EnvFilter, Registry>,
+}
+
+impl LogControl {
+    /// `path` is the log file, `level` the most verbose level `RUST_LOG`
+    /// let through.
+    pub fn new(
+        path: PathBuf,
+        level: LevelFilter,
+        handle: reload::Handle<EnvFilter, Registry>,
+    ) -> Self {
+        Self {
+            path,
+            level,
+            handle,
+        }
+    }
+
+    /// The file written today, the daily rolling appender suffixes the path
+    /// with the UTC date.
+    pub(crate) fn current_file(&self) -> PathBuf {
+        let mut file = self.path.clone().into_os_string();
+        file.push(Utc::now().format(".%Y-%m-%d").to_string());
+        file.into()
+    }
+
+    pub(crate) fn level(&self) -> LevelFilter {
+        self.level
+    }
+
+    /// Switches to the next more verbose level, back to errors after traces.
+    /// The per target directives of `RUST_LOG` are replaced.
+    pub(crate) fn cycle_level(&mut self) -> anyhow::Result<LevelFilter> {
+        let next = LEVELS
+            .iter()
+            .position(|level| *level == self.level)
+            .map_or(LevelFilter::INFO, |i| LEVELS[(i + 1) % LEVELS.len()]);
+        self.handle.reload(EnvFilter::new(next.to_string()))?;
+        self.level = next;
+        Ok(next)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_cycle_level() {
+        let (layer, handle) = reload::Layer::<_, Registry>::new(EnvFilter::new("info"));
+        let mut log = LogControl::new(PathBuf::from("logs/ratatai.log"), LevelFilter::INFO, handle);
+        assert!(
+            log.current_file()
+                .to_string_lossy()
+                .starts_with("logs/ratatai.log.20")
+        );
+
+        assert_eq!(log.cycle_level().unwrap(), LevelFilter::DEBUG);
+        assert_eq!(log.cycle_level().unwrap(), LevelFilter::TRACE);
+        assert_eq!(log.cycle_level().unwrap(), LevelFilter::ERROR);
+        assert_eq!(log.level(), LevelFilter::ERROR);
+
+        // The subscriber is gone, the level is kept
+        drop(layer);
+        assert!(log.cycle_level().is_err());
+        assert_eq!(log.level(), LevelFilter::ERROR);
+    }
+}
@@ -1,17 +1,98 @@
 // src/main.rs
 
 use anyhow::bail;
+use clap::{Parser, Subcommand};
+use std::path::{self, PathBuf};
 // Import everything public from our 'tui_app' crate (which will be defined in lib.rs)
-use ratatai::{exit_gui, run, start_gui};
-use tracing_subscriber::{EnvFilter, fmt, prelude::*};
+use ratatai::{
+    config::{Config, ThemePreset},
+    exit_gui, gemini_api_key,
+    logging::LogControl,
+    run, send_outbox, start_gui,
+};
+use tracing::level_filters::LevelFilter;
+use tracing_subscriber::{EnvFilter, fmt, prelude::*, reload};
+
+/// Triage Launchpad bugs with the help of an AI.
+#[derive(Debug, Parser)]
+#[command(version, about)]
+struct Cli {
+    /// Launchpad project whose bugs are triaged [default: nova]
+    #[arg(short, long, value_parser = parse_project)]
+    project: Option<String>,
+
+    /// Gemini model used to answer the AI requests [default: gemini-2.5-flash]
+    #[arg(short, long)]
+    model: Option<String>,
+
+    /// File holding the prompt used to craft replies, instead of the nova one
+    #[arg(long)]
+    prompt_file: Option<PathBuf>,
+
+    /// Refresh the bug list every given number of seconds
+    #[arg(long, value_name = "SECONDS")]
+    refresh_interval: Option<u64>,
+
+    /// Color theme, the colors of the configuration still apply [default: dark]
+    #[arg(long, value_enum)]
+    theme: Option<ThemePreset>,
+
+    /// Browse the bugs without the AI features, implied without GOOGLE_API_KEY
+    #[arg(long)]
+    offline: bool,
+
+    /// Configuration file [default: ~/.config/ratatai/config.toml]
+    #[arg(short, long)]
+    config: Option<PathBuf>,
+
+    #[command(subcommand)]
+    command: Option<Command>,
+}
+
+#[derive(Debug, Subcommand)]
+enum Command {
+    /// Post the replies saved in the outbox
+    SendOutbox,
+}
+
+fn parse_project(project: &str) -> Result<String, String> {
+    let project = project.trim();
+    if project.is_empty() {
+        return Err("the project name cannot be empty".to_string());
+    }
+    Ok(project.to_string())
+}
 
 #[tokio::main]
 async fn main() -> anyhow::Result<()> {
+    let cli = Cli::parse();
+    // The command line takes precedence over the file, which takes precedence
+    // over the environment
+    let mut config = Config::load(cli.config.as_deref())?.with_env();
+    config.project = cli.project.or(config.project);
+    config.gemini_model = cli.model.or(config.gemini_model);
+    config.prompt_file = cli.prompt_file.or(config.prompt_file);
+    config.refresh_interval = cli.refresh_interval.or(config.refresh_interval);
+    config.theme.preset = cli.theme.or(config.theme.preset);
+    if cli.offline {
+        config.offline = Some(true);
+    }
+    if let Some(Command::SendOutbox) = cli.command {
+        return send_outbox(&config).await;
+    }
+    // Checked before taking over the terminal so the error is readable
+    gemini_api_key(&config)?;
+
     // Setup logging
-    let file_appender = tracing_appender::rolling::daily("logs", "ratatai.log");
+    let log_dir = path::absolute("logs")?;
+    let file_appender = tracing_appender::rolling::daily(&log_dir, "ratatai.log");
     let (non_blocking_appender, _guard) = tracing_appender::non_blocking(file_appender);
 
     let filter = EnvFilter::try_from_default_env().unwrap_or_else(|_| EnvFilter::new("info"));
+    let level = filter.max_level_hint().unwrap_or(LevelFilter::INFO);
+    // The filter is reloaded to change the level from the interface
+    let (filter, reload_handle) = reload::Layer::new(filter);
+    let log = LogControl::new(log_dir.join("ratatai.log"), level, reload_handle);
 
     tracing_subscriber::registry()
         .with(filter)
@@ -27,7 +108,7 @@ async fn main() -> anyhow::Result<()> {
     // Initialize Crossterm and Ratatui terminal
     let mut terminal = start_gui()?;
     // Call the main function of our application defined in lib.rs
-    match run(&mut terminal).await {
+    match run(&mut terminal, config, None, Some(log)).await {
         Ok(_) => {
             exit_gui(terminal)?;
         }
@@ -0,0 +1,360 @@
+// src/markdown.rs
+
+//! Rendering of markdown texts, like the bug descriptions and the AI
+//! answers, as styled and wrapped lines.
+
+use pulldown_cmark::{Event, HeadingLevel, Options, Parser, Tag, TagEnd};
+use ratatui::{
+    style::{Modifier, Style},
+    text::{Line, Span},
+};
+use textwrap::core::display_width;
+
+use crate::config::Theme;
+
+/// Renders `text` as lines no wider than `width`, in the colors of `theme`.
+/// Line breaks are kept as bug reports are rarely written with markdown
+/// paragraphs in mind.
+pub(crate) fn render_markdown(text: &str, width: usize, theme: &Theme) -> Vec<Line<'static>> {
+    let mut renderer = Renderer::new(width.max(1), *theme);
+    for event in Parser::new_ext(text, Options::ENABLE_STRIKETHROUGH) {
+        renderer.event(event);
+    }
+    renderer.finish()
+}
+
+struct Renderer {
+    width: usize,
+    theme: Theme,
+    lines: Vec<Line<'static>>,
+    current: Vec<Span<'static>>,
+    styles: Vec<Style>,
+    /// Numbering of the nested lists, `None` for bullet lists
+    lists: Vec<Option<u64>>,
+    quote_depth: usize,
+    in_code_block: bool,
+}
+
+impl Renderer {
+    fn new(width: usize, theme: Theme) -> Self {
+        Self {
+            width,
+            theme,
+            lines: Vec::new(),
+            current: Vec::new(),
+            styles: vec![Style::default()],
+            lists: Vec::new(),
+            quote_depth: 0,
+            in_code_block: false,
+        }
+    }
+
+    fn code_style(&self) -> Style {
+        Style::default().fg(self.theme.warning).bg(self.theme.muted)
+    }
+
+    fn style(&self) -> Style {
+        self.styles.last().copied().unwrap_or_default()
+    }
+
+    fn push_style(&mut self, patch: Style) {
+        self.styles.push(self.style().patch(patch));
+    }
+
+    fn event(&mut self, event: Event) {
+        match event {
+            Event::Start(tag) => self.start(tag),
+            Event::End(tag) => self.end(tag),
+            Event::Text(text) if self.in_code_block => self.code_block(&text),
+            Event::Text(text) | Event::Html(text) | Event::InlineHtml(text) => {
+                // Html blocks come with their line breaks, like tracebacks
+                // quoting `<module>`
+                let mut lines = text.split('\n').peekable();
+                while let Some(line) = lines.next() {
+                    self.text(line, self.style());
+                    if lines.peek().is_some() {
+                        self.flush();
+                    }
+                }
+            }
+            Event::Code(code) => self.text(&code, self.style().patch(self.code_style())),
+            Event::SoftBreak | Event::HardBreak => self.flush(),
+            Event::Rule => {
+                self.flush();
+                self.lines.push(Line::from("─".repeat(self.width)));
+            }
+            Event::TaskListMarker(done) => {
+                self.text(if done { "[x] " } else { "[ ] " }, self.style())
+            }
+            Event::FootnoteReference(_) | Event::InlineMath(_) | Event::DisplayMath(_) => {}
+        }
+    }
+
+    fn start(&mut self, tag: Tag) {
+        match tag {
+            Tag::Heading { level, .. } => {
+                self.blank_line();
+                let style = Style::default().add_modifier(Modifier::BOLD);
+                self.push_style(match level {
+                    HeadingLevel::H1 => style
+                        .fg(self.theme.accent)
+                        .add_modifier(Modifier::UNDERLINED),
+                    HeadingLevel::H2 => style.fg(self.theme.accent),
+                    _ => style,
+                });
+            }
+            Tag::Emphasis => self.push_style(Style::default().add_modifier(Modifier::ITALIC)),
+            Tag::Strong => self.push_style(Style::default().add_modifier(Modifier::BOLD)),
+            Tag::Strikethrough => {
+                self.push_style(Style::default().add_modifier(Modifier::CROSSED_OUT))
+            }
+            Tag::Link { .. } => self.push_style(
+                Style::default()
+                    .fg(self.theme.secondary)
+                    .add_modifier(Modifier::UNDERLINED),
+            ),
+            Tag::CodeBlock(_) => {
+                self.flush();
+                self.in_code_block = true;
+            }
+            Tag::BlockQuote(_) => {
+                self.flush();
+                self.quote_depth += 1;
+                self.push_style(Style::default().fg(self.theme.muted));
+            }
+            Tag::List(first) => {
+                self.flush();
+                self.lists.push(first);
+            }
+            Tag::Item => {
+                self.flush();
+                let marker = match self.lists.last_mut() {
+                    Some(Some(number)) => {
+                        *number += 1;
+                        format!("{}. ", *number - 1)
+                    }
+                    _ => "- ".to_string(),
+                };
+                let indent = "  ".repeat(self.lists.len().saturating_sub(1));
+                let prefix = format!("{}{indent}{marker}", "> ".repeat(self.quote_depth));
+                self.current.push(Span::raw(prefix));
+            }
+            _ => {}
+        }
+    }
+
+    fn end(&mut self, tag: TagEnd) {
+        match tag {
+            TagEnd::Heading(_) | TagEnd::BlockQuote(_) => {
+                self.styles.pop();
+                self.flush();
+                if let TagEnd::BlockQuote(_) = tag {
+                    self.quote_depth -= 1;
+                }
+                self.blank_line();
+            }
+            TagEnd::Emphasis | TagEnd::Strong | TagEnd::Strikethrough | TagEnd::Link => {
+                self.styles.pop();
+            }
+            TagEnd::CodeBlock => {
+                self.in_code_block = false;
+                self.blank_line();
+            }
+            TagEnd::Paragraph | TagEnd::HtmlBlock => {
+                self.flush();
+                self.blank_line();
+            }
+            TagEnd::List(_) => {
+                self.flush();
+                self.lists.pop();
+                if self.lists.is_empty() {
+                    self.blank_line();
+                }
+            }
+            TagEnd::Item => self.flush(),
+            _ => {}
+        }
+    }
+
+    /// Prefix of the lines continuing a list item or a quote.
+    fn indent(&self) -> String {
+        format!(
+            "{}{}",
+            "> ".repeat(self.quote_depth),
+            "  ".repeat(self.lists.len())
+        )
+    }
+
+    fn current_width(&self) -> usize {
+        self.current.iter().map(|span| span.width()).sum()
+    }
+
+    /// Appends text to the current line, wrapping it at the spaces.
+    fn text(&mut self, text: &str, style: Style) {
+        for word in text.split_inclusive(' ') {
+            if self.current.is_empty() {
+                let indent = self.indent();
+                if !indent.is_empty() {
+                    self.current.push(Span::raw(indent));
+                }
+            }
+            let used = self.current_width();
+            let word_width = display_width(word.trim_end());
+            if used + word_width > self.width && used > display_width(&self.indent()) {
+                self.flush();
+                self.text(word.trim_start(), style);
+                continue;
+            }
+            if display_width(word) > self.width {
+                // Words wider than the panel are split anywhere
+                let split = word
+                    .char_indices()
+                    .nth(self.width.saturating_sub(used).max(1))
+                    .map_or(word.len(), |(i, _)| i);
+                self.current
+                    .push(Span::styled(word[..split].to_string(), style));
+                if split < word.len() {
+                    self.flush();
+                    self.text(&word[split..], style);
+                }
+                continue;
+            }
+            self.current.push(Span::styled(word.to_string(), style));
+        }
+    }
+
+    fn code_block(&mut self, code: &str) {
+        let indent = self.indent();
+        for line in code.lines() {
+            let line = line.replace('\t', "    ");
+            let chars: Vec<char> = line.chars().collect();
+            let available = self.width.saturating_sub(indent.len()).max(1);
+            for chunk in chars
+                .chunks(available)
+                .chain(chars.is_empty().then_some(&[][..]))
+            {
+                let text: String = chunk.iter().collect();
+                let padding = " ".repeat(available.saturating_sub(display_width(&text)));
+                self.lines.push(Line::from(vec![
+                    Span::raw(indent.clone()),
+                    Span::styled(format!("{text}{padding}"), self.code_style()),
+                ]));
+            }
+        }
+    }
+
+    fn flush(&mut self) {
+        if !self.current.is_empty() {
+            self.lines
+                .push(Line::from(std::mem::take(&mut self.current)));
+        }
+    }
+
+    /// Separates two blocks with an empty line, never doubling it.
+    fn blank_line(&mut self) {
+        self.flush();
+        if self.lines.last().is_some_and(|line| line.width() > 0) {
+            self.lines.push(Line::default());
+        }
+    }
+
+    fn finish(mut self) -> Vec<Line<'static>> {
+        self.flush();
+        if self.lines.last().is_some_and(|line| line.width() == 0) {
+            self.lines.pop();
+        }
+        self.lines
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::ThemePreset;
+    use ratatui::style::Color;
+
+    fn texts(lines: &[Line]) -> Vec<String> {
+        lines.iter().map(|line| line.to_string()).collect()
+    }
+
+    #[test]
+    fn test_render_markdown() {
+        let theme = Theme::default();
+        let lines = render_markdown(
+            "# Title\n\nSome **bold** and `code`\nnext line\n\n```\nfn main() {}\n```\n\n- one\n- two",
+            20,
+            &theme,
+        );
+        assert_eq!(
+            texts(&lines),
+            [
+                "Title",
+                "",
+                "Some bold and code",
+                "next line",
+                "",
+                "fn main() {}        ",
+                "",
+                "- one",
+                "- two",
+            ]
+        );
+        assert!(
+            lines[0].spans[0]
+                .style
+                .add_modifier
+                .contains(Modifier::BOLD)
+        );
+        let bold = lines[2].spans.iter().find(|s| s.content == "bold").unwrap();
+        assert!(bold.style.add_modifier.contains(Modifier::BOLD));
+        let code = lines[2].spans.iter().find(|s| s.content == "code").unwrap();
+        assert_eq!(code.style.bg, Some(Color::DarkGray));
+        assert_eq!(
+            lines[5].spans[1].style,
+            Style::default().fg(Color::Yellow).bg(Color::DarkGray)
+        );
+    }
+
+    #[test]
+    fn test_render_markdown_theme() {
+        let theme = Theme::preset(ThemePreset::Light);
+        let lines = render_markdown(
+            "## Logs\n\n> quoted\n\nSee [the docs](https://docs.openstack.org) and `nova`",
+            80,
+            &theme,
+        );
+        let span = |text: &str| {
+            lines
+                .iter()
+                .flat_map(|line| &line.spans)
+                .find(|span| span.content.contains(text))
+                .unwrap()
+                .style
+        };
+        assert_eq!(span("Logs").fg, Some(theme.accent));
+        assert_eq!(span("quoted").fg, Some(theme.muted));
+        assert_eq!(span("docs").fg, Some(theme.secondary));
+        assert_eq!(span("nova").bg, Some(theme.muted));
+    }
+
+    #[test]
+    fn test_render_markdown_wraps() {
+        let lines = render_markdown(
+            "> a quoted text to wrap\n\nTraceback <module>",
+            12,
+            &Theme::default(),
+        );
+        assert_eq!(
+            texts(&lines),
+            [
+                "> a quoted ",
+                "> text to ",
+                "> wrap",
+                "",
+                "Traceback ",
+                "<module>"
+            ]
+        );
+        assert!(lines.iter().all(|line| line.width() <= 12));
+    }
+}
@@ -0,0 +1,127 @@
+// src/outbox.rs
+
+//! Replies waiting for a review before being posted to Launchpad.
+
+use chrono::{DateTime, Utc};
+use launchpad_api_client::LaunchpadBug;
+use serde::{Deserialize, Serialize};
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+/// Returns the outbox directory, overridden by the `RATATAI_OUTBOX` variable.
+pub(crate) fn outbox_dir() -> Option<PathBuf> {
+    std::env::var_os("RATATAI_OUTBOX")
+        .map(PathBuf::from)
+        .or_else(|| dirs::data_dir().map(|dir| dir.join("ratatai").join("outbox")))
+}
+
+/// A reply saved in the outbox, with what a reviewer needs to check it.
+#[derive(Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub(crate) struct OutboxEntry {
+    pub bug_id: u32,
+    pub title: String,
+    pub web_link: String,
+    pub reply: String,
+    pub created: DateTime<Utc>,
+}
+
+impl OutboxEntry {
+    pub(crate) fn new(bug: &LaunchpadBug, reply: &str) -> Self {
+        Self {
+            bug_id: bug.id,
+            title: bug.title.clone(),
+            web_link: bug.web_link.clone(),
+            reply: reply.to_string(),
+            created: Utc::now(),
+        }
+    }
+
+    /// Writes the entry as a json file in `dir`, one file per bug and date.
+    pub(crate) fn write(&self, dir: &Path) -> anyhow::Result<PathBuf> {
+        fs::create_dir_all(dir)?;
+        let path = dir.join(format!(
+            "{}-{}.json",
+            self.bug_id,
+            self.created.format("%Y%m%dT%H%M%S")
+        ));
+        fs::write(&path, serde_json::to_string_pretty(self)?)?;
+        Ok(path)
+    }
+}
+
+/// Reads the entries waiting in `dir`, oldest first.
+pub(crate) fn read_entries(dir: &Path) -> anyhow::Result<Vec<(PathBuf, OutboxEntry)>> {
+    if !dir.exists() {
+        return Ok(Vec::new());
+    }
+    let mut entries = Vec::new();
+    for file in fs::read_dir(dir)? {
+        let path = file?.path();
+        if path.extension().is_some_and(|ext| ext == "json") {
+            let entry = serde_json::from_str(&fs::read_to_string(&path)?)?;
+            entries.push((path, entry));
+        }
+    }
+    entries.sort_by_key(|(_, entry): &(PathBuf, OutboxEntry)| entry.created);
+    Ok(entries)
+}
+
+/// Moves a posted entry to the `sent` sub directory so it is not posted twice.
+pub(crate) fn mark_sent(path: &Path) -> anyhow::Result<PathBuf> {
+    let Some(dir) = path.parent() else {
+        anyhow::bail!("{} has no parent directory", path.display());
+    };
+    let sent = dir.join("sent");
+    fs::create_dir_all(&sent)?;
+    let target = sent.join(path.file_name().unwrap_or_default());
+    fs::rename(path, &target)?;
+    Ok(target)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fixtures::bug;
+
+    #[test]
+    fn test_write_entry() {
+        let dir = tempfile::tempdir().unwrap();
+        let entry = OutboxEntry::new(&bug(42, "crash on boot"), "Thanks for the report.");
+
+        let path = entry.write(&dir.path().join("outbox")).unwrap();
+
+        assert!(
+            path.file_name()
+                .unwrap()
+                .to_str()
+                .unwrap()
+                .starts_with("42-")
+        );
+        let written: OutboxEntry =
+            serde_json::from_str(&fs::read_to_string(path).unwrap()).unwrap();
+        assert_eq!(written, entry);
+        assert_eq!(written.web_link, "https://bugs.launchpad.net/bugs/42");
+    }
+
+    #[test]
+    fn test_read_and_mark_sent() {
+        let dir = tempfile::tempdir().unwrap();
+        assert!(
+            read_entries(&dir.path().join("missing"))
+                .unwrap()
+                .is_empty()
+        );
+        let entry = OutboxEntry::new(&bug(42, "crash on boot"), "Thanks for the report.");
+        entry.write(dir.path()).unwrap();
+
+        let entries = read_entries(dir.path()).unwrap();
+        assert_eq!(entries.len(), 1);
+        assert_eq!(entries[0].1, entry);
+
+        let sent = mark_sent(&entries[0].0).unwrap();
+        assert!(sent.exists());
+        assert!(read_entries(dir.path()).unwrap().is_empty());
+    }
+}
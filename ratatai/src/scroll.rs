@@ -0,0 +1,42 @@
+// src/scroll.rs
+
+//! Scroll offsets computations shared by the scrollable panels.
+
+/// Returns the offset showing the last line of the content at the bottom of
+/// the viewport, 0 if the content fits in it.
+pub(crate) fn end_scroll(content_len: usize, viewport: usize) -> u16 {
+    content_len
+        .saturating_sub(viewport)
+        .try_into()
+        .unwrap_or(u16::MAX)
+}
+
+/// Keeps a scroll offset from going past the end of the content.
+pub(crate) fn clamp_scroll(current: u16, content_len: usize, viewport: usize) -> u16 {
+    current.min(end_scroll(content_len, viewport))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_content_shorter_than_viewport() {
+        assert_eq!(end_scroll(5, 10), 0);
+        assert_eq!(clamp_scroll(3, 5, 10), 0);
+    }
+
+    #[test]
+    fn test_content_equal_to_viewport() {
+        assert_eq!(end_scroll(10, 10), 0);
+        assert_eq!(clamp_scroll(1, 10, 10), 0);
+    }
+
+    #[test]
+    fn test_content_longer_than_viewport() {
+        assert_eq!(end_scroll(1000, 10), 990);
+        assert_eq!(clamp_scroll(42, 1000, 10), 42);
+        assert_eq!(clamp_scroll(u16::MAX, 1000, 10), 990);
+        assert_eq!(end_scroll(usize::MAX, 10), u16::MAX);
+    }
+}
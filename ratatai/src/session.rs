@@ -0,0 +1,157 @@
+// src/session.rs
+
+use serde::{Deserialize, Serialize};
+use std::{collections::HashMap, fs, path::PathBuf};
+use tracing::warn;
+
+const SESSION_FILE: &str = "session.json";
+const DRAFTS_DIR: &str = "drafts";
+
+/// Returns the directory where ratatai keeps its persistent state.
+pub(crate) fn cache_dir() -> Option<PathBuf> {
+    dirs::cache_dir().map(|dir| dir.join("ratatai"))
+}
+
+/// State persisted between two runs of the application.
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub(crate) struct Session {
+    /// Last `message_count` seen for each bug id.
+    #[serde(default)]
+    pub seen_message_counts: HashMap<u32, u32>,
+    /// Statuses of the listed bugs, as displayed ("Fix Released").
+    #[serde(default)]
+    pub statuses: Vec<String>,
+    #[serde(skip)]
+    path: Option<PathBuf>,
+    /// Where the reply drafts are kept, next to the session file
+    #[serde(skip)]
+    drafts_dir: Option<PathBuf>,
+}
+
+impl Session {
+    /// Loads the session from the cache directory, starting fresh if it is missing or unreadable.
+    pub(crate) fn load() -> Self {
+        match cache_dir() {
+            Some(dir) => Self::load_from(dir.join(SESSION_FILE)),
+            None => {
+                warn!("No cache directory available, session will not be persisted");
+                Self::default()
+            }
+        }
+    }
+
+    pub(crate) fn load_from(path: PathBuf) -> Self {
+        let mut session = match fs::read_to_string(&path) {
+            Ok(content) => serde_json::from_str(&content).unwrap_or_else(|e| {
+                warn!("Ignoring invalid session file {}: {e}", path.display());
+                Self::default()
+            }),
+            Err(_) => Self::default(),
+        };
+        session.drafts_dir = path.parent().map(|dir| dir.join(DRAFTS_DIR));
+        session.path = Some(path);
+        session
+    }
+
+    pub(crate) fn save(&self) -> anyhow::Result<()> {
+        if let Some(path) = &self.path {
+            if let Some(parent) = path.parent() {
+                fs::create_dir_all(parent)?;
+            }
+            fs::write(path, serde_json::to_string_pretty(self)?)?;
+        }
+        Ok(())
+    }
+
+    fn draft_path(&self, bug_id: u32) -> Option<PathBuf> {
+        self.drafts_dir
+            .as_ref()
+            .map(|dir| dir.join(format!("{bug_id}.txt")))
+    }
+
+    /// Saves the reply draft of a bug so it survives a crash or an accidental quit.
+    pub(crate) fn save_draft(&self, bug_id: u32, content: &str) -> anyhow::Result<()> {
+        if let Some(path) = self.draft_path(bug_id) {
+            if let Some(parent) = path.parent() {
+                fs::create_dir_all(parent)?;
+            }
+            fs::write(path, content)?;
+        }
+        Ok(())
+    }
+
+    /// Removes the reply draft of a bug once the reply is posted.
+    pub(crate) fn remove_draft(&self, bug_id: u32) -> anyhow::Result<()> {
+        if let Some(path) = self.draft_path(bug_id)
+            && path.exists()
+        {
+            fs::remove_file(path)?;
+        }
+        Ok(())
+    }
+
+    /// Returns the reply draft saved for a bug, if any.
+    pub(crate) fn load_draft(&self, bug_id: u32) -> Option<String> {
+        self.draft_path(bug_id)
+            .and_then(|path| fs::read_to_string(path).ok())
+            .filter(|content| !content.trim().is_empty())
+    }
+
+    /// Stores the current message count of a bug and returns how many comments
+    /// arrived since it was last viewed, or `None` if it was never viewed before.
+    pub(crate) fn record_message_count(&mut self, bug_id: u32, message_count: u32) -> Option<u32> {
+        self.seen_message_counts
+            .insert(bug_id, message_count)
+            .map(|seen| message_count.saturating_sub(seen))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_record_message_count() {
+        let mut session = Session::default();
+
+        assert_eq!(session.record_message_count(42, 3), None);
+        assert_eq!(session.record_message_count(42, 3), Some(0));
+        assert_eq!(session.record_message_count(42, 5), Some(2));
+        assert_eq!(session.seen_message_counts[&42], 5);
+    }
+
+    #[test]
+    fn test_session_round_trip() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join(SESSION_FILE);
+
+        let mut session = Session::load_from(path.clone());
+        session.record_message_count(42, 3);
+        session.save().unwrap();
+
+        let session = Session::load_from(path);
+        assert_eq!(session.seen_message_counts[&42], 3);
+    }
+
+    #[test]
+    fn test_drafts() {
+        let dir = tempfile::tempdir().unwrap();
+        let session = Session::load_from(dir.path().join(SESSION_FILE));
+
+        assert_eq!(session.load_draft(1), None);
+        session.save_draft(1, "Thanks for the report").unwrap();
+        assert!(dir.path().join(DRAFTS_DIR).join("1.txt").exists());
+        assert_eq!(
+            session.load_draft(1).as_deref(),
+            Some("Thanks for the report")
+        );
+        session.remove_draft(1).unwrap();
+        assert_eq!(session.load_draft(1), None);
+        session.remove_draft(1).unwrap();
+
+        // Nothing is written without a session file
+        let session = Session::default();
+        session.save_draft(1, "Thanks").unwrap();
+        assert_eq!(session.load_draft(1), None);
+    }
+}
@@ -4,19 +4,28 @@ use ratatui::{
     Frame,
     layout::{Alignment, Constraint, Direction, Layout, Margin, Rect},
     style::{Color, Modifier, Style},
-    text::Line,
+    text::{Line, Span},
     widgets::{
-        Block, Borders, Cell, Paragraph, Row, Scrollbar, ScrollbarOrientation, ScrollbarState,
-        Table,
+        Block, Borders, Cell, Clear, Paragraph, Row, Scrollbar, ScrollbarOrientation,
+        ScrollbarState, Table, Wrap,
     },
 };
 use textwrap::wrap;
 
 // We need the App struct to access the application state
 use chrono::Local;
-use throbber_widgets_tui::Throbber;
+use launchpad_api_client::{InformationType, LaunchpadBug, StatusFilter};
+use std::time::Instant;
+use throbber_widgets_tui::{Throbber, ThrobberState, symbols::throbber};
 
-use crate::app::{ActivePanel, App, Screen};
+use crate::{
+    app::{ActivePanel, App, ChatRole, Popup, Screen},
+    config::{SpinnerStyle, Theme},
+    input::TextInput,
+    keymap::Action,
+    markdown::render_markdown,
+    scroll::{clamp_scroll, end_scroll},
+};
 
 /// Playful labels for the spinner, cycled with each 's' key press
 pub const SPINNER_LABELS: [&str; 5] = [
@@ -27,6 +36,69 @@ pub const SPINNER_LABELS: [&str; 5] = [
     "Coffee time",
 ];
 
+/// Narrowest terminal showing the reply next to the bug list and description
+const SPLIT_VIEW_MIN_WIDTH: u16 = 150;
+
+/// Symbols used to decorate the widgets, with an ASCII fallback for
+/// terminals that cannot render unicode (e.g. serial consoles).
+#[derive(Debug, Clone)]
+pub(crate) struct Symbols {
+    pub scroll_begin: &'static str,
+    pub scroll_end: &'static str,
+    pub unicode: bool,
+    pub throbber: throbber::Set,
+}
+
+impl Symbols {
+    pub(crate) fn unicode() -> Self {
+        Self {
+            scroll_begin: "↑",
+            scroll_end: "↓",
+            unicode: true,
+            throbber: throbber::BRAILLE_SIX,
+        }
+    }
+
+    pub(crate) fn ascii() -> Self {
+        Self {
+            scroll_begin: "^",
+            scroll_end: "v",
+            unicode: false,
+            throbber: throbber::ASCII,
+        }
+    }
+
+    /// Picks the symbols according to the locale, `RATATAI_ASCII` forces the ASCII set.
+    pub(crate) fn detect() -> Self {
+        if std::env::var_os("RATATAI_ASCII").is_some() {
+            return Self::ascii();
+        }
+        let locale = ["LC_ALL", "LC_CTYPE", "LANG"]
+            .iter()
+            .filter_map(|var| std::env::var(var).ok())
+            .find(|value| !value.is_empty())
+            .unwrap_or_default()
+            .to_lowercase();
+        if locale.contains("utf-8") || locale.contains("utf8") {
+            Self::unicode()
+        } else {
+            Self::ascii()
+        }
+    }
+
+    /// Replaces the spinner characters by the configured ones.
+    pub(crate) fn with_spinner(mut self, style: Option<SpinnerStyle>) -> Self {
+        self.throbber = match style {
+            None => return self,
+            Some(SpinnerStyle::Braille) => throbber::BRAILLE_SIX,
+            Some(SpinnerStyle::Dots) => throbber::BRAILLE_ONE,
+            Some(SpinnerStyle::Line) => throbber::BOX_DRAWING,
+            Some(SpinnerStyle::Ascii) => throbber::ASCII,
+        };
+        self
+    }
+}
+
 /// Draws the application's user interface.
 /// Takes a Ratatui Frame and a mutable reference to the application state.
 pub fn draw_ui(f: &mut Frame, app: &mut App) {
@@ -37,6 +109,21 @@ pub fn draw_ui(f: &mut Frame, app: &mut App) {
         .split(f.area());
 
     match app.current_screen {
+        // The reply is only added next to the description if it fits
+        Screen::BugList if app.split_view && chunks[0].width >= SPLIT_VIEW_MIN_WIDTH => {
+            let main_chunks = Layout::default()
+                .direction(Direction::Horizontal)
+                .constraints([
+                    Constraint::Percentage(40),
+                    Constraint::Percentage(30),
+                    Constraint::Percentage(30),
+                ])
+                .split(chunks[0]);
+
+            draw_bug_list(f, app, main_chunks[0]);
+            draw_bug_description(f, app, main_chunks[1]);
+            draw_bug_reply(f, app, main_chunks[2]);
+        }
         Screen::BugList => {
             let main_chunks = Layout::default()
                 .direction(Direction::Horizontal)
@@ -62,110 +149,414 @@ pub fn draw_ui(f: &mut Frame, app: &mut App) {
 
     // Bottom Status Panel (for spinner and time)
     draw_bottom_panel(f, app, chunks[1]);
+
+    if let Some(popup) = &app.popup {
+        draw_popup(f, app, popup, f.area());
+    }
+}
+
+/// Returns a rectangle centered in `area` using the given percentages of its size.
+fn centered_rect(percent_x: u16, percent_y: u16, area: Rect) -> Rect {
+    let vertical = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([
+            Constraint::Percentage((100 - percent_y) / 2),
+            Constraint::Percentage(percent_y),
+            Constraint::Percentage((100 - percent_y) / 2),
+        ])
+        .split(area);
+    Layout::default()
+        .direction(Direction::Horizontal)
+        .constraints([
+            Constraint::Percentage((100 - percent_x) / 2),
+            Constraint::Percentage(percent_x),
+            Constraint::Percentage((100 - percent_x) / 2),
+        ])
+        .split(vertical[1])[1]
+}
+
+fn draw_popup(f: &mut Frame, app: &App, popup: &Popup, area: Rect) {
+    let (title, text, popup_area) = match popup {
+        Popup::RestoreDraft(draft) => (
+            "Saved draft",
+            format!(
+                "A reply draft was saved for this bug, restore it? (y/n)\n\n{}",
+                draft.lines().take(5).collect::<Vec<_>>().join("\n")
+            ),
+            centered_rect(60, 30, area),
+        ),
+        Popup::ConfirmReply { action, warnings } => {
+            let question = match (action, warnings.is_empty()) {
+                (Action::PostReply, true) => "Post this reply on Launchpad? (y/n)",
+                (Action::PostReply, false) => "Post it on Launchpad anyway? (y/n)",
+                (Action::PostAndMarkIncomplete, true) => {
+                    "Post this reply and mark the bug Incomplete? (y/n)"
+                }
+                (Action::PostAndMarkIncomplete, false) => {
+                    "Post it and mark the bug Incomplete anyway? (y/n)"
+                }
+                _ => "Send it to the chat anyway? (y/n)",
+            };
+            let warnings: String = warnings.iter().map(|w| format!("- {w}\n")).collect();
+            (
+                "Check the reply",
+                format!("{warnings}\n{question}"),
+                centered_rect(60, 30, area),
+            )
+        }
+        Popup::ExportBugs(input) => (
+            "Export the listed bugs (Enter write, Esc cancel)",
+            format!("File name, ending with .csv or .json:\n{}", input.text()),
+            centered_rect(60, 20, area),
+        ),
+        Popup::ExportMarkdown(input) => (
+            "Export the bug as markdown (Enter write, Esc cancel)",
+            format!("File name:\n{}", input.text()),
+            centered_rect(60, 20, area),
+        ),
+        Popup::GoToBug(input) => (
+            "Go to bug (Enter open, Esc cancel)",
+            format!("Bug number:\n{}", input.text()),
+            centered_rect(40, 20, area),
+        ),
+        Popup::PromptPreview { prompt, .. } => (
+            "AI prompt preview (Up/Down scroll, Esc close)",
+            prompt.clone(),
+            centered_rect(80, 80, area),
+        ),
+        Popup::ConfirmQuit => (
+            "Quit",
+            "The reply was edited but not posted, quit anyway? (y/n)".to_string(),
+            centered_rect(50, 20, area),
+        ),
+        Popup::Error(message) => (
+            "Error (press any key to close)",
+            message.clone(),
+            centered_rect(60, 20, area),
+        ),
+        Popup::StatusFilter { checked, cursor } => (
+            "Statuses (Space toggle, Enter apply, Esc cancel)",
+            StatusFilter::ALL
+                .iter()
+                .zip(checked)
+                .enumerate()
+                .map(|(i, (status, checked))| {
+                    format!(
+                        "{} [{}] {status}",
+                        if i == *cursor { ">" } else { " " },
+                        if *checked { "x" } else { " " }
+                    )
+                })
+                .collect::<Vec<_>>()
+                .join("\n"),
+            centered_rect(40, 50, area),
+        ),
+        Popup::Help => (
+            "Help (press any key to close)",
+            app.log
+                .iter()
+                .map(|log| {
+                    format!(
+                        "Logs written to {} at level {}\n",
+                        log.current_file().display(),
+                        log.level()
+                    )
+                })
+                .chain(app.keymap.help(app.symbols.unicode))
+                .collect::<Vec<_>>()
+                .join("\n"),
+            centered_rect(70, 80, area),
+        ),
+    };
+
+    let paragraph = Paragraph::new(text)
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .title(title)
+                .border_style(Style::default().fg(match popup {
+                    Popup::Error(_) => app.theme.error,
+                    _ => app.theme.warning,
+                })),
+        )
+        .wrap(Wrap { trim: false });
+    let paragraph = match popup {
+        Popup::PromptPreview { scroll, .. } => paragraph.scroll((*scroll, 0)),
+        _ => paragraph,
+    };
+
+    f.render_widget(Clear, popup_area);
+    f.render_widget(paragraph, popup_area);
+    if let Popup::ExportBugs(input) | Popup::ExportMarkdown(input) | Popup::GoToBug(input) = popup {
+        f.set_cursor_position((popup_area.x + 1 + input.cursor() as u16, popup_area.y + 2));
+    }
 }
 
 /// Draws the bottom panel for the spinner and time.
 fn draw_bottom_panel(f: &mut Frame, app: &mut App, area: Rect) {
     let time_str = Local::now().format("%H:%M:%S").to_string();
-    let spinner_label_width = SPINNER_LABELS[app.spinner_label_index].len() as u16 + 2; // +2 for throbber
+    // The tokens are dropped with the clock on narrow terminals
+    let time_str = match app.token_usage.lock().unwrap().label() {
+        Some(tokens) => format!("{tokens} {time_str}"),
+        None => time_str,
+    };
+
+    app.advance_spinner(Instant::now());
+
+    let loading = app.loading.label();
+    let label = loading
+        .as_deref()
+        .unwrap_or(SPINNER_LABELS[app.spinner_label_index]);
+    let command_text = match &app.status_message {
+        Some(message) => message.clone(),
+        None if app.filter_editing => {
+            format!("Search: {}_ (Enter keep, Esc clear)", app.filter_query)
+        }
+        None if app.chat_input.is_some() => {
+            "Enter send to the chat, Esc cancel, Left/Right/Home/End move".to_string()
+        }
+        None => {
+            let hint = app.keymap.hint(app.key_context(), app.symbols.unicode);
+            // Room left once the spinner with its label and the clock are drawn
+            let room = area
+                .width
+                .saturating_sub(2 + label.chars().count() as u16 + time_str.len() as u16);
+            let help_open = app.popup == Some(Popup::Help);
+            let hint = if !help_open && (app.compact_hints || hint.chars().count() as u16 > room) {
+                app.keymap.compact_hint(app.symbols.unicode)
+            } else {
+                hint
+            };
+            if app.filter_query.is_empty() {
+                hint
+            } else {
+                format!("Search '{}', {hint}", app.filter_query)
+            }
+        }
+    };
+
+    let status_bar = StatusBar {
+        theme: app.theme,
+        label,
+        throbber: app.symbols.throbber.clone(),
+        command: &command_text,
+        time: &time_str,
+    };
+    draw_status_bar(f, &status_bar, area, &mut app.spinner_state);
+}
+
+/// Content of the bottom bar.
+struct StatusBar<'a> {
+    theme: Theme,
+    label: &'a str,
+    throbber: throbber::Set,
+    command: &'a str,
+    time: &'a str,
+}
+
+/// Computes the widths of the spinner and of the clock in the bottom bar.
+/// On narrow areas the spinner label is dropped first, then the clock.
+fn status_bar_widths(area_width: u16, label_width: u16, time_width: u16) -> (u16, bool, u16) {
+    // The throbber symbol and the space after it
+    let throbber_width = 2;
+
+    if area_width >= throbber_width + label_width + time_width {
+        (throbber_width + label_width, true, time_width)
+    } else if area_width >= throbber_width + time_width {
+        (throbber_width, false, time_width)
+    } else {
+        (throbber_width.min(area_width), false, 0)
+    }
+}
+
+fn draw_status_bar(f: &mut Frame, status_bar: &StatusBar, area: Rect, state: &mut ThrobberState) {
+    let (spinner_width, show_label, time_width) = status_bar_widths(
+        area.width,
+        status_bar.label.chars().count() as u16,
+        status_bar.time.chars().count() as u16,
+    );
     let chunks = Layout::default()
         .direction(Direction::Horizontal)
         .constraints(
             [
-                Constraint::Length(spinner_label_width),
+                Constraint::Length(spinner_width),
                 Constraint::Min(0),
-                Constraint::Length(time_str.len() as u16),
+                Constraint::Length(time_width),
             ]
             .as_ref(),
         )
         .split(area);
 
     // Left sub-panel: spinner with throbber and label styled separately
-    if app.spinner_enabled {
-        app.spinner_state.calc_next();
+    let mut spinner = Throbber::default()
+        .throbber_set(status_bar.throbber.clone())
+        .throbber_style(Style::default().fg(status_bar.theme.secondary))
+        .style(Style::default().fg(status_bar.theme.accent));
+    if show_label {
+        spinner = spinner.label(status_bar.label);
     }
-    let spinner = Throbber::default()
-        .throbber_style(Style::default().fg(Color::Magenta))
-        .label(SPINNER_LABELS[app.spinner_label_index])
-        .style(Style::default().fg(Color::Cyan));
-    f.render_stateful_widget(spinner, chunks[0], &mut app.spinner_state);
+    f.render_stateful_widget(spinner, chunks[0], state);
 
     // Middle sub-panel: Command input
-    let command_text = match app.current_screen {
-        Screen::BugList => match app.active_panel {
-            ActivePanel::Left => {
-                "Tab selection, ↑↓ PgUp/PgDown Home/End to navigate, 'r' to refresh list, 'Enter' to open bug"
-            }
-            ActivePanel::Right => {
-                "Tab selection, ↑↓ PgUp/PgDown Home/End to navigate, 'e' to edit, 'Enter' to reply to this bug"
-            }
-        },
-        Screen::BugEditing => match app.active_panel {
-            ActivePanel::Left => {
-                "Tab selection, ↑↓ PgUp/PgDown Home/End to navigate, 'e' to edit, 'Enter' to craft a reply to this bug"
-            }
-            ActivePanel::Right => "'e' to edit, 'Enter' to ask chat to refine this bug",
-        },
-    };
-    let command_paragraph = Paragraph::new(command_text)
+    let command_paragraph = Paragraph::new(status_bar.command)
         .alignment(Alignment::Center)
         .style(
             Style::default()
-                .fg(Color::Cyan)
+                .fg(status_bar.theme.accent)
                 .add_modifier(Modifier::BOLD),
         );
     f.render_widget(command_paragraph, chunks[1]);
 
     // Right sub-panel with current time at bottom-right
-    let time_paragraph = Paragraph::new(time_str).alignment(Alignment::Right);
-    f.render_widget(time_paragraph, chunks[2]);
+    if time_width > 0 {
+        let time_paragraph = Paragraph::new(status_bar.time).alignment(Alignment::Right);
+        f.render_widget(time_paragraph, chunks[2]);
+    }
+}
+
+/// Describes the statuses of the listed bugs for the table title.
+fn statuses_label(statuses: &[StatusFilter]) -> String {
+    let names: Vec<String> = statuses.iter().map(|s| format!("'{s}'")).collect();
+    match names.len() {
+        1 => format!("status {}", names[0]),
+        _ => format!("statuses {}", names.join(", ")),
+    }
+}
+
+/// Bottom title of a panel whose content is being fetched.
+fn loading_title(text: &str, theme: &Theme) -> Line<'static> {
+    Line::styled(
+        text.to_string(),
+        Style::default()
+            .fg(theme.warning)
+            .add_modifier(Modifier::ITALIC),
+    )
+    .right_aligned()
 }
 
 fn draw_bug_list(f: &mut Frame, app: &mut App, area: Rect) {
-    let table_title = format!(
-        "Bugs in status 'New' {}/{}",
-        match app.bug_table_state.selected() {
-            None => "-".to_string(),
-            Some(n) => (n + 1).to_string(),
-        },
-        app.bug_table_items.len()
-    );
-    let header_cells = ["Bug ID", "Date", "Title"]
-        .iter()
-        .map(|h| Cell::from(*h).style(Style::default().fg(Color::Red)));
+    // Without the borders and the header
+    app.bug_table_viewport = Some(area.height.saturating_sub(3));
+    let progress = match app.bugs_progress {
+        Some((fetched, total)) => format!(" Loaded {fetched}/{total}"),
+        None => String::new(),
+    };
+    let table_title = if app.loading.bugs && app.bug_table_items.is_empty() {
+        format!(
+            "Loading {} bugs in {}...{progress}",
+            app.project,
+            statuses_label(&app.statuses)
+        )
+    } else {
+        format!(
+            "{} bugs in {}{}{}{}{} {}/{}",
+            app.project,
+            statuses_label(&app.statuses),
+            match app.assignee.as_ref().filter(|_| app.only_assigned_to_me) {
+                Some(assignee) => format!(" assigned to {assignee}"),
+                None => String::new(),
+            },
+            match (app.importance, &app.tag_filter) {
+                (Some(importance), Some(tag)) => {
+                    format!(" of importance {importance} tagged '{tag}'")
+                }
+                (Some(importance), None) => format!(" of importance {importance}"),
+                (None, Some(tag)) => format!(" tagged '{tag}'"),
+                (None, None) => String::new(),
+            },
+            if app.omit_duplicates {
+                " without duplicates"
+            } else {
+                ""
+            },
+            if app.only_my_activity {
+                " (my activity)"
+            } else {
+                ""
+            },
+            match app.bug_table_state.selected() {
+                None => "-".to_string(),
+                Some(n) => (n + 1).to_string(),
+            },
+            app.bug_table_items.len()
+        )
+    };
+    let header_cells = [
+        "Bug ID",
+        "Date",
+        "Status",
+        "Importance",
+        "Assignee",
+        "Title",
+    ]
+    .iter()
+    .map(|h| {
+        if *h == app.sort_key.column() {
+            let arrow = match (app.sort_descending, app.symbols.unicode) {
+                (true, true) => "↓",
+                (false, true) => "↑",
+                (true, false) => "v",
+                (false, false) => "^",
+            };
+            Cell::from(format!("{h} {arrow}")).style(
+                Style::default()
+                    .fg(app.theme.header)
+                    .add_modifier(Modifier::BOLD | Modifier::UNDERLINED),
+            )
+        } else {
+            Cell::from(*h).style(Style::default().fg(app.theme.header))
+        }
+    });
     let header = Row::new(header_cells).style(Style::default()).height(1);
 
     let widths = &[
         Constraint::Length(9),
         Constraint::Length(12),
+        Constraint::Length(14),
+        Constraint::Length(12),
+        Constraint::Length(16),
         Constraint::Percentage(100),
     ];
     let table_border_style = if let ActivePanel::Left = app.active_panel {
-        Style::default().fg(Color::Green)
+        Style::default().fg(app.theme.active_border)
     } else {
-        Style::default().fg(Color::White)
+        Style::default().fg(app.theme.border)
     };
 
+    let mut table_block = Block::default()
+        .borders(Borders::ALL)
+        .title(table_title)
+        .border_style(table_border_style);
+    if let Some(summary) = app.status_summary() {
+        table_block = table_block.title_bottom(Line::from(summary).right_aligned());
+    }
+    // The title already tells the first fetch
+    if app.loading.bugs && !app.bug_table_items.is_empty() {
+        table_block = table_block.title_bottom(loading_title(
+            &format!("Refreshing...{progress}"),
+            &app.theme,
+        ));
+    }
+
     let table_widget = Table::new(app.bug_table_rows.iter().cloned(), widths)
         .header(header)
-        .block(
-            Block::default()
-                .borders(Borders::ALL)
-                .title(table_title)
-                .border_style(table_border_style),
-        )
+        .block(table_block)
         .row_highlight_style(
             Style::default()
-                .bg(Color::LightCyan)
-                .fg(Color::Black)
+                .bg(app.theme.highlight_bg)
+                .fg(app.theme.highlight_fg)
                 .add_modifier(Modifier::BOLD),
         );
 
     f.render_stateful_widget(table_widget, area, &mut app.bug_table_state);
 
+    app.bug_table_scrollbar_state = app
+        .bug_table_scrollbar_state
+        .viewport_content_length(area.height.saturating_sub(3).into());
+
     let scrollbar = Scrollbar::new(ScrollbarOrientation::VerticalRight)
-        .begin_symbol(Some("↑"))
-        .end_symbol(Some("↓"));
+        .begin_symbol(Some(app.symbols.scroll_begin))
+        .end_symbol(Some(app.symbols.scroll_end));
 
     f.render_stateful_widget(
         scrollbar,
@@ -178,71 +569,147 @@ fn draw_bug_list(f: &mut Frame, app: &mut App, area: Rect) {
 }
 
 fn draw_bug_description(f: &mut Frame, app: &mut App, area: Rect) {
-    let current_display_text = app.gemini_response.lock().unwrap().clone();
+    let current_display_text = if app.show_activity {
+        app.activity_timeline()
+    } else {
+        app.gemini_response.lock().unwrap().clone()
+    };
     // Replace tab characters with spaces to prevent layout corruption.
     // The Paragraph widget miscalculates line widths when tabs are present,
     // causing severe misalignment and scroll glitches.
     let current_display_text = current_display_text.replace('\t', " ");
 
+    let reporter = app
+        .current_bug
+        .as_ref()
+        .map(|bug| format!("Reported by {}", app.person_name(&bug.owner_link)));
+
     let title = if let Some(bug) = &app.current_bug {
         let title_trunc: String = bug.title.chars().take(64).collect();
-        format!("Bug '{}', {}...", bug.id, title_trunc)
+        let text = match app.new_comments {
+            Some(n) => format!(
+                "Bug '{}', {}... ({n} new comment{})",
+                bug.id,
+                title_trunc,
+                if n > 1 { "s" } else { "" }
+            ),
+            None => format!("Bug '{}', {}...", bug.id, title_trunc),
+        };
+        let mut spans = vec![information_badge(&app.theme, bug), Span::raw(" ")];
+        // The bugs others were marked duplicate of are worth a look
+        if bug.number_of_duplicates > 0 {
+            let mark = if app.symbols.unicode { "⧉" } else { "dup:" };
+            spans.push(Span::styled(
+                format!("{mark}{} ", bug.number_of_duplicates),
+                Style::default().fg(app.theme.accent),
+            ));
+        }
+        spans.push(Span::raw(text));
+        if let Some(status) = app.status_change() {
+            spans.push(Span::styled(
+                format!(" status changed to {status} since listing"),
+                Style::default().fg(app.theme.muted),
+            ));
+        }
+        Line::from(spans)
     } else {
-        "No bug selected".to_string()
+        Line::from("No bug selected")
     };
 
     let panel_border_style = match app.current_screen {
         Screen::BugList => match app.active_panel {
-            ActivePanel::Right => Style::default().fg(Color::Green),
-            _ => Style::default().fg(Color::White),
+            ActivePanel::Right => Style::default().fg(app.theme.active_border),
+            _ => Style::default().fg(app.theme.border),
         },
         Screen::BugEditing => match app.active_panel {
-            ActivePanel::Left => Style::default().fg(Color::Green),
-            _ => Style::default().fg(Color::White),
+            ActivePanel::Left => Style::default().fg(app.theme.active_border),
+            _ => Style::default().fg(app.theme.border),
         },
     };
 
-    let scrollbar_area = area.inner(Margin {
-        vertical: 1,
-        horizontal: 0,
-    });
-    let scrollbar_height = scrollbar_area.height as usize;
+    let mut block = Block::default()
+        .borders(Borders::ALL)
+        .title(title)
+        .border_style(panel_border_style);
+    if let Some(reporter) = reporter {
+        block = block.title(Line::from(reporter).right_aligned());
+    }
+    if app.loading.bug {
+        block = block.title_bottom(loading_title("Loading bug...", &app.theme));
+    } else if app.show_activity && app.loading.activity {
+        block = block.title_bottom(loading_title("Loading activity...", &app.theme));
+    } else if app.show_activity {
+        block = block.title_bottom(
+            Line::styled("Activity", Style::default().fg(app.theme.muted)).right_aligned(),
+        );
+    } else if app.loading.ai {
+        block = block.title_bottom(loading_title("Waiting for AI...", &app.theme));
+    }
 
-    let wrapped_text = wrap(&current_display_text, (scrollbar_area.width - 2) as usize);
+    // The metadata header is dropped when it would leave no room to read
+    let inner = block.inner(area);
+    let header = app
+        .current_bug
+        .as_ref()
+        .map(|bug| bug_header(app, bug))
+        .unwrap_or_default();
+    let header_height = header.len() as u16 + 1;
+    let header_height = if header.is_empty() || inner.height < header_height + 3 {
+        0
+    } else {
+        header_height
+    };
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Length(header_height), Constraint::Min(0)])
+        .split(inner);
+    let body_area = chunks[1];
 
-    let wrapped_text: Vec<Line> = wrapped_text
-        .iter()
-        .map(|line| Line::from(line.to_string()))
-        .collect();
+    // The scrollbar is drawn over the right border, next to the body
+    let scrollbar_area = Rect::new(area.x, body_area.y, area.width, body_area.height);
+    let scrollbar_height = scrollbar_area.height as usize;
+    app.bug_desc_viewport = Some(scrollbar_area.height);
+
+    let text_width = body_area.width as usize;
+    // The activity is plain text, whatever the rendering of the description
+    let wrapped_text: Vec<Line> = if app.markdown && !app.show_activity {
+        render_markdown(&current_display_text, text_width, &app.theme)
+    } else {
+        wrap(&current_display_text, text_width)
+            .iter()
+            .map(|line| Line::from(line.to_string()))
+            .collect()
+    };
 
     let content_length = wrapped_text.len();
 
     if app.bug_desc_scroll_to_end {
-        app.bug_desc_scroll = content_length.saturating_sub(scrollbar_height) as u16;
+        app.bug_desc_scroll = end_scroll(content_length, scrollbar_height);
         app.bug_desc_scroll_to_end = false;
     }
+    app.bug_desc_scroll = clamp_scroll(app.bug_desc_scroll, content_length, scrollbar_height);
 
-    let max_scroll = content_length.saturating_sub(scrollbar_height) as u16;
-    app.bug_desc_scroll = app.bug_desc_scroll.min(max_scroll);
-
-    let bug_description_paragraph = Paragraph::new(wrapped_text)
-        .block(
+    f.render_widget(block, area);
+    if header_height > 0 {
+        let header_paragraph = Paragraph::new(header).block(
             Block::default()
-                .borders(Borders::ALL)
-                .title(title)
-                .border_style(panel_border_style),
-        )
-        .scroll((app.bug_desc_scroll, 0));
+                .borders(Borders::BOTTOM)
+                .border_style(Style::default().fg(app.theme.muted)),
+        );
+        f.render_widget(header_paragraph, chunks[0]);
+    }
+
+    let bug_description_paragraph = Paragraph::new(wrapped_text).scroll((app.bug_desc_scroll, 0));
 
-    f.render_widget(bug_description_paragraph, area);
+    f.render_widget(bug_description_paragraph, body_area);
 
     let mut bug_desc_scrollbar_state = ScrollbarState::new(content_length)
         .viewport_content_length(scrollbar_height)
         .position(app.bug_desc_scroll as usize);
 
     let bug_table_scrollbar = Scrollbar::new(ScrollbarOrientation::VerticalRight)
-        .begin_symbol(Some("↑"))
-        .end_symbol(Some("↓"));
+        .begin_symbol(Some(app.symbols.scroll_begin))
+        .end_symbol(Some(app.symbols.scroll_end));
 
     f.render_stateful_widget(
         bug_table_scrollbar,
@@ -251,18 +718,575 @@ fn draw_bug_description(f: &mut Frame, app: &mut App, area: Rect) {
     );
 }
 
-fn draw_bug_reply(f: &mut Frame, app: &mut App, area: Rect) {
-    let bug_reply_paragraph = Paragraph::new(app.bug_reply_text.clone())
+/// Metadata of the open bug shown above its description, to judge its
+/// severity at a glance.
+fn bug_header(app: &App, bug: &LaunchpadBug) -> Vec<Line<'static>> {
+    let label = |text: &str| Span::styled(text.to_string(), Style::default().fg(app.theme.muted));
+    let value = |text: String| Span::styled(text, Style::default().add_modifier(Modifier::BOLD));
+    let date = |date: Option<chrono::DateTime<chrono::Utc>>| {
+        date.map_or("-".to_string(), |date| date.date_naive().to_string())
+    };
+
+    let mut lines = vec![
+        Line::from(vec![
+            label("Heat "),
+            value(bug.heat.to_string()),
+            label("  Messages "),
+            value(bug.message_count.to_string()),
+            label("  Users affected "),
+            value(bug.users_affected_count.to_string()),
+        ]),
+        Line::from(vec![
+            label("Created "),
+            value(date(bug.date_created)),
+            label("  Updated "),
+            value(date(bug.date_last_updated)),
+        ]),
+    ];
+    if let Some((words, minutes)) = app.description_stats {
+        let separator = if app.symbols.unicode { "·" } else { "-" };
+        lines[0].push_span(label(&format!(
+            "  ({words} words {separator} ~{minutes} min)"
+        )));
+    }
+    if let Some(original) = bug.duplicate_of() {
+        lines.push(Line::from(vec![
+            label("Duplicate of "),
+            Span::styled(
+                format!("bug #{original}"),
+                Style::default()
+                    .fg(app.theme.accent)
+                    .add_modifier(Modifier::BOLD),
+            ),
+        ]));
+    }
+    if !bug.tags.is_empty() {
+        // The tag filtering the list stands out
+        let mut spans = vec![label("Tags ")];
+        for (i, tag) in bug.tags.iter().enumerate() {
+            if i > 0 {
+                spans.push(Span::raw(", "));
+            }
+            let style = if app.tag_filter.as_ref() == Some(tag) {
+                Style::default().add_modifier(Modifier::BOLD | Modifier::UNDERLINED)
+            } else {
+                Style::default()
+            };
+            spans.push(Span::styled(tag.clone(), style));
+        }
+        lines.push(Line::from(spans));
+    }
+    lines
+}
+
+/// Color of a bug importance in the table, the most important stand out.
+pub(crate) fn importance_color(theme: &Theme, importance: &str) -> Color {
+    match importance {
+        "Critical" => theme.error,
+        "High" => theme.warning,
+        "Medium" => theme.accent,
+        "Low" | "Wishlist" => theme.muted,
+        _ => Color::Reset,
+    }
+}
+
+/// Badge showing the confidentiality of a bug, colored by sensitivity.
+fn information_badge(theme: &Theme, bug: &LaunchpadBug) -> Span<'static> {
+    let color = badge_color(
+        theme,
+        &bug.information_type,
+        bug.private,
+        bug.security_related,
+    );
+    Span::styled(
+        format!("[{}]", bug.information_type),
+        Style::default()
+            .fg(theme.highlight_fg)
+            .bg(color)
+            .add_modifier(Modifier::BOLD),
+    )
+}
+
+fn badge_color(
+    theme: &Theme,
+    information_type: &InformationType,
+    private: bool,
+    security_related: bool,
+) -> Color {
+    if private || information_type.is_private() {
+        theme.error
+    } else if security_related || information_type.is_security() {
+        theme.warning
+    } else {
+        theme.success
+    }
+}
+
+/// Number of lines of a prompt shown in the chat history, the prompts
+/// crafting a reply embed the whole bug.
+const PROMPT_PREVIEW_LINES: usize = 4;
+
+/// Draws the prompts and answers of the chat above the reply draft.
+fn draw_chat_history(f: &mut Frame, app: &mut App, area: Rect) {
+    let width = area.width.saturating_sub(2) as usize;
+    let mut lines: Vec<Line> = Vec::new();
+    for turn in &app.chat_history {
+        let (label, color, text) = match turn.role {
+            ChatRole::User => {
+                let mut preview: Vec<&str> = turn.text.lines().collect();
+                if preview.len() > PROMPT_PREVIEW_LINES {
+                    preview.truncate(PROMPT_PREVIEW_LINES);
+                    preview.push("...");
+                }
+                ("You", app.theme.accent, preview.join("\n"))
+            }
+            ChatRole::Model => ("Gemini", app.theme.secondary, turn.text.clone()),
+        };
+        if !lines.is_empty() {
+            lines.push(Line::default());
+        }
+        lines.push(Line::styled(
+            format!("{label}:"),
+            Style::default().fg(color).add_modifier(Modifier::BOLD),
+        ));
+        lines.extend(
+            wrap(&text.replace('\t', " "), width)
+                .iter()
+                .map(|line| Line::from(line.to_string())),
+        );
+    }
+
+    let scrollbar_area = area.inner(Margin {
+        vertical: 1,
+        horizontal: 0,
+    });
+    let viewport = scrollbar_area.height as usize;
+    let content_length = lines.len();
+    if app.chat_scroll_to_end {
+        app.chat_scroll = end_scroll(content_length, viewport);
+        app.chat_scroll_to_end = false;
+    }
+    app.chat_scroll = clamp_scroll(app.chat_scroll, content_length, viewport);
+
+    let active = app.active_panel == ActivePanel::Right && app.chat_focused;
+    let paragraph = Paragraph::new(lines)
         .block(
             Block::default()
                 .borders(Borders::ALL)
-                .title("Bug reply")
-                .border_style(match app.active_panel {
-                    ActivePanel::Right => Style::default().fg(Color::Green),
-                    _ => Style::default().fg(Color::White),
-                }),
+                .title("Chat history")
+                .border_style(Style::default().fg(if active {
+                    app.theme.active_border
+                } else {
+                    app.theme.border
+                })),
         )
-        .wrap(ratatui::widgets::Wrap { trim: true });
+        .scroll((app.chat_scroll, 0));
+    f.render_widget(paragraph, area);
+
+    let mut scrollbar_state = ScrollbarState::new(content_length)
+        .viewport_content_length(viewport)
+        .position(app.chat_scroll as usize);
+    let scrollbar = Scrollbar::new(ScrollbarOrientation::VerticalRight)
+        .begin_symbol(Some(app.symbols.scroll_begin))
+        .end_symbol(Some(app.symbols.scroll_end));
+    f.render_stateful_widget(scrollbar, scrollbar_area, &mut scrollbar_state);
+}
+
+/// Draws the prompt being typed to the chat, scrolled to keep the cursor visible.
+fn draw_chat_input(f: &mut Frame, input: &TextInput, theme: &Theme, area: Rect) {
+    let width = area.width.saturating_sub(2).max(1) as usize;
+    let skip = (input.cursor() + 1).saturating_sub(width);
+    let visible: String = input.text().chars().skip(skip).take(width).collect();
+    let paragraph = Paragraph::new(visible).block(
+        Block::default()
+            .borders(Borders::ALL)
+            .title("Ask the chat")
+            .border_style(Style::default().fg(theme.warning)),
+    );
+    f.render_widget(paragraph, area);
+    f.set_cursor_position((area.x + 1 + (input.cursor() - skip) as u16, area.y + 1));
+}
+
+fn draw_bug_reply(f: &mut Frame, app: &mut App, area: Rect) {
+    let area = match &app.chat_input {
+        Some(input) => {
+            let chunks = Layout::default()
+                .direction(Direction::Vertical)
+                .constraints([Constraint::Min(0), Constraint::Length(3)])
+                .split(area);
+            draw_chat_input(f, input, &app.theme, chunks[1]);
+            chunks[0]
+        }
+        None => area,
+    };
+    // The draft stays below the chat history once something was asked
+    let area = if app.chat_history.is_empty() {
+        area
+    } else {
+        let chunks = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([Constraint::Percentage(50), Constraint::Percentage(50)])
+            .split(area);
+        draw_chat_history(f, app, chunks[0]);
+        chunks[1]
+    };
+    // Same tab issue as the description panel
+    let reply_text = app.bug_reply_text.replace('\t', " ");
+    let wrapped_text: Vec<Line> = wrap(&reply_text, area.width.saturating_sub(2) as usize)
+        .iter()
+        .map(|line| Line::from(line.to_string()))
+        .collect();
+    let scrollbar_area = area.inner(Margin {
+        vertical: 1,
+        horizontal: 0,
+    });
+    let viewport = scrollbar_area.height as usize;
+    let content_length = wrapped_text.len();
+
+    if app.bug_reply_scroll_to_end {
+        app.bug_reply_scroll = end_scroll(content_length, viewport);
+        app.bug_reply_scroll_to_end = false;
+    }
+    app.bug_reply_scroll = clamp_scroll(app.bug_reply_scroll, content_length, viewport);
+
+    let mut block = Block::default()
+        .borders(Borders::ALL)
+        .title("Reply / AI draft")
+        // Only focused on the editing screen, the split view just shows it
+        .border_style(match (&app.current_screen, &app.active_panel) {
+            (Screen::BugEditing, ActivePanel::Right) if !app.chat_focused => {
+                Style::default().fg(app.theme.active_border)
+            }
+            _ => Style::default().fg(app.theme.border),
+        });
+    if app.loading.reply {
+        block = block.title_bottom(loading_title("Waiting for an answer...", &app.theme));
+    }
+
+    let bug_reply_paragraph = Paragraph::new(wrapped_text)
+        .block(block)
+        .scroll((app.bug_reply_scroll, 0));
 
     f.render_widget(bug_reply_paragraph, area);
+
+    let mut bug_reply_scrollbar_state = ScrollbarState::new(content_length)
+        .viewport_content_length(viewport)
+        .position(app.bug_reply_scroll as usize);
+
+    let bug_reply_scrollbar = Scrollbar::new(ScrollbarOrientation::VerticalRight)
+        .begin_symbol(Some(app.symbols.scroll_begin))
+        .end_symbol(Some(app.symbols.scroll_end));
+
+    f.render_stateful_widget(
+        bug_reply_scrollbar,
+        scrollbar_area,
+        &mut bug_reply_scrollbar_state,
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{app::ChatTurn, config::ThemePreset};
+    use ratatui::{Terminal, backend::TestBackend};
+
+    fn screen(terminal: &Terminal<TestBackend>) -> String {
+        terminal
+            .backend()
+            .buffer()
+            .content()
+            .iter()
+            .map(|cell| cell.symbol())
+            .collect()
+    }
+
+    /// Same as [`screen`] with one line per terminal row.
+    fn screen_lines(terminal: &Terminal<TestBackend>) -> String {
+        let buffer = terminal.backend().buffer();
+        buffer
+            .content()
+            .chunks(usize::from(buffer.area.width))
+            .map(|line| line.iter().map(|cell| cell.symbol()).collect::<String>())
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+
+    fn render_status_bar(width: u16) -> String {
+        let mut terminal = Terminal::new(TestBackend::new(width, 1)).unwrap();
+        let status_bar = StatusBar {
+            theme: Theme::default(),
+            label: SPINNER_LABELS[0],
+            throbber: throbber::ASCII,
+            command: "Tab selection",
+            time: "12:34:56",
+        };
+        let mut state = ThrobberState::default();
+        terminal
+            .draw(|f| draw_status_bar(f, &status_bar, f.area(), &mut state))
+            .unwrap();
+        screen(&terminal)
+    }
+
+    #[tokio::test]
+    async fn test_bug_reply_renders_reply_text() {
+        let mut app = crate::fixtures::test_app();
+        app.bug_reply_text = (1..=20)
+            .map(|i| format!("line {i}"))
+            .collect::<Vec<_>>()
+            .join("\n");
+        app.bug_reply_scroll_to_end = true;
+        let mut terminal = Terminal::new(TestBackend::new(30, 7)).unwrap();
+
+        terminal
+            .draw(|f| draw_bug_reply(f, &mut app, f.area()))
+            .unwrap();
+
+        assert_eq!(app.bug_reply_scroll, 15);
+        assert!(!app.bug_reply_scroll_to_end);
+        let text = screen(&terminal);
+        assert!(text.contains("Reply / AI draft"));
+        assert!(text.contains("line 20"));
+        assert!(!text.contains("line 15 "));
+        assert!(!text.contains("Waiting"));
+
+        // Cannot scroll past the end
+        app.bug_reply_scroll = u16::MAX;
+        app.loading.reply = true;
+        terminal
+            .draw(|f| draw_bug_reply(f, &mut app, f.area()))
+            .unwrap();
+        assert_eq!(app.bug_reply_scroll, 15);
+        let text = screen(&terminal);
+        assert!(text.contains("Waiting for an answer..."));
+    }
+
+    #[tokio::test]
+    async fn test_chat_history_above_draft() {
+        let mut app = crate::fixtures::test_app();
+        app.chat_history = vec![
+            ChatTurn {
+                role: ChatRole::User,
+                text: (1..=6).map(|i| format!("prompt {i}\n")).collect(),
+            },
+            ChatTurn {
+                role: ChatRole::Model,
+                text: "Thanks for the report.".to_string(),
+            },
+        ];
+        app.bug_reply_text = "Thanks for the report.".to_string();
+        let mut terminal = Terminal::new(TestBackend::new(40, 24)).unwrap();
+
+        terminal
+            .draw(|f| draw_bug_reply(f, &mut app, f.area()))
+            .unwrap();
+
+        let text = screen(&terminal);
+        assert!(text.contains("Chat history"));
+        assert!(text.contains("You:"));
+        assert!(text.contains("prompt 4"));
+        assert!(!text.contains("prompt 5"));
+        assert!(text.contains("Gemini:"));
+        assert!(text.contains("Reply / AI draft"));
+    }
+
+    #[tokio::test]
+    async fn test_draw_bug_header() {
+        let mut app = crate::fixtures::test_app();
+        let mut bug = crate::fixtures::bug(1, "bug");
+        bug.tags = vec!["compute".to_string(), "libvirt".to_string()];
+        bug.number_of_duplicates = 3;
+        app.current_bug = Some(bug);
+        app.symbols = Symbols::ascii();
+        *app.gemini_response.lock().unwrap() = "A long description\n".repeat(50);
+        let mut terminal = Terminal::new(TestBackend::new(60, 20)).unwrap();
+
+        terminal
+            .draw(|f| draw_bug_description(f, &mut app, f.area()))
+            .unwrap();
+
+        let text = screen(&terminal);
+        assert!(text.contains("Heat 6"));
+        assert!(text.contains("Created 2025-01-13"));
+        assert!(text.contains("Tags compute, libvirt"));
+        assert!(text.contains("dup:3"));
+        assert!(!text.contains("words"));
+        assert!(!text.contains("Duplicate of"));
+        // Borders and header leave the body 14 lines
+        assert_eq!(app.bug_desc_viewport, Some(14));
+
+        app.description_stats = Some((412, 2));
+        app.current_bug.as_mut().unwrap().duplicate_of_link =
+            Some("https://api.launchpad.net/1.0/bugs/42".to_string());
+        terminal
+            .draw(|f| draw_bug_description(f, &mut app, f.area()))
+            .unwrap();
+        let text = screen(&terminal);
+        assert!(text.contains("(412 words"));
+        assert!(text.contains("~2 min)"));
+        assert!(text.contains("Duplicate of bug #42"));
+    }
+
+    #[tokio::test]
+    async fn test_split_view() {
+        let mut app = crate::fixtures::test_app();
+        app.current_bug = Some(crate::fixtures::bug(1, "bug"));
+        app.bug_reply_text = "Thanks for the report".to_string();
+        app.split_view = true;
+        let mut draw = |width| {
+            let mut terminal = Terminal::new(TestBackend::new(width, 30)).unwrap();
+            terminal.draw(|f| draw_ui(f, &mut app)).unwrap();
+            screen(&terminal)
+        };
+
+        assert!(draw(160).contains("Thanks for the report"));
+        // Back to two panels when narrow
+        assert!(!draw(120).contains("Thanks for the report"));
+    }
+
+    #[tokio::test]
+    async fn test_bug_rows_reused_until_etag_changes() {
+        use crate::app::BugsDiff;
+        use crate::fixtures::bug_task;
+
+        let mut app = crate::fixtures::test_app();
+        let draw = |app: &mut App| {
+            let mut terminal = Terminal::new(TestBackend::new(240, 20)).unwrap();
+            terminal.draw(|f| draw_ui(f, app)).unwrap();
+            screen_lines(&terminal)
+        };
+        app.update_bugs(vec![bug_task(1, "first"), bug_task(2, "second")].into_boxed_slice());
+        assert_eq!(app.bugs_diff.added, vec![1, 2]);
+        assert!(draw(&mut app).contains("first"));
+
+        // Same etag, the row built from the previous title is kept
+        let mut renamed = bug_task(1, "renamed");
+        app.update_bugs(vec![renamed.clone(), bug_task(3, "third")].into_boxed_slice());
+        assert_eq!(
+            app.bugs_diff,
+            BugsDiff {
+                added: vec![3],
+                changed: vec![],
+                removed: vec![2],
+            }
+        );
+        let screen = draw(&mut app);
+        assert!(screen.contains("first"));
+        assert!(screen.contains("third"));
+        assert_eq!(app.bug_row_cache.len(), 2);
+
+        renamed.http_etag = "\"etag-1b\"".to_string();
+        app.update_bugs(vec![renamed, bug_task(3, "third")].into_boxed_slice());
+        assert_eq!(app.bugs_diff.changed, vec![1]);
+        let screen = draw(&mut app);
+        assert!(screen.contains("renamed"));
+        assert!(!screen.contains("first"));
+
+        // A status set from the application keeps the etag
+        let task_link = app.all_bug_items[1].self_link.clone();
+        app.update_status_changed(3, &task_link, StatusFilter::Incomplete, Ok(()));
+        let screen = draw(&mut app);
+        let row = screen.lines().find(|line| line.contains("third")).unwrap();
+        assert!(row.contains("Incomplete"));
+    }
+
+    #[tokio::test]
+    async fn test_draw_ui_on_tiny_terminals() {
+        let mut app = crate::fixtures::test_app();
+        app.update_bugs(
+            (1..=30)
+                .map(|i| crate::fixtures::bug_task(i, "bug"))
+                .collect(),
+        );
+        app.current_bug = Some(crate::fixtures::bug(1, "bug"));
+        *app.gemini_response.lock().unwrap() = "A long description\n".repeat(50);
+        app.bug_desc_scroll_to_end = true;
+        app.bug_reply_text = "A long reply\n".repeat(50);
+        app.bug_reply_scroll_to_end = true;
+        app.chat_input = Some(TextInput::new("make it shorter"));
+
+        for screen in [Screen::BugList, Screen::BugEditing] {
+            app.current_screen = screen;
+            for (width, height) in [(80, 40), (0, 0), (1, 1), (3, 3), (10, 4), (20, 5), (80, 40)] {
+                let mut terminal = Terminal::new(TestBackend::new(width, height)).unwrap();
+                app.handle_resize();
+                terminal.draw(|f| draw_ui(f, &mut app)).unwrap();
+                app.popup = Some(Popup::ExportBugs(TextInput::new("bugs.csv")));
+                terminal.draw(|f| draw_ui(f, &mut app)).unwrap();
+                app.popup = None;
+            }
+        }
+        // The offsets were clamped to the content once grown back
+        assert!(app.bug_desc_scroll > 0);
+        assert!(app.bug_desc_scroll < 50);
+    }
+
+    #[test]
+    fn test_importance_color() {
+        let theme = Theme::default();
+        assert_eq!(importance_color(&theme, "Critical"), Color::Red);
+        assert_eq!(importance_color(&theme, "High"), Color::Yellow);
+        assert_eq!(importance_color(&theme, "Wishlist"), Color::DarkGray);
+        assert_eq!(importance_color(&theme, "Undecided"), Color::Reset);
+
+        let light = Theme::preset(ThemePreset::Light);
+        assert_eq!(importance_color(&light, "Medium"), Color::Blue);
+    }
+
+    #[test]
+    fn test_badge_color() {
+        let theme = Theme::default();
+        assert_eq!(
+            badge_color(&theme, &InformationType::Public, false, false),
+            Color::Green
+        );
+        assert_eq!(
+            badge_color(&theme, &InformationType::Public, false, true),
+            Color::Yellow
+        );
+        assert_eq!(
+            badge_color(&theme, &InformationType::PublicSecurity, false, false),
+            Color::Yellow
+        );
+        assert_eq!(
+            badge_color(&theme, &InformationType::Public, true, false),
+            Color::Red
+        );
+        assert_eq!(
+            badge_color(&theme, &InformationType::Proprietary, false, false),
+            Color::Red
+        );
+        assert_eq!(
+            badge_color(
+                &theme,
+                &InformationType::Other("Unknown".to_string()),
+                false,
+                false
+            ),
+            Color::Red
+        );
+    }
+
+    #[test]
+    fn test_status_bar_widths() {
+        assert_eq!(status_bar_widths(80, 10, 8), (12, true, 8));
+        assert_eq!(status_bar_widths(15, 10, 8), (2, false, 8));
+        assert_eq!(status_bar_widths(5, 10, 8), (2, false, 0));
+        assert_eq!(status_bar_widths(1, 10, 8), (1, false, 0));
+        assert_eq!(status_bar_widths(0, 10, 8), (0, false, 0));
+    }
+
+    #[test]
+    fn test_status_bar_narrow_render() {
+        let line = render_status_bar(10);
+        assert!(line.ends_with("12:34:56"));
+        assert!(!line.contains("Loading"));
+
+        let line = render_status_bar(5);
+        assert!(!line.contains("12"));
+
+        let line = render_status_bar(80);
+        assert!(line.contains("Loading..."));
+        assert!(line.contains("Tab selection"));
+        assert!(line.ends_with("12:34:56"));
+    }
 }